@@ -1,11 +1,17 @@
 use std::env;
 
 fn main() {
-    for (name, _value) in env::vars() {
+    for (name, value) in env::vars() {
         if name.starts_with("DEP_FFMPEG_") {
+            let feature = name["DEP_FFMPEG_".len()..name.len()].to_lowercase();
+
+            if value == "true" {
+                println!(r#"cargo:rustc-cfg=feature="{}""#, feature);
+            }
+
             println!(
-                r#"cargo:rustc-cfg=feature="{}""#,
-                name["DEP_FFMPEG_".len()..name.len()].to_lowercase()
+                r#"cargo:rustc-check-cfg=cfg(feature, values("{}"))"#,
+                feature
             );
         }
     }
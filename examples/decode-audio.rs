@@ -5,7 +5,11 @@ fn main() {
     let mut input = format::input(&"./examples/test.mp3").unwrap();
     let input_stream = input.streams().best(media::Type::Audio).unwrap();
     let audio_stream_index = input_stream.index();
-    let mut decoder = input_stream.codec().decoder().audio().unwrap();
+    let mut decoder = Context::from_parameters(input_stream.parameters())
+        .unwrap()
+        .decoder()
+        .audio()
+        .unwrap();
 
 
     let mut frame_index = 0;
@@ -16,7 +20,7 @@ fn main() {
                 while decoder.receive_frame(&mut decoded).is_ok() {
                     let packed = decoded.format().packed();
                     decoded.set_format(packed);
-                    println!("{{{:?} \n {:?} \n {:?} }}",decoded.metadata(),decoded,decoded.data(0));
+                    println!("{{{:?} \n {:?} \n {:?} }}",decoded.metadata(),decoded,decoded.plane_bytes(0));
                     frame_index += 1;
                 }
                 
@@ -42,7 +42,8 @@ fn main() {
                 println!("\tdiscard: {:?}", stream.discard());
                 println!("\trate: {}", stream.rate());
 
-                let codec = stream.codec();
+                let codec = ffmpeg::codec::Context::from_parameters(stream.parameters())
+                    .expect("unable to create codec context");
                 println!("\tmedium: {:?}", codec.medium());
                 println!("\tid: {:?}", codec.id());
 
@@ -53,8 +53,64 @@ fn filter(
     Ok(filter)
 }
 
+// The settings of an output audio stream, transcoded from an input stream.
+#[derive(Clone, Debug)]
+struct Mapping {
+    input: usize,
+    codec: Option<String>,
+    bit_rate: Option<usize>,
+    language: Option<String>,
+}
+
+fn map_audio(input: usize) -> Mapping {
+    Mapping {
+        input,
+        codec: None,
+        bit_rate: None,
+        language: None,
+    }
+}
+
+impl Mapping {
+    fn codec(mut self, name: &str) -> Self {
+        self.codec = Some(name.to_owned());
+        self
+    }
+
+    fn bit_rate(mut self, value: usize) -> Self {
+        self.bit_rate = Some(value);
+        self
+    }
+
+    fn language(mut self, value: &str) -> Self {
+        self.language = Some(value.to_owned());
+        self
+    }
+
+    // Parse `input[:codec[:bit_rate[:language]]]`, empty fields keeping the defaults.
+    fn parse(spec: &str) -> Option<Self> {
+        let mut fields = spec.split(':');
+        let mut mapping = map_audio(fields.next()?.parse().ok()?);
+
+        if let Some(codec) = fields.next().filter(|f| !f.is_empty()) {
+            mapping = mapping.codec(codec);
+        }
+
+        if let Some(bit_rate) = fields.next().filter(|f| !f.is_empty()) {
+            mapping = mapping.bit_rate(bit_rate.parse().ok()?);
+        }
+
+        if let Some(language) = fields.next().filter(|f| !f.is_empty()) {
+            mapping = mapping.language(language);
+        }
+
+        Some(mapping)
+    }
+}
+
 struct Transcoder {
     stream: usize,
+    output: usize,
     filter: filter::Graph,
     decoder: codec::decoder::Audio,
     encoder: codec::encoder::Audio,
@@ -67,24 +123,28 @@ fn transcoder<P: AsRef<Path>>(
     octx: &mut format::context::Output,
     path: &P,
     filter_spec: &str,
+    mapping: &Mapping,
 ) -> Result<Transcoder, ffmpeg::Error> {
     let input = ictx
-        .streams()
-        .best(media::Type::Audio)
-        .expect("could not find best audio stream");
-    let mut decoder = input.codec().decoder().audio()?;
-    let codec = ffmpeg::encoder::find(octx.format().codec(path, media::Type::Audio))
-        .expect("failed to find encoder")
+        .stream(mapping.input)
+        .filter(|stream| stream.parameters().medium() == media::Type::Audio)
+        .ok_or(ffmpeg::Error::StreamNotFound)?;
+    let mut decoder = codec::Context::from_parameters(input.parameters())?
+        .decoder()
         .audio()?;
+    let codec = match mapping.codec {
+        Some(ref name) => ffmpeg::encoder::find_by_name(name),
+        None => ffmpeg::encoder::find(octx.format().codec(path, media::Type::Audio)),
+    }
+    .ok_or(ffmpeg::Error::EncoderNotFound)?
+    .audio()?;
     let global = octx
         .format()
         .flags()
         .contains(ffmpeg::format::flag::Flags::GLOBAL_HEADER);
 
-    decoder.set_parameters(input.parameters())?;
-
     let mut output = octx.add_stream(codec)?;
-    let mut encoder = output.codec().encoder().audio()?;
+    let mut encoder = codec::Context::new().encoder().audio()?;
 
     let channel_layout = codec
         .channel_layouts()
@@ -105,7 +165,7 @@ fn transcoder<P: AsRef<Path>>(
             .next()
             .unwrap(),
     );
-    encoder.set_bit_rate(decoder.bit_rate());
+    encoder.set_bit_rate(mapping.bit_rate.unwrap_or_else(|| decoder.bit_rate()));
     encoder.set_max_bit_rate(decoder.max_bit_rate());
 
     encoder.set_time_base((1, decoder.rate() as i32));
@@ -114,6 +174,12 @@ fn transcoder<P: AsRef<Path>>(
     let encoder = encoder.open_as(codec)?;
     output.set_parameters(&encoder);
 
+    let mut metadata = input.metadata().to_owned();
+    if let Some(ref language) = mapping.language {
+        metadata.set("language", language);
+    }
+    output.set_metadata(metadata);
+
     let filter = filter(filter_spec, &decoder, &encoder)?;
 
     let in_time_base = decoder.time_base();
@@ -121,6 +187,7 @@ fn transcoder<P: AsRef<Path>>(
 
     Ok(Transcoder {
         stream: input.index(),
+        output: output.index(),
         filter,
         decoder,
         encoder,
@@ -141,7 +208,7 @@ impl Transcoder {
     fn receive_and_process_encoded_packets(&mut self, octx: &mut format::context::Output) {
         let mut encoded = ffmpeg::Packet::empty();
         while self.encoder.receive_packet(&mut encoded).is_ok() {
-            encoded.set_stream(0);
+            encoded.set_stream(self.output);
             encoded.rescale_ts(self.in_time_base, self.out_time_base);
             encoded.write_interleaved(octx).unwrap();
         }
@@ -192,6 +259,9 @@ impl Transcoder {
 // Transcode the `best` audio stream of the input file into a the output file while applying a
 // given filter. If no filter was specified the stream gets copied (`anull` filter).
 //
+// Further arguments map input audio streams to output streams instead, as
+// `input[:codec[:bit_rate[:language]]]`, each one transcoded with the filter.
+//
 // Example 1: Transcode *.mp3 file to *.wmv while speeding it up
 // transcode-audio in.mp3 out.wmv "atempo=1.2"
 //
@@ -200,6 +270,9 @@ impl Transcoder {
 //
 // Example 3: Seek to a specified position (in seconds)
 // transcode-audio in.mp3 out.mp3 anull 30
+//
+// Example 4: Transcode two audio tracks with their languages
+// transcode-audio in.mkv out.mkv anull 0 1:aac:128000:eng 2:aac:96000:fra
 fn main() {
     ffmpeg::init().unwrap();
 
@@ -210,9 +283,27 @@ fn main() {
 
     let mut ictx = format::input(&input).unwrap();
     let mut octx = format::output(&output).unwrap();
-    let mut transcoder = transcoder(&mut ictx, &mut octx, &output, &filter).unwrap();
 
-    if let Some(position) = seek {
+    let mut mappings = env::args()
+        .skip(5)
+        .map(|spec| Mapping::parse(&spec).expect("invalid mapping"))
+        .collect::<Vec<_>>();
+
+    if mappings.is_empty() {
+        let best = ictx
+            .streams()
+            .best(media::Type::Audio)
+            .expect("could not find best audio stream");
+
+        mappings.push(map_audio(best.index()));
+    }
+
+    let mut transcoders = mappings
+        .iter()
+        .map(|mapping| transcoder(&mut ictx, &mut octx, &output, &filter, mapping).unwrap())
+        .collect::<Vec<_>>();
+
+    if let Some(position) = seek.filter(|&position| position > 0) {
         // If the position was given in seconds, rescale it to ffmpegs base timebase.
         let position = position.rescale((1, 1), rescale::TIME_BASE);
         // If this seek was embedded in the transcoding loop, a call of `flush()`
@@ -223,22 +314,28 @@ fn main() {
     octx.set_metadata(ictx.metadata().to_owned());
     octx.write_header().unwrap();
 
-    for (stream, mut packet) in ictx.packets() {
-        if stream.index() == transcoder.stream {
+    for (stream, packet) in ictx.packets() {
+        for transcoder in transcoders
+            .iter_mut()
+            .filter(|transcoder| transcoder.stream == stream.index())
+        {
+            let mut packet = packet.clone();
             packet.rescale_ts(stream.time_base(), transcoder.in_time_base);
             transcoder.send_packet_to_decoder(&packet);
             transcoder.receive_and_process_decoded_frames(&mut octx);
         }
     }
 
-    transcoder.send_eof_to_decoder();
-    transcoder.receive_and_process_decoded_frames(&mut octx);
+    for transcoder in &mut transcoders {
+        transcoder.send_eof_to_decoder();
+        transcoder.receive_and_process_decoded_frames(&mut octx);
 
-    transcoder.flush_filter();
-    transcoder.get_and_process_filtered_frames(&mut octx);
+        transcoder.flush_filter();
+        transcoder.get_and_process_filtered_frames(&mut octx);
 
-    transcoder.send_eof_to_encoder();
-    transcoder.receive_and_process_encoded_packets(&mut octx);
+        transcoder.send_eof_to_encoder();
+        transcoder.receive_and_process_encoded_packets(&mut octx);
+    }
 
     octx.write_trailer().unwrap();
 }
@@ -30,7 +30,7 @@ const DEFAULT_X264_OPTS: &str = "preset=medium";
 struct Transcoder {
     ost_index: usize,
     decoder: decoder::Video,
-    encoder: encoder::video::Video,
+    encoder: encoder::video::Encoder,
     logging_enabled: bool,
     frame_count: usize,
     last_log_frame_count: usize,
@@ -47,9 +47,12 @@ impl Transcoder {
         enable_logging: bool,
     ) -> Result<Self, ffmpeg::Error> {
         let global_header = octx.format().flags().contains(format::Flags::GLOBAL_HEADER);
-        let decoder = ist.codec().decoder().video()?;
-        let mut ost = octx.add_stream(encoder::find(codec::Id::H264))?;
-        let mut encoder = ost.codec().encoder().video()?;
+        let decoder = codec::Context::from_parameters(ist.parameters())?
+            .decoder()
+            .video()?;
+        let codec = encoder::find(codec::Id::H264).ok_or(ffmpeg::Error::EncoderNotFound)?;
+        let mut ost = octx.add_stream(codec)?;
+        let mut encoder = codec::Context::new().encoder().video()?;
         encoder.set_height(decoder.height());
         encoder.set_width(decoder.width());
         encoder.set_aspect_ratio(decoder.aspect_ratio());
@@ -59,15 +62,14 @@ impl Transcoder {
         if global_header {
             encoder.set_flags(codec::Flags::GLOBAL_HEADER);
         }
-        encoder
-            .open_with(x264_opts)
+        let encoder = encoder
+            .open_as_with(codec, x264_opts)
             .expect("error opening libx264 encoder with supplied settings");
-        encoder = ost.codec().encoder().video()?;
-        ost.set_parameters(encoder);
+        ost.set_parameters(&encoder);
         Ok(Self {
             ost_index,
             decoder,
-            encoder: ost.codec().encoder().video()?,
+            encoder,
             logging_enabled: enable_logging,
             frame_count: 0,
             last_log_frame_count: 0,
@@ -184,7 +186,7 @@ fn main() {
     let mut transcoders = HashMap::new();
     let mut ost_index = 0;
     for (ist_index, ist) in ictx.streams().enumerate() {
-        let ist_medium = ist.codec().medium();
+        let ist_medium = ist.parameters().medium();
         if ist_medium != media::Type::Audio
             && ist_medium != media::Type::Video
             && ist_medium != media::Type::Subtitle
@@ -38,15 +38,46 @@ impl Audio {
 
     pub fn channel_layouts(&self) -> Option<ChannelLayoutIter> {
         unsafe {
-            if (*self.codec.as_ptr()).channel_layouts.is_null() {
+            let ptr = self.layouts();
+
+            if ptr.is_null() {
                 None
             } else {
-                Some(ChannelLayoutIter::new(
-                    (*self.codec.as_ptr()).channel_layouts,
-                ))
+                Some(ChannelLayoutIter::new(ptr))
             }
         }
     }
+
+    /// Iterate over the sample rates supported by the codec.
+    ///
+    /// Unlike [rates()], a codec that accepts any rate yields an empty
+    /// iterator whose [best_match()] returns the requested rate unchanged.
+    ///
+    /// [rates()]: Self::rates
+    /// [best_match()]: RateIter::best_match
+    pub fn supported_rates(&self) -> RateIter {
+        unsafe { RateIter::new((*self.codec.as_ptr()).supported_samplerates) }
+    }
+
+    /// Iterate over the sample formats supported by the codec.
+    pub fn supported_formats(&self) -> FormatIter {
+        unsafe { FormatIter::new((*self.codec.as_ptr()).sample_fmts) }
+    }
+
+    /// Iterate over the channel layouts supported by the codec.
+    pub fn supported_channel_layouts(&self) -> ChannelLayoutIter {
+        unsafe { ChannelLayoutIter::new(self.layouts()) }
+    }
+
+    #[cfg(not(feature = "ffmpeg_7_0"))]
+    unsafe fn layouts(&self) -> *const u64 {
+        (*self.codec.as_ptr()).channel_layouts
+    }
+
+    #[cfg(feature = "ffmpeg_7_0")]
+    unsafe fn layouts(&self) -> *const AVChannelLayout {
+        (*self.codec.as_ptr()).ch_layouts
+    }
 }
 
 impl Deref for Audio {
@@ -65,6 +96,17 @@ impl RateIter {
     pub fn new(ptr: *const i32) -> Self {
         RateIter { ptr }
     }
+
+    /// Get the supported rate closest to `rate`, preferring the higher one
+    /// on ties. If the codec does not restrict rates, `rate` is returned.
+    pub fn best_match(self, rate: i32) -> i32 {
+        if self.ptr.is_null() {
+            return rate;
+        }
+
+        self.min_by_key(|&cur| ((i64::from(cur) - i64::from(rate)).abs(), -i64::from(cur)))
+            .unwrap_or(rate)
+    }
 }
 
 impl Iterator for RateIter {
@@ -72,7 +114,7 @@ impl Iterator for RateIter {
 
     fn next(&mut self) -> Option<<Self as Iterator>::Item> {
         unsafe {
-            if *self.ptr == 0 {
+            if self.ptr.is_null() || *self.ptr == 0 {
                 return None;
             }
 
@@ -92,6 +134,27 @@ impl FormatIter {
     pub fn new(ptr: *const AVSampleFormat) -> Self {
         FormatIter { ptr }
     }
+
+    /// Get the supported format closest to `format`.
+    ///
+    /// An exact match wins, then the same sample type with the other
+    /// packing, then the first format the codec lists. If the codec does
+    /// not restrict formats, `format` is returned.
+    pub fn best_match(self, format: format::Sample) -> format::Sample {
+        if self.ptr.is_null() {
+            return format;
+        }
+
+        let formats = self.collect::<Vec<_>>();
+
+        if formats.contains(&format) {
+            format
+        } else if let Some(&alt) = formats.iter().find(|f| f.packed() == format.packed()) {
+            alt
+        } else {
+            formats.first().cloned().unwrap_or(format)
+        }
+    }
 }
 
 impl Iterator for FormatIter {
@@ -99,7 +162,7 @@ impl Iterator for FormatIter {
 
     fn next(&mut self) -> Option<<Self as Iterator>::Item> {
         unsafe {
-            if *self.ptr == AVSampleFormat::AV_SAMPLE_FMT_NONE {
+            if self.ptr.is_null() || *self.ptr == AVSampleFormat::AV_SAMPLE_FMT_NONE {
                 return None;
             }
 
@@ -112,14 +175,50 @@ impl Iterator for FormatIter {
 }
 
 pub struct ChannelLayoutIter {
+    #[cfg(not(feature = "ffmpeg_7_0"))]
     ptr: *const u64,
+    #[cfg(feature = "ffmpeg_7_0")]
+    ptr: *const AVChannelLayout,
 }
 
 impl ChannelLayoutIter {
+    #[cfg(not(feature = "ffmpeg_7_0"))]
     pub fn new(ptr: *const u64) -> Self {
         ChannelLayoutIter { ptr }
     }
 
+    /// Iterate over the native layouts of a list terminated by a zeroed
+    /// layout, skipping the others.
+    #[cfg(feature = "ffmpeg_7_0")]
+    pub fn new(ptr: *const AVChannelLayout) -> Self {
+        ChannelLayoutIter { ptr }
+    }
+
+    /// Get the supported layout closest to `layout`.
+    ///
+    /// An exact match wins, then a layout with the same number of channels,
+    /// then the supported layout with the most channels but no more than
+    /// `layout`. If the codec does not restrict layouts, `layout` is
+    /// returned, and `None` if all the supported layouts have more channels.
+    pub fn best_match(self, layout: ChannelLayout) -> Option<ChannelLayout> {
+        if self.ptr.is_null() {
+            return Some(layout);
+        }
+
+        let layouts = self.collect::<Vec<_>>();
+
+        if layouts.contains(&layout) {
+            Some(layout)
+        } else if let Some(&same) = layouts.iter().find(|l| l.channels() == layout.channels()) {
+            Some(same)
+        } else {
+            layouts
+                .into_iter()
+                .filter(|l| l.channels() <= layout.channels())
+                .max_by_key(|l| l.channels())
+        }
+    }
+
     pub fn best(self, max: i32) -> ChannelLayout {
         self.fold(ChannelLayout::MONO, |acc, cur| {
             if cur.channels() > acc.channels() && cur.channels() <= max {
@@ -134,9 +233,10 @@ impl ChannelLayoutIter {
 impl Iterator for ChannelLayoutIter {
     type Item = ChannelLayout;
 
+    #[cfg(not(feature = "ffmpeg_7_0"))]
     fn next(&mut self) -> Option<<Self as Iterator>::Item> {
         unsafe {
-            if *self.ptr == 0 {
+            if self.ptr.is_null() || *self.ptr == 0 {
                 return None;
             }
 
@@ -146,4 +246,20 @@ impl Iterator for ChannelLayoutIter {
             Some(layout)
         }
     }
+
+    #[cfg(feature = "ffmpeg_7_0")]
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        unsafe {
+            while !self.ptr.is_null() && (*self.ptr).nb_channels != 0 {
+                let layout = &*self.ptr;
+                self.ptr = self.ptr.offset(1);
+
+                if layout.order == AVChannelOrder::AV_CHANNEL_ORDER_NATIVE {
+                    return Some(ChannelLayout::from_bits_truncate(layout.u.mask));
+                }
+            }
+
+            None
+        }
+    }
 }
@@ -0,0 +1,161 @@
+//! Bitstream filters, rewriting the packets of a stream without decoding
+//! them, e.g. to repeat the parameter sets or insert access unit delimiters
+//! for encoders that cannot do it themselves.
+
+use std::ffi::CString;
+use std::ptr;
+
+use super::packet::{Mut, Packet};
+use super::{Id, Parameters};
+use ffi::*;
+use {Dictionary, Error, Rational};
+
+pub struct Filter {
+    ptr: *mut AVBSFContext,
+}
+
+unsafe impl Send for Filter {}
+
+impl Filter {
+    pub unsafe fn as_ptr(&self) -> *const AVBSFContext {
+        self.ptr as *const _
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVBSFContext {
+        self.ptr
+    }
+}
+
+impl Filter {
+    /// Create the filter `name` for a stream, e.g. `h264_mp4toannexb`.
+    pub fn new(
+        name: &str,
+        parameters: &Parameters,
+        time_base: Rational,
+        options: Dictionary,
+    ) -> Result<Self, Error> {
+        unsafe {
+            let name = CString::new(name).map_err(|_| Error::BsfNotFound)?;
+            let kind = av_bsf_get_by_name(name.as_ptr());
+
+            if kind.is_null() {
+                return Err(Error::BsfNotFound);
+            }
+
+            let mut ptr = ptr::null_mut();
+
+            match av_bsf_alloc(kind, &mut ptr) {
+                e if e < 0 => return Err(Error::from(e)),
+                _ => (),
+            }
+
+            let mut filter = Filter { ptr };
+            let mut opts = options.disown();
+
+            let res = avcodec_parameters_copy((*ptr).par_in, parameters.as_ptr());
+            (*ptr).time_base_in = time_base.into();
+
+            let res = if res < 0 {
+                res
+            } else if (*kind).priv_class.is_null() {
+                0
+            } else {
+                av_opt_set_dict2((*ptr).priv_data, &mut opts, 0)
+            };
+
+            let remaining = Dictionary::own(opts);
+
+            match res {
+                e if e < 0 => Err(Error::from(e)),
+                _ if remaining.iter().next().is_some() => Err(Error::OptionNotFound),
+                _ => match av_bsf_init(filter.as_mut_ptr()) {
+                    e if e < 0 => Err(Error::from(e)),
+                    _ => Ok(filter),
+                },
+            }
+        }
+    }
+
+    /// Create a filter inserting the global headers (e.g. SPS/PPS) before
+    /// every keyframe, for clients joining mid-stream.
+    pub fn repeat_headers(parameters: &Parameters, time_base: Rational) -> Result<Self, Error> {
+        let mut options = Dictionary::new();
+        options.set("freq", "keyframe");
+
+        Filter::new("dump_extra", parameters, time_base, options)
+    }
+
+    /// Create a filter inserting access unit delimiters in H.264 or HEVC
+    /// streams.
+    pub fn insert_aud(parameters: &Parameters, time_base: Rational) -> Result<Self, Error> {
+        let name = match parameters.id() {
+            Id::H264 => "h264_metadata",
+            Id::HEVC => "hevc_metadata",
+            _ => return Err(Error::BsfNotFound),
+        };
+
+        let mut options = Dictionary::new();
+        options.set("aud", "insert");
+
+        Filter::new(name, parameters, time_base, options)
+    }
+
+    /// Get the parameters of the filtered stream.
+    pub fn parameters(&self) -> Parameters {
+        unsafe {
+            let mut parameters = Parameters::new();
+            avcodec_parameters_copy(parameters.as_mut_ptr(), (*self.as_ptr()).par_out);
+
+            parameters
+        }
+    }
+
+    /// Get the time base of the filtered packets.
+    pub fn time_base(&self) -> Rational {
+        unsafe { Rational::from((*self.as_ptr()).time_base_out) }
+    }
+
+    /// Send a packet to the filter, taking its content.
+    pub fn send(&mut self, packet: &mut Packet) -> Result<(), Error> {
+        unsafe {
+            match av_bsf_send_packet(self.as_mut_ptr(), packet.as_mut_ptr()) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Signal the end of the stream, to drain the filter.
+    pub fn send_eof(&mut self) -> Result<(), Error> {
+        unsafe {
+            match av_bsf_send_packet(self.as_mut_ptr(), ptr::null_mut()) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    pub fn receive(&mut self, packet: &mut Packet) -> Result<(), Error> {
+        unsafe {
+            match av_bsf_receive_packet(self.as_mut_ptr(), packet.as_mut_ptr()) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Reset the state of the filter, e.g. after seeking.
+    pub fn flush(&mut self) {
+        unsafe {
+            av_bsf_flush(self.as_mut_ptr());
+        }
+    }
+}
+
+impl Drop for Filter {
+    fn drop(&mut self) {
+        unsafe {
+            av_bsf_free(&mut self.ptr);
+        }
+    }
+}
@@ -1,12 +1,17 @@
+use std::mem;
 use std::ptr;
 use std::rc::Rc;
+use std::slice;
 
 use super::decoder::Decoder;
 use super::encoder::Encoder;
 use super::{threading, Compliance, Debug, Flags, Id, Parameters};
 use ffi::*;
-use libc::c_int;
+use libc::{c_int, c_void, ENOMEM};
 use media;
+use option;
+#[cfg(feature = "ffmpeg_5_1")]
+use util::channel_layout::Layout;
 use {Codec, Error};
 
 /// The codec context.
@@ -29,6 +34,25 @@ impl Context {
     pub unsafe fn as_mut_ptr(&mut self) -> *mut AVCodecContext {
         self.ptr
     }
+
+    /// Take the ownership of a context, e.g. from `into_raw`.
+    pub unsafe fn from_raw(ptr: *mut AVCodecContext) -> Self {
+        Context::wrap(ptr, None)
+    }
+
+    /// Give up the ownership of the context, to be released with
+    /// `avcodec_free_context`. The context is returned back if it is not
+    /// owned, e.g. the context of a stream.
+    pub fn into_raw(self) -> Result<*mut AVCodecContext, Self> {
+        if self.owner.is_some() {
+            return Err(self);
+        }
+
+        let ptr = self.ptr;
+        mem::forget(self);
+
+        Ok(ptr)
+    }
 }
 
 impl Context {
@@ -41,6 +65,14 @@ impl Context {
             }
         }
     }
+    /// Setup a new codec context from the `parameters` of a stream, e.g. to
+    /// decode it with `Context::from_parameters(stream.parameters())?.decoder()`.
+    pub fn from_parameters<P: Into<Parameters>>(parameters: P) -> Result<Self, Error> {
+        let mut context = Context::new();
+        context.set_parameters(parameters)?;
+
+        Ok(context)
+    }
     /// Take the codec context into a decoder.
     pub fn decoder(self) -> Decoder {
         Decoder(self)
@@ -95,9 +127,13 @@ impl Context {
     /// mutithreading method.
     pub fn set_threading(&mut self, config: threading::Config) {
         unsafe {
+            #[cfg(not(feature = "ffmpeg_6_0"))]
+            {
+                (*self.as_mut_ptr()).thread_safe_callbacks = if config.safe { 1 } else { 0 };
+            }
+
             (*self.as_mut_ptr()).thread_type = config.kind.into();
             (*self.as_mut_ptr()).thread_count = config.count as c_int;
-            (*self.as_mut_ptr()).thread_safe_callbacks = if config.safe { 1 } else { 0 };
         }
     }
 
@@ -107,7 +143,10 @@ impl Context {
             threading::Config {
                 kind: threading::Type::from((*self.as_ptr()).active_thread_type),
                 count: (*self.as_ptr()).thread_count as usize,
+                #[cfg(not(feature = "ffmpeg_6_0"))]
                 safe: (*self.as_ptr()).thread_safe_callbacks != 0,
+                #[cfg(feature = "ffmpeg_6_0")]
+                safe: false,
             }
         }
     }
@@ -122,8 +161,77 @@ impl Context {
             }
         }
     }
+    /// Get the global headers of the codec, e.g. the SPS/PPS of H.264 or the
+    /// AudioSpecificConfig of AAC.
+    pub fn extradata(&self) -> &[u8] {
+        unsafe {
+            let ptr = self.as_ptr();
+
+            if (*ptr).extradata.is_null() || (*ptr).extradata_size <= 0 {
+                &[]
+            } else {
+                slice::from_raw_parts((*ptr).extradata, (*ptr).extradata_size as usize)
+            }
+        }
+    }
+    /// Set the global headers of the codec, replacing the previous ones.
+    pub fn set_extradata(&mut self, data: &[u8]) -> Result<(), Error> {
+        unsafe {
+            let ptr = self.as_mut_ptr();
+            let extradata =
+                av_mallocz(data.len() + AV_INPUT_BUFFER_PADDING_SIZE as usize) as *mut u8;
+
+            if extradata.is_null() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            ptr::copy_nonoverlapping(data.as_ptr(), extradata, data.len());
+
+            av_freep(&mut (*ptr).extradata as *mut *mut u8 as *mut c_void);
+            (*ptr).extradata = extradata;
+            (*ptr).extradata_size = data.len() as c_int;
+
+            Ok(())
+        }
+    }
+    /// Get the audio channel layout, of any order.
+    #[cfg(feature = "ffmpeg_5_1")]
+    pub fn ch_layout(&self) -> Layout {
+        unsafe {
+            let mut layout = Layout::unspecified(0);
+            av_channel_layout_copy(layout.as_mut_ptr(), &(*self.as_ptr()).ch_layout);
+
+            layout
+        }
+    }
+    /// Set the audio channel layout, of any order.
+    #[cfg(feature = "ffmpeg_5_1")]
+    pub fn set_ch_layout(&mut self, value: &Layout) -> Result<(), Error> {
+        unsafe {
+            match av_channel_layout_copy(&mut (*self.as_mut_ptr()).ch_layout, value.as_ptr()) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
 }
 
+unsafe impl option::Target for Context {
+    fn as_ptr(&self) -> *const c_void {
+        self.ptr as *const _
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut c_void {
+        self.ptr as *mut _
+    }
+}
+
+impl option::Settable for Context {}
+
+impl option::Gettable for Context {}
+
+impl option::Iterable for Context {}
+
 impl Default for Context {
     fn default() -> Self {
         Self::new()
@@ -148,9 +256,24 @@ impl Clone for Context {
         ctx
     }
 
+    #[cfg(not(feature = "ffmpeg_5_0"))]
     fn clone_from(&mut self, source: &Self) {
         unsafe {
             avcodec_copy_context(self.as_mut_ptr(), source.as_ptr());
         }
     }
+
+    // avcodec_copy_context is gone, copy what the parameters carry instead
+    #[cfg(feature = "ffmpeg_5_0")]
+    fn clone_from(&mut self, source: &Self) {
+        unsafe {
+            let mut parameters = Parameters::new();
+
+            avcodec_parameters_from_context(parameters.as_mut_ptr(), source.as_ptr());
+            avcodec_parameters_to_context(self.as_mut_ptr(), parameters.as_ptr());
+
+            (*self.as_mut_ptr()).time_base = (*source.as_ptr()).time_base;
+            (*self.as_mut_ptr()).framerate = (*source.as_ptr()).framerate;
+        }
+    }
 }
@@ -1,17 +1,24 @@
 use std::ops::{Deref, DerefMut};
 
 use ffi::*;
+#[cfg(not(feature = "ffmpeg_5_0"))]
 use libc::c_int;
 
 use super::Opened;
 use codec::Context;
+#[cfg(not(feature = "ffmpeg_5_0"))]
 use frame;
+#[cfg(not(feature = "ffmpeg_5_0"))]
+use packet;
+#[cfg(feature = "ffmpeg_7_0")]
+use util::channel_layout::Layout;
 use util::format;
-use {packet, AudioService, ChannelLayout, Error};
+use {AudioService, ChannelLayout, Error};
 /// The audio decoder.
 pub struct Audio(pub Opened);
 
 impl Audio {
+    #[cfg(not(feature = "ffmpeg_5_0"))]
     #[deprecated(
         since = "4.4.0",
         note = "Underlying API avcodec_decode_audio4 has been deprecated since FFmpeg 3.1; \
@@ -41,9 +48,15 @@ impl Audio {
         unsafe { (*self.as_ptr()).sample_rate as u32 }
     }
     /// Get the total amount of channels.
+    #[cfg(not(feature = "ffmpeg_7_0"))]
     pub fn channels(&self) -> u16 {
         unsafe { (*self.as_ptr()).channels as u16 }
     }
+    /// Get the total amount of channels.
+    #[cfg(feature = "ffmpeg_7_0")]
+    pub fn channels(&self) -> u16 {
+        unsafe { (*self.as_ptr()).ch_layout.nb_channels as u16 }
+    }
     /// Get the format of decoded audio.
     pub fn format(&self) -> format::Sample {
         unsafe { format::Sample::from((*self.as_ptr()).sample_fmt) }
@@ -56,30 +69,67 @@ impl Audio {
         }
     }
     /// Get the frame total amount. 
+    #[cfg(not(feature = "ffmpeg_6_0"))]
     pub fn frames(&self) -> usize {
         unsafe { (*self.as_ptr()).frame_number as usize }
     }
+    /// Get the frame total amount.
+    #[cfg(feature = "ffmpeg_6_0")]
+    pub fn frames(&self) -> usize {
+        unsafe { (*self.as_ptr()).frame_num as usize }
+    }
     /// Get the number of bytes per packet.
     /// May return 0 in some WAV based audio codecs.
     pub fn align(&self) -> usize {
         unsafe { (*self.as_ptr()).block_align as usize }
     }
     /// Get the audio channel layout.
+    #[cfg(not(feature = "ffmpeg_7_0"))]
     pub fn channel_layout(&self) -> ChannelLayout {
         unsafe { ChannelLayout::from_bits_truncate((*self.as_ptr()).channel_layout) }
     }
+    /// Get the audio channel layout, empty if it is not a native one.
+    #[cfg(feature = "ffmpeg_7_0")]
+    pub fn channel_layout(&self) -> ChannelLayout {
+        self.ch_layout().mask().unwrap_or_else(ChannelLayout::empty)
+    }
     /// Set the audio channel layout.
+    #[cfg(not(feature = "ffmpeg_7_0"))]
     pub fn set_channel_layout(&mut self, value: ChannelLayout) {
         unsafe {
             (*self.as_mut_ptr()).channel_layout = value.bits();
         }
     }
+    /// Set the audio channel layout, panicking if out of memory, see the
+    /// fallible `set_ch_layout`.
+    #[cfg(feature = "ffmpeg_7_0")]
+    pub fn set_channel_layout(&mut self, value: ChannelLayout) {
+        if self.set_ch_layout(&Layout::native(value)).is_err() {
+            panic!("out of memory");
+        }
+    }
     /// Set the audio channel layout that the decoder will try to use this if it can.
+    #[cfg(not(feature = "ffmpeg_7_0"))]
     pub fn request_channel_layout(&mut self, value: ChannelLayout) {
         unsafe {
             (*self.as_mut_ptr()).request_channel_layout = value.bits();
         }
     }
+    /// Set the audio channel layout that the decoder will try to use this if it can,
+    /// through the `downmix` option of the decoders supporting it.
+    #[cfg(feature = "ffmpeg_7_0")]
+    pub fn request_channel_layout(&mut self, value: ChannelLayout) {
+        unsafe {
+            let layout = Layout::native(value);
+
+            av_opt_set_chlayout(
+                self.as_mut_ptr() as *mut _,
+                b"downmix\0".as_ptr() as *const _,
+                layout.as_ptr(),
+                AV_OPT_SEARCH_CHILDREN,
+            );
+        }
+    }
     /// Get the audio service type which is set by codec.
     pub fn audio_service(&mut self) -> AudioService {
         unsafe { AudioService::from((*self.as_mut_ptr()).audio_service_type) }
@@ -93,6 +143,7 @@ impl Audio {
         unsafe { (*self.as_ptr()).frame_size as u32 }
     }
 
+    #[cfg(not(feature = "ffmpeg_5_0"))]
     pub fn frame_start(&self) -> Option<usize> {
         unsafe {
             match (*self.as_ptr()).timecode_frame_start {
@@ -1,9 +1,11 @@
 use std::ops::{Deref, DerefMut};
 use std::ptr;
 
-use super::{Audio, Check, Conceal, Opened, Subtitle, Video};
+use super::{Audio, Check, Conceal, Limits, Opened, Policy, Subtitle, Video};
 use codec::{traits, Context};
 use ffi::*;
+#[cfg(feature = "ffmpeg_4_4")]
+use libc::c_int;
 use {Dictionary, Discard, Error, Rational};
 
 pub struct Decoder(pub Context);
@@ -111,6 +113,58 @@ impl Decoder {
             (*self.as_mut_ptr()).skip_frame = value.into();
         }
     }
+    /// Set whether the decoder applies the film grain (the default), or
+    /// exports its parameters as frame side data, see `frame::film_grain`.
+    #[cfg(feature = "ffmpeg_4_4")]
+    pub fn apply_film_grain(&mut self, value: bool) {
+        unsafe {
+            if value {
+                (*self.as_mut_ptr()).export_side_data &=
+                    !(AV_CODEC_EXPORT_DATA_FILM_GRAIN as c_int);
+            } else {
+                (*self.as_mut_ptr()).export_side_data |= AV_CODEC_EXPORT_DATA_FILM_GRAIN as c_int;
+            }
+        }
+    }
+    /// Apply resolution and frame rate limits, call it after setting the
+    /// parameters and before opening the decoder.
+    ///
+    /// The frame rate is only checked when known to the context, e.g. from
+    /// the parameters with FFmpeg 6.1 or later, see `Limits::accepts_rate`
+    /// to check the rate of the stream otherwise.
+    pub fn limit(&mut self, limits: &Limits, policy: Policy) -> Result<(), Error> {
+        unsafe {
+            let ptr = self.as_mut_ptr();
+            let width = (*ptr).width.max(0) as u32;
+            let height = (*ptr).height.max(0) as u32;
+            let rate = Rational::from((*ptr).framerate);
+
+            if let Some(pixels) = limits.pixels {
+                (*ptr).max_pixels = pixels.min(i64::max_value() as u64) as i64;
+            }
+
+            match policy {
+                Policy::Reject => {
+                    if !limits.accepts(width, height) || !limits.accepts_rate(rate) {
+                        return Err(Error::InvalidData);
+                    }
+                }
+
+                Policy::Downscale => {
+                    if !limits.accepts(width, height) {
+                        let max = super::find(self.id()).map_or(0, |codec| codec.max_lowres());
+                        (*ptr).lowres = limits.lowres(width, height, max);
+                    }
+
+                    if !limits.accepts_rate(rate) {
+                        (*ptr).skip_frame = Discard::NonKey.into();
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
     /// Get the time stamp unit(in seconds) of frames.
     pub fn time_base(&self) -> Rational {
         unsafe { Rational::from((*self.as_ptr()).time_base) }
@@ -0,0 +1,131 @@
+//! Guardrails against streams larger than an application is willing to
+//! decode, e.g. decompression bombs sent to a thumbnailing service.
+//!
+//! The limits are applied to a `Decoder` before opening it with
+//! `Decoder::limit`. With `Policy::Downscale` the decoder is set to a lower
+//! resolution when the codec supports it, and `decoder::Video::fit` returns
+//! a scaler for whatever is left.
+
+use Rational;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Policy {
+    /// Fail when the stream is above the limits.
+    Reject,
+    /// Decode at a lower resolution (lowres) and scale down what is left,
+    /// only decoding keyframes when the frame rate is above the limit.
+    Downscale,
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Default)]
+pub struct Limits {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// The number of pixels of decoded frames, after the lowres reduction.
+    ///
+    /// Unlike the other limits it is enforced by the decoder on every frame,
+    /// so it always rejects, whatever the policy, including streams whose
+    /// resolution is unknown or changes midway.
+    pub pixels: Option<u64>,
+    pub rate: Option<Rational>,
+}
+
+impl Limits {
+    pub fn new() -> Self {
+        Limits::default()
+    }
+
+    pub fn width(mut self, value: u32) -> Self {
+        self.width = Some(value);
+        self
+    }
+
+    pub fn height(mut self, value: u32) -> Self {
+        self.height = Some(value);
+        self
+    }
+
+    pub fn pixels(mut self, value: u64) -> Self {
+        self.pixels = Some(value);
+        self
+    }
+
+    pub fn rate(mut self, value: Rational) -> Self {
+        self.rate = Some(value);
+        self
+    }
+
+    /// Check whether a resolution fits within the width and height limits.
+    pub fn accepts(&self, width: u32, height: u32) -> bool {
+        self.width.map_or(true, |max| width <= max) && self.height.map_or(true, |max| height <= max)
+    }
+
+    /// Check whether a frame rate is within the limit, unknown rates are
+    /// accepted.
+    pub fn accepts_rate(&self, rate: Rational) -> bool {
+        match self.rate {
+            Some(max) if rate.numerator() > 0 && rate.denominator() > 0 => {
+                f64::from(rate) <= f64::from(max)
+            }
+            _ => true,
+        }
+    }
+
+    /// Get the largest resolution within the width and height limits with
+    /// the aspect ratio of `width`x`height`.
+    pub fn fit(&self, width: u32, height: u32) -> (u32, u32) {
+        if self.accepts(width, height) || width == 0 || height == 0 {
+            return (width, height);
+        }
+
+        let scale = f64::min(
+            self.width
+                .map_or(1.0, |max| f64::from(max) / f64::from(width)),
+            self.height
+                .map_or(1.0, |max| f64::from(max) / f64::from(height)),
+        );
+
+        (
+            ((f64::from(width) * scale) as u32).max(1),
+            ((f64::from(height) * scale) as u32).max(1),
+        )
+    }
+
+    /// Get the lowest lowres reduction, up to `max`, bringing the resolution
+    /// within the limits, or `max` if none does.
+    pub fn lowres(&self, width: u32, height: u32, max: i32) -> i32 {
+        let max = max.max(0);
+
+        (0..max)
+            .find(|&factor| self.accepts(reduce(width, factor), reduce(height, factor)))
+            .unwrap_or(max)
+    }
+}
+
+fn reduce(value: u32, factor: i32) -> u32 {
+    ((u64::from(value) + (1 << factor) - 1) >> factor) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit() {
+        let limits = Limits::new().width(1920).height(1080);
+
+        assert_eq!(limits.fit(1280, 720), (1280, 720));
+        assert_eq!(limits.fit(7680, 4320), (1920, 1080));
+        assert_eq!(limits.fit(4000, 1000), (1920, 480));
+    }
+
+    #[test]
+    fn test_lowres() {
+        let limits = Limits::new().width(1920).height(1080);
+
+        assert_eq!(limits.lowres(1920, 1080, 3), 0);
+        assert_eq!(limits.lowres(7680, 4320, 3), 2);
+        assert_eq!(limits.lowres(7680, 4320, 1), 1);
+        assert_eq!(limits.lowres(7680, 4320, 0), 0);
+    }
+}
@@ -22,7 +22,11 @@
 //!     // Get the audio stream index.
 //!     let audio_stream_index = input_stream.index();
 //!     // Find the decoder of input context.
-//!     let mut decoder = input_stream.codec().decoder().audio().unwrap();
+//!     let mut decoder = Context::from_parameters(input_stream.parameters())
+//!         .unwrap()
+//!         .decoder()
+//!         .audio()
+//!         .unwrap();
 //!
 //!     // the frame index.
 //!     let mut frame_index = 0;
@@ -39,7 +43,7 @@
 //!                     let packed = decoded.format().packed();
 //!                     // now set the new format of sample.
 //!                     decoded.set_format(packed);
-//!                     println!("{{{:?} \n {:?} \n {:?} }}",decoded.metadata(),decoded,decoded.data(audio_stream_index));
+//!                     println!("{{{:?} \n {:?} \n {:?} }}",decoded.metadata(),decoded,decoded.plane_bytes(0));
 //!                     // move to next frame, like iter.next().
 //!                     frame_index += 1;
 //!                 }
@@ -78,6 +82,9 @@ pub use self::conceal::Conceal;
 /// Flags of verify context
 pub mod check;
 pub use self::check::Check;
+/// Resolution and frame rate guardrails
+pub mod limits;
+pub use self::limits::{Limits, Policy};
 /// The context of decoder
 pub mod opened;
 pub use self::opened::Opened;
@@ -103,7 +110,7 @@ pub fn find(id: Id) -> Option<Codec> {
         if ptr.is_null() {
             None
         } else {
-            Some(Codec::wrap(ptr))
+            Some(Codec::wrap(ptr as *mut _))
         }
     }
 }
@@ -117,7 +124,7 @@ pub fn find_by_name(name: &str) -> Option<Codec> {
         if ptr.is_null() {
             None
         } else {
-            Some(Codec::wrap(ptr))
+            Some(Codec::wrap(ptr as *mut _))
         }
     }
 }
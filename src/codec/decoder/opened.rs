@@ -2,7 +2,7 @@ use std::ops::{Deref, DerefMut};
 use std::ptr;
 
 use super::{Audio, Decoder, Subtitle, Video};
-use codec::{Context, Profile};
+use codec::{Context, Profile, Properties};
 use ffi::*;
 use {media, packet, Error, Frame, Rational};
 /// The context of decoder.
@@ -92,6 +92,14 @@ impl Opened {
         }
     }
 
+    /// Get the properties of the decoded stream, which are only known once
+    /// some frames have been decoded.
+    pub fn properties(&self) -> Properties {
+        unsafe { Properties::from_bits_truncate((*self.as_ptr()).properties) }
+    }
+
+    /// Reset the state of the decoder, dropping the frames it buffered, to
+    /// decode from another position after seeking, without reopening it.
     pub fn flush(&mut self) {
         unsafe {
             avcodec_flush_buffers(self.as_mut_ptr());
@@ -6,14 +6,18 @@ use libc::c_int;
 use super::{slice, Opened};
 use codec::Context;
 use color;
+#[cfg(not(feature = "ffmpeg_5_0"))]
 use frame;
+#[cfg(not(feature = "ffmpeg_5_0"))]
+use packet;
 use util::chroma;
 use util::format;
-use {packet, Error, FieldOrder, Rational};
+use {Error, FieldOrder, Rational};
 
 pub struct Video(pub Opened);
 
 impl Video {
+    #[cfg(not(feature = "ffmpeg_5_0"))]
     #[deprecated(
         since = "4.4.0",
         note = "Underlying API avcodec_decode_video2 has been deprecated since FFmpeg 3.1; \
@@ -2,12 +2,17 @@ use std::ops::{Deref, DerefMut};
 use std::ptr;
 
 use ffi::*;
+#[cfg(not(feature = "ffmpeg_5_0"))]
 use libc::c_int;
 
 use super::Encoder as Super;
-use codec::{traits, Context};
+use codec::{self, traits, Context};
+#[cfg(feature = "ffmpeg_7_0")]
+use util::channel_layout::Layout;
 use util::format;
-use {frame, packet, ChannelLayout, Dictionary, Error};
+#[cfg(not(feature = "ffmpeg_5_0"))]
+use {frame, packet};
+use {ChannelLayout, Dictionary, Error};
 /// The context of audio encoder.
 pub struct Audio(pub Super);
 
@@ -92,25 +97,96 @@ impl Audio {
         unsafe { format::Sample::from((*self.as_ptr()).sample_fmt) }
     }
     /// Set the encode channel layout.
+    #[cfg(not(feature = "ffmpeg_7_0"))]
     pub fn set_channel_layout(&mut self, value: ChannelLayout) {
         unsafe {
             (*self.as_mut_ptr()).channel_layout = value.bits();
         }
     }
+    /// Set the encode channel layout, panicking if out of memory, see the
+    /// fallible `set_ch_layout`.
+    #[cfg(feature = "ffmpeg_7_0")]
+    pub fn set_channel_layout(&mut self, value: ChannelLayout) {
+        if self.set_ch_layout(&Layout::native(value)).is_err() {
+            panic!("out of memory");
+        }
+    }
     /// Get the encode channel layout from encoder.
+    #[cfg(not(feature = "ffmpeg_7_0"))]
     pub fn channel_layout(&self) -> ChannelLayout {
         unsafe { ChannelLayout::from_bits_truncate((*self.as_ptr()).channel_layout) }
     }
+    /// Get the encode channel layout from encoder, empty if it is not a native one.
+    #[cfg(feature = "ffmpeg_7_0")]
+    pub fn channel_layout(&self) -> ChannelLayout {
+        self.ch_layout().mask().unwrap_or_else(ChannelLayout::empty)
+    }
     /// Set the encode channel number.
+    #[cfg(not(feature = "ffmpeg_7_0"))]
     pub fn set_channels(&mut self, value: i32) {
         unsafe {
             (*self.as_mut_ptr()).channels = value;
         }
     }
+    /// Set the encode channel number, keeping the layout if it already has
+    /// that many channels, panicking if out of memory.
+    #[cfg(feature = "ffmpeg_7_0")]
+    pub fn set_channels(&mut self, value: i32) {
+        if i32::from(self.channels()) != value {
+            let layout = Layout::unspecified(value.max(0) as u32);
+
+            if self.set_ch_layout(&layout).is_err() {
+                panic!("out of memory");
+            }
+        }
+    }
     /// Get the total amount of channel from encoder.
+    #[cfg(not(feature = "ffmpeg_7_0"))]
     pub fn channels(&self) -> u16 {
         unsafe { (*self.as_ptr()).channels as u16 }
     }
+    /// Get the total amount of channel from encoder.
+    #[cfg(feature = "ffmpeg_7_0")]
+    pub fn channels(&self) -> u16 {
+        unsafe { (*self.as_ptr()).ch_layout.nb_channels as u16 }
+    }
+    /// Set the sample format to the supported one closest to `preferred`, and return it.
+    pub fn pick_best_format(&mut self, preferred: format::Sample) -> format::Sample {
+        let format = match self.audio_codec() {
+            Some(codec) => codec.supported_formats().best_match(preferred),
+            None => preferred,
+        };
+
+        self.set_format(format);
+        format
+    }
+    /// Set the sample rate to the supported one closest to `preferred`, and return it.
+    pub fn pick_best_rate(&mut self, preferred: i32) -> i32 {
+        let rate = match self.audio_codec() {
+            Some(codec) => codec.supported_rates().best_match(preferred),
+            None => preferred,
+        };
+
+        self.set_rate(rate);
+        rate
+    }
+    /// Set the channel layout, and the matching channel count, to the supported
+    /// layout closest to `preferred`, and return it. Nothing is set if the
+    /// codec only supports layouts with more channels.
+    pub fn pick_best_channel_layout(&mut self, preferred: ChannelLayout) -> Option<ChannelLayout> {
+        let layout = match self.audio_codec() {
+            Some(codec) => codec.supported_channel_layouts().best_match(preferred)?,
+            None => preferred,
+        };
+
+        self.set_channel_layout(layout);
+        self.set_channels(layout.channels());
+        Some(layout)
+    }
+
+    fn audio_codec(&self) -> Option<codec::Audio> {
+        self.codec().and_then(|c| c.audio().ok())
+    }
 }
 
 impl Deref for Audio {
@@ -142,6 +218,7 @@ impl AsMut<Context> for Audio {
 pub struct Encoder(pub Audio);
 
 impl Encoder {
+    #[cfg(not(feature = "ffmpeg_5_0"))]
     #[deprecated(
         since = "4.4.0",
         note = "Underlying API avcodec_encode_audio2 has been deprecated since FFmpeg 3.1; \
@@ -171,6 +248,7 @@ impl Encoder {
         }
     }
 
+    #[cfg(not(feature = "ffmpeg_5_0"))]
     #[deprecated(
         since = "4.4.0",
         note = "Underlying API avcodec_encode_audio2 has been deprecated since FFmpeg 3.1; \
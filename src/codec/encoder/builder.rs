@@ -0,0 +1,184 @@
+//! Opening of encoders with options checked beforehand against the options
+//! of the codec, instead of `avcodec_open2` leaving misspelled options unused
+//! or failing without naming the culprit.
+
+use std::ffi::CString;
+use std::fmt;
+use std::ptr;
+
+use super::{audio, subtitle, video, Audio, Subtitle, Video};
+use ffi::*;
+use libc::{c_void, EINVAL, ENOMEM, ERANGE};
+use option;
+use {Codec, Dictionary, Error};
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Reason {
+    /// Neither the codec nor the generic codec context have the option.
+    NotFound,
+    /// The value cannot be parsed as the type of the option.
+    InvalidValue(option::Type),
+    /// The value is out of the range of the option.
+    OutOfRange { min: f64, max: f64 },
+}
+
+/// An option rejected by `Builder::invalid` or `Builder::validate`.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Invalid {
+    pub name: String,
+    pub value: String,
+    pub reason: Reason,
+}
+
+impl fmt::Display for Invalid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.reason {
+            Reason::NotFound => write!(f, "option `{}` not found", self.name),
+            Reason::InvalidValue(kind) => write!(
+                f,
+                "invalid value `{}` for option `{}` of type {:?}",
+                self.value, self.name, kind
+            ),
+            Reason::OutOfRange { min, max } => write!(
+                f,
+                "value `{}` for option `{}` out of range [{}, {}]",
+                self.value, self.name, min, max
+            ),
+        }
+    }
+}
+
+impl From<Invalid> for Error {
+    fn from(value: Invalid) -> Error {
+        match value.reason {
+            Reason::NotFound => Error::OptionNotFound,
+            Reason::InvalidValue(..) => Error::Other { errno: EINVAL },
+            Reason::OutOfRange { .. } => Error::Other { errno: ERANGE },
+        }
+    }
+}
+
+pub struct Builder {
+    codec: Codec,
+    options: Vec<(String, String)>,
+}
+
+impl Builder {
+    pub fn new(codec: Codec) -> Self {
+        Builder {
+            codec,
+            options: Vec::new(),
+        }
+    }
+
+    pub fn codec(&self) -> Codec {
+        self.codec
+    }
+
+    /// Queue an option, either generic (e.g. `g`) or private to the codec
+    /// (e.g. `crf` for libx264).
+    pub fn option<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.options.push((key.into(), value.into()));
+        self
+    }
+
+    /// Queue a set of options, e.g. from `options::X264`.
+    pub fn options_from<I>(mut self, options: I) -> Self
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        self.options.extend(options);
+        self
+    }
+
+    /// Check the queued options against the options of the codec, returning
+    /// all the invalid ones.
+    pub fn invalid(&self) -> Result<Vec<Invalid>, Error> {
+        unsafe {
+            let mut ctx = avcodec_alloc_context3(self.codec.as_ptr());
+
+            if ctx.is_null() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            let invalid = self
+                .options
+                .iter()
+                .filter_map(|&(ref name, ref value)| {
+                    check(ctx as *mut c_void, name, value).map(|reason| Invalid {
+                        name: name.clone(),
+                        value: value.clone(),
+                        reason,
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            avcodec_free_context(&mut ctx);
+
+            Ok(invalid)
+        }
+    }
+
+    /// Check the queued options against the options of the codec, failing
+    /// with the first invalid one if any.
+    pub fn validate(&self) -> Result<(), Error> {
+        match self.invalid()?.into_iter().next() {
+            Some(invalid) => Err(Error::from(invalid)),
+            None => Ok(()),
+        }
+    }
+
+    /// Get the queued options.
+    pub fn options(&self) -> Dictionary {
+        let mut options = Dictionary::new();
+
+        for &(ref key, ref value) in &self.options {
+            options.set(key, value);
+        }
+
+        options
+    }
+
+    /// Open a configured video encoder, failing with the first invalid option
+    /// if any.
+    pub fn open_video(&self, encoder: Video) -> Result<video::Encoder, Error> {
+        self.validate()?;
+        encoder.open_as_with(self.codec, self.options())
+    }
+
+    /// Open a configured audio encoder, failing with the first invalid option
+    /// if any.
+    pub fn open_audio(&self, encoder: Audio) -> Result<audio::Encoder, Error> {
+        self.validate()?;
+        encoder.open_as_with(self.codec, self.options())
+    }
+
+    /// Open a configured subtitle encoder, failing with the first invalid
+    /// option if any.
+    pub fn open_subtitle(&self, encoder: Subtitle) -> Result<subtitle::Encoder, Error> {
+        self.validate()?;
+        encoder.open_as_with(self.codec, self.options())
+    }
+}
+
+unsafe fn check(ctx: *mut c_void, name: &str, value: &str) -> Option<Reason> {
+    let (name, value) = match (CString::new(name), CString::new(value)) {
+        (Ok(name), Ok(value)) => (name, value),
+        _ => return Some(Reason::NotFound),
+    };
+
+    let opt = av_opt_find(ctx, name.as_ptr(), ptr::null(), 0, AV_OPT_SEARCH_CHILDREN);
+
+    if opt.is_null() {
+        return Some(Reason::NotFound);
+    }
+
+    match av_opt_set(ctx, name.as_ptr(), value.as_ptr(), AV_OPT_SEARCH_CHILDREN) {
+        0 => None,
+        e if e == AVERROR(ERANGE) => Some(Reason::OutOfRange {
+            min: (*opt).min,
+            max: (*opt).max,
+        }),
+        _ => Some(Reason::InvalidValue(option::Type::from((*opt).type_))),
+    }
+}
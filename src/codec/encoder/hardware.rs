@@ -0,0 +1,281 @@
+//! Typed private options of the hardware encoders of NVIDIA (NVENC), Intel
+//! (QSV) and AMD (AMF), along the lines of `encoder::options`.
+//!
+//! The encoders are only present in builds configured for them, `encoder`
+//! finds them for a codec or fails with `Error::EncoderNotFound`.
+
+use codec::Id;
+use {Codec, Error};
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum RateControl {
+    /// Constant quantizer, set with `qp`.
+    ConstantQp,
+    /// Variable bit rate, capped by the maximum rate of the context.
+    Vbr,
+    /// Constant bit rate.
+    Cbr,
+}
+
+fn encoder(id: Id, suffix: &str) -> Result<Codec, Error> {
+    let name = match id {
+        Id::H264 => "h264",
+        Id::HEVC => "hevc",
+        Id::AV1 => "av1",
+        Id::VP9 => "vp9",
+        Id::MPEG2VIDEO => "mpeg2",
+        _ => return Err(Error::EncoderNotFound),
+    };
+
+    super::find_by_name(&format!("{}_{}", name, suffix)).ok_or(Error::EncoderNotFound)
+}
+
+/// The presets of NVENC, from the fastest (`P1`) to the best quality (`P7`).
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum NvencPreset {
+    P1,
+    P2,
+    P3,
+    P4,
+    P5,
+    P6,
+    P7,
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum NvencTune {
+    HighQuality,
+    LowLatency,
+    UltraLowLatency,
+    Lossless,
+}
+
+/// Private options of the `*_nvenc` encoders.
+#[derive(Clone, Debug, Default)]
+pub struct Nvenc {
+    options: Vec<(String, String)>,
+}
+
+impl Nvenc {
+    /// Find the NVENC encoder of `id` (H.264, HEVC or AV1).
+    pub fn encoder(id: Id) -> Result<Codec, Error> {
+        encoder(id, "nvenc")
+    }
+
+    pub fn preset(self, value: NvencPreset) -> Self {
+        let name = match value {
+            NvencPreset::P1 => "p1",
+            NvencPreset::P2 => "p2",
+            NvencPreset::P3 => "p3",
+            NvencPreset::P4 => "p4",
+            NvencPreset::P5 => "p5",
+            NvencPreset::P6 => "p6",
+            NvencPreset::P7 => "p7",
+        };
+
+        self.set("preset", name)
+    }
+
+    pub fn tune(self, value: NvencTune) -> Self {
+        let name = match value {
+            NvencTune::HighQuality => "hq",
+            NvencTune::LowLatency => "ll",
+            NvencTune::UltraLowLatency => "ull",
+            NvencTune::Lossless => "lossless",
+        };
+
+        self.set("tune", name)
+    }
+
+    pub fn rc(self, value: RateControl) -> Self {
+        let name = match value {
+            RateControl::ConstantQp => "constqp",
+            RateControl::Vbr => "vbr",
+            RateControl::Cbr => "cbr",
+        };
+
+        self.set("rc", name)
+    }
+
+    pub fn qp(self, value: u32) -> Self {
+        self.set("qp", value.to_string())
+    }
+
+    /// Set the target quality of `RateControl::Vbr`, from 0 (automatic) to
+    /// 51.
+    pub fn cq(self, value: u32) -> Self {
+        self.set("cq", value.to_string())
+    }
+
+    /// Set the number of frames to look ahead, 0 to disable.
+    pub fn lookahead(self, value: u32) -> Self {
+        self.set("rc-lookahead", value.to_string())
+    }
+
+    /// Set the number of concurrent surfaces, up to 64.
+    pub fn surfaces(self, value: u32) -> Self {
+        self.set("surfaces", value.to_string())
+    }
+
+    /// Select the GPU, by index.
+    pub fn gpu(self, value: u32) -> Self {
+        self.set("gpu", value.to_string())
+    }
+
+    /// Insert access unit delimiters.
+    pub fn aud(self, value: bool) -> Self {
+        self.set("aud", if value { "1" } else { "0" })
+    }
+}
+
+options!(Nvenc);
+
+/// The presets of QSV.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum QsvPreset {
+    Veryfast,
+    Faster,
+    Fast,
+    Medium,
+    Slow,
+    Slower,
+    Veryslow,
+}
+
+/// Private options of the `*_qsv` encoders.
+///
+/// QSV has no rate control option, the mode follows the options set:
+/// `global_quality` for the intelligent constant quality mode, the bit
+/// rates of the context otherwise.
+#[derive(Clone, Debug, Default)]
+pub struct Qsv {
+    options: Vec<(String, String)>,
+}
+
+impl Qsv {
+    /// Find the QSV encoder of `id` (H.264, HEVC, AV1, VP9 or MPEG-2).
+    pub fn encoder(id: Id) -> Result<Codec, Error> {
+        encoder(id, "qsv")
+    }
+
+    pub fn preset(self, value: QsvPreset) -> Self {
+        let name = match value {
+            QsvPreset::Veryfast => "veryfast",
+            QsvPreset::Faster => "faster",
+            QsvPreset::Fast => "fast",
+            QsvPreset::Medium => "medium",
+            QsvPreset::Slow => "slow",
+            QsvPreset::Slower => "slower",
+            QsvPreset::Veryslow => "veryslow",
+        };
+
+        self.set("preset", name)
+    }
+
+    /// Set the quality of the intelligent constant quality mode, from 1 to
+    /// 51.
+    pub fn global_quality(self, value: u32) -> Self {
+        self.set("global_quality", value.to_string())
+    }
+
+    /// Set the number of frames to look ahead, H.264 only, 0 to disable.
+    pub fn lookahead(self, value: u32) -> Self {
+        if value == 0 {
+            self.set("look_ahead", "0")
+        } else {
+            self.set("look_ahead", "1")
+                .set("look_ahead_depth", value.to_string())
+        }
+    }
+
+    /// Set the number of frames processed in parallel, which sets the
+    /// number of surfaces.
+    pub fn surfaces(self, value: u32) -> Self {
+        self.set("async_depth", value.to_string())
+    }
+
+    /// Insert access unit delimiters.
+    pub fn aud(self, value: bool) -> Self {
+        self.set("aud", if value { "1" } else { "0" })
+    }
+}
+
+options!(Qsv);
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum AmfUsage {
+    Transcoding,
+    LowLatency,
+    UltraLowLatency,
+    Webcam,
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum AmfQuality {
+    Speed,
+    Balanced,
+    Quality,
+}
+
+/// Private options of the `*_amf` encoders.
+#[derive(Clone, Debug, Default)]
+pub struct Amf {
+    options: Vec<(String, String)>,
+}
+
+impl Amf {
+    /// Find the AMF encoder of `id` (H.264, HEVC or AV1).
+    pub fn encoder(id: Id) -> Result<Codec, Error> {
+        encoder(id, "amf")
+    }
+
+    pub fn usage(self, value: AmfUsage) -> Self {
+        let name = match value {
+            AmfUsage::Transcoding => "transcoding",
+            AmfUsage::LowLatency => "lowlatency",
+            AmfUsage::UltraLowLatency => "ultralowlatency",
+            AmfUsage::Webcam => "webcam",
+        };
+
+        self.set("usage", name)
+    }
+
+    /// Set the quality preset.
+    pub fn quality(self, value: AmfQuality) -> Self {
+        let name = match value {
+            AmfQuality::Speed => "speed",
+            AmfQuality::Balanced => "balanced",
+            AmfQuality::Quality => "quality",
+        };
+
+        self.set("quality", name)
+    }
+
+    pub fn rc(self, value: RateControl) -> Self {
+        let name = match value {
+            RateControl::ConstantQp => "cqp",
+            RateControl::Vbr => "vbr_peak",
+            RateControl::Cbr => "cbr",
+        };
+
+        self.set("rc", name)
+    }
+
+    /// Set the quantizers of `RateControl::ConstantQp`.
+    pub fn qp(self, value: u32) -> Self {
+        self.set("qp_i", value.to_string())
+            .set("qp_p", value.to_string())
+    }
+
+    /// Enable the pre-analysis, the closest AMF has to a lookahead.
+    pub fn lookahead(self, value: bool) -> Self {
+        self.set("preanalysis", if value { "1" } else { "0" })
+    }
+
+    /// Insert access unit delimiters.
+    pub fn aud(self, value: bool) -> Self {
+        self.set("aud", if value { "1" } else { "0" })
+    }
+}
+
+options!(Amf);
@@ -22,6 +22,14 @@ pub use self::comparison::Comparison;
 pub mod decision;
 pub use self::decision::Decision;
 
+pub mod builder;
+pub use self::builder::Builder;
+
+#[cfg(feature = "encoder-options")]
+pub mod options;
+
+pub mod hardware;
+
 use std::ffi::CString;
 
 use codec::Context;
@@ -40,7 +48,7 @@ pub fn find(id: Id) -> Option<Codec> {
         if ptr.is_null() {
             None
         } else {
-            Some(Codec::wrap(ptr))
+            Some(Codec::wrap(ptr as *mut _))
         }
     }
 }
@@ -53,7 +61,7 @@ pub fn find_by_name(name: &str) -> Option<Codec> {
         if ptr.is_null() {
             None
         } else {
-            Some(Codec::wrap(ptr))
+            Some(Codec::wrap(ptr as *mut _))
         }
     }
 }
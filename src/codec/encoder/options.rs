@@ -0,0 +1,264 @@
+//! Typed private options of the most used software encoders, producing the
+//! keys and values expected by libx264, libx265 and libvpx.
+//!
+//! The builders turn into a `Dictionary` for `open_with`, or are queued on an
+//! `encoder::Builder` with `Builder::options_from`.
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Preset {
+    Ultrafast,
+    Superfast,
+    Veryfast,
+    Faster,
+    Fast,
+    Medium,
+    Slow,
+    Slower,
+    Veryslow,
+    Placebo,
+}
+
+impl Preset {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Preset::Ultrafast => "ultrafast",
+            Preset::Superfast => "superfast",
+            Preset::Veryfast => "veryfast",
+            Preset::Faster => "faster",
+            Preset::Fast => "fast",
+            Preset::Medium => "medium",
+            Preset::Slow => "slow",
+            Preset::Slower => "slower",
+            Preset::Veryslow => "veryslow",
+            Preset::Placebo => "placebo",
+        }
+    }
+}
+
+/// The tunings of libx264 and libx265, the latter not supporting `Film` and
+/// `StillImage`.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Tune {
+    Film,
+    Animation,
+    Grain,
+    StillImage,
+    Psnr,
+    Ssim,
+    FastDecode,
+    ZeroLatency,
+}
+
+impl Tune {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Tune::Film => "film",
+            Tune::Animation => "animation",
+            Tune::Grain => "grain",
+            Tune::StillImage => "stillimage",
+            Tune::Psnr => "psnr",
+            Tune::Ssim => "ssim",
+            Tune::FastDecode => "fastdecode",
+            Tune::ZeroLatency => "zerolatency",
+        }
+    }
+}
+
+/// The quality/speed trade-off of libvpx.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Deadline {
+    Best,
+    Good,
+    Realtime,
+}
+
+impl Deadline {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Deadline::Best => "best",
+            Deadline::Good => "good",
+            Deadline::Realtime => "realtime",
+        }
+    }
+}
+
+/// Private options of libx264.
+#[derive(Clone, Debug, Default)]
+pub struct X264 {
+    options: Vec<(String, String)>,
+    params: Vec<String>,
+}
+
+impl X264 {
+    pub const CODEC: &'static str = "libx264";
+
+    pub fn preset(self, value: Preset) -> Self {
+        self.set("preset", value.name())
+    }
+
+    pub fn tune(self, value: Tune) -> Self {
+        self.set("tune", value.name())
+    }
+
+    /// Set the constant rate factor, from 0 (lossless) to 51, 23 by default.
+    pub fn crf(self, value: f32) -> Self {
+        self.set("crf", value.to_string())
+    }
+
+    /// Set the profile, e.g. `baseline`, `main` or `high`.
+    pub fn profile(self, value: &str) -> Self {
+        self.set("profile", value)
+    }
+
+    /// Set a parameter of the library not exposed as an option, passed in
+    /// `x264-params`.
+    pub fn param(mut self, key: &str, value: &str) -> Self {
+        self.params.push(format!("{}={}", key, value));
+        self
+    }
+
+    /// Repeat the SPS/PPS before every keyframe, for clients joining
+    /// mid-stream.
+    pub fn repeat_headers(self, value: bool) -> Self {
+        self.param("repeat-headers", if value { "1" } else { "0" })
+    }
+
+    /// Insert access unit delimiters.
+    pub fn aud(self, value: bool) -> Self {
+        self.set("aud", if value { "1" } else { "0" })
+    }
+}
+
+options!(X264, |mut value: X264| {
+    if !value.params.is_empty() {
+        value
+            .options
+            .push(("x264-params".into(), value.params.join(":")));
+    }
+
+    value.options
+});
+
+/// Private options of libx265.
+#[derive(Clone, Debug, Default)]
+pub struct X265 {
+    options: Vec<(String, String)>,
+    params: Vec<String>,
+}
+
+impl X265 {
+    pub const CODEC: &'static str = "libx265";
+
+    pub fn preset(self, value: Preset) -> Self {
+        self.set("preset", value.name())
+    }
+
+    pub fn tune(self, value: Tune) -> Self {
+        self.set("tune", value.name())
+    }
+
+    /// Set the constant rate factor, from 0 to 51, 28 by default.
+    pub fn crf(self, value: f32) -> Self {
+        self.set("crf", value.to_string())
+    }
+
+    /// Set the profile, e.g. `main`, `main10` or `main444-8`.
+    pub fn profile(self, value: &str) -> Self {
+        self.set("profile", value)
+    }
+
+    /// Set a parameter of the library not exposed as an option, passed in
+    /// `x265-params`.
+    pub fn param(mut self, key: &str, value: &str) -> Self {
+        self.params.push(format!("{}={}", key, value));
+        self
+    }
+
+    /// Repeat the VPS/SPS/PPS before every keyframe, for clients joining
+    /// mid-stream.
+    pub fn repeat_headers(self, value: bool) -> Self {
+        self.param("repeat-headers", if value { "1" } else { "0" })
+    }
+
+    /// Insert access unit delimiters.
+    pub fn aud(self, value: bool) -> Self {
+        self.param("aud", if value { "1" } else { "0" })
+    }
+}
+
+options!(X265, |mut value: X265| {
+    if !value.params.is_empty() {
+        value
+            .options
+            .push(("x265-params".into(), value.params.join(":")));
+    }
+
+    value.options
+});
+
+/// Private options of libvpx, for both VP8 and VP9.
+#[derive(Clone, Debug, Default)]
+pub struct Vpx {
+    options: Vec<(String, String)>,
+}
+
+impl Vpx {
+    pub const VP8: &'static str = "libvpx";
+    pub const VP9: &'static str = "libvpx-vp9";
+
+    pub fn deadline(self, value: Deadline) -> Self {
+        self.set("deadline", value.name())
+    }
+
+    /// Set the speed, from -16 to 16 (higher is faster), with the `Good` and
+    /// `Realtime` deadlines.
+    pub fn cpu_used(self, value: i32) -> Self {
+        self.set("cpu-used", value.to_string())
+    }
+
+    /// Set the constant quality, from 0 to 63, along with a bit rate of 0
+    /// for the constant quality mode, or as a cap on the bit rate otherwise.
+    pub fn crf(self, value: u32) -> Self {
+        self.set("crf", value.to_string())
+    }
+
+    /// Encode the rows in parallel, VP9 only.
+    pub fn row_mt(self, value: bool) -> Self {
+        self.set("row-mt", if value { "1" } else { "0" })
+    }
+
+    /// Set the number of frames to look ahead, 0 to disable alternate
+    /// reference frames for low latency.
+    pub fn lag_in_frames(self, value: u32) -> Self {
+        self.set("lag-in-frames", value.to_string())
+    }
+}
+
+options!(Vpx);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_x264() {
+        let options = X264::new()
+            .preset(Preset::Medium)
+            .tune(Tune::Film)
+            .crf(23.0)
+            .param("keyint", "60")
+            .param("bframes", "2")
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            options,
+            vec![
+                ("preset".to_string(), "medium".to_string()),
+                ("tune".to_string(), "film".to_string()),
+                ("crf".to_string(), "23".to_string()),
+                ("x264-params".to_string(), "keyint=60:bframes=2".to_string()),
+            ]
+        );
+    }
+}
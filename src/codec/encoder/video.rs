@@ -5,9 +5,13 @@ use ffi::*;
 use libc::{c_float, c_int};
 
 use super::Encoder as Super;
-use super::{Comparison, Decision, MotionEstimation, Prediction};
+use super::{Comparison, Decision};
+#[cfg(not(feature = "ffmpeg_5_0"))]
+use super::{MotionEstimation, Prediction};
 use codec::{traits, Context};
-use {color, format, frame, packet, Dictionary, Error, Rational};
+use {color, format, Dictionary, Error, Rational};
+#[cfg(not(feature = "ffmpeg_5_0"))]
+use {frame, packet};
 
 pub struct Video(pub Super);
 
@@ -195,6 +199,7 @@ impl Video {
         }
     }
 
+    #[cfg(not(feature = "ffmpeg_5_0"))]
     #[inline]
     pub fn set_prediction(&mut self, value: Prediction) {
         unsafe {
@@ -251,6 +256,7 @@ impl Video {
         }
     }
 
+    #[cfg(not(feature = "ffmpeg_5_0"))]
     #[inline]
     pub fn set_pre_me(&mut self, value: MotionEstimation) {
         unsafe {
@@ -382,6 +388,21 @@ impl Video {
     pub fn color_range(&self) -> color::Range {
         unsafe { (*self.as_ptr()).color_range.into() }
     }
+
+    /// Set the pixel format to the one supported by the encoder that loses the
+    /// least information compared to `preferred`, and return it.
+    ///
+    /// If the codec is unknown or does not restrict its pixel formats,
+    /// `preferred` is used as is.
+    pub fn pick_best_pixel_format(&mut self, preferred: format::Pixel) -> format::Pixel {
+        let format = match self.codec().and_then(|c| c.video().ok()) {
+            Some(codec) => codec.supported_formats().best_match(preferred),
+            None => preferred,
+        };
+
+        self.set_format(format);
+        format
+    }
 }
 
 impl Deref for Video {
@@ -415,6 +436,7 @@ impl AsMut<Context> for Video {
 pub struct Encoder(pub Video);
 
 impl Encoder {
+    #[cfg(not(feature = "ffmpeg_5_0"))]
     #[deprecated(
         since = "4.4.0",
         note = "Underlying API avcodec_encode_video2 has been deprecated since FFmpeg 3.1; \
@@ -448,6 +470,7 @@ impl Encoder {
         }
     }
 
+    #[cfg(not(feature = "ffmpeg_5_0"))]
     #[deprecated(
         since = "4.4.0",
         note = "Underlying API avcodec_encode_video2 has been deprecated since FFmpeg 3.1; \
@@ -3,6 +3,8 @@ use std::str::from_utf8_unchecked;
 
 use ffi::AVCodecID::*;
 use ffi::*;
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer};
 use util::media;
 
 #[allow(non_camel_case_types)]
@@ -581,6 +583,13 @@ impl Id {
     }
 }
 
+#[cfg(feature = "serde")]
+impl Serialize for Id {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
 impl From<AVCodecID> for Id {
     fn from(value: AVCodecID) -> Self {
         match value {
@@ -1139,6 +1148,9 @@ impl From<AVCodecID> for Id {
             AV_CODEC_ID_HCA => Id::HCA,
             #[cfg(feature = "ffmpeg_4_3")]
             AV_CODEC_ID_EPG => Id::EPG,
+
+            #[allow(unreachable_patterns)]
+            _ => Id::None,
         }
     }
 }
@@ -8,8 +8,14 @@ pub use self::id::Id;
 
 pub mod packet;
 
+pub mod parser;
+pub use self::parser::Parser;
+
+pub mod bitstream;
+
 pub mod subtitle;
 
+#[cfg(not(feature = "ffmpeg_5_0"))]
 pub mod picture;
 
 pub mod discard;
@@ -19,11 +25,14 @@ pub use self::context::Context;
 
 pub mod capabilities;
 pub use self::capabilities::Capabilities;
+
+pub mod properties;
+pub use self::properties::Properties;
 /// Codec Module
 pub mod codec;
 
 pub mod parameters;
-pub use self::parameters::Parameters;
+pub use self::parameters::{Parameters, Summary};
 
 pub mod video;
 pub use self::video::Video;
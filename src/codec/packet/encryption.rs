@@ -0,0 +1,350 @@
+//! Common Encryption (CENC) metadata carried as side data, per packet
+//! (`Info`) and per stream (`InitInfo`).
+
+use std::ptr;
+use std::slice;
+
+use self::raw::*;
+use libc::{size_t, ENOMEM};
+use Error;
+
+/// The clear and protected byte counts of a subsample.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct Subsample {
+    pub clear_bytes: u32,
+    pub protected_bytes: u32,
+}
+
+/// The encryption parameters of a packet.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Info {
+    /// The protection scheme FourCC, e.g. `cenc` or `cbcs`.
+    pub scheme: u32,
+    pub crypt_byte_block: u32,
+    pub skip_byte_block: u32,
+    pub key_id: Vec<u8>,
+    pub iv: Vec<u8>,
+    /// The subsamples, if empty the whole packet is encrypted.
+    pub subsamples: Vec<Subsample>,
+}
+
+impl Info {
+    /// Parse `AV_PKT_DATA_ENCRYPTION_INFO` side data.
+    pub fn from_side_data(data: &[u8]) -> Option<Self> {
+        unsafe {
+            let ptr = av_encryption_info_get_side_data(data.as_ptr(), data.len() as size_t);
+
+            if ptr.is_null() {
+                return None;
+            }
+
+            let info = Info {
+                scheme: (*ptr).scheme,
+                crypt_byte_block: (*ptr).crypt_byte_block,
+                skip_byte_block: (*ptr).skip_byte_block,
+                key_id: bytes((*ptr).key_id, (*ptr).key_id_size),
+                iv: bytes((*ptr).iv, (*ptr).iv_size),
+                subsamples: if (*ptr).subsample_count == 0 {
+                    Vec::new()
+                } else {
+                    slice::from_raw_parts((*ptr).subsamples, (*ptr).subsample_count as usize)
+                        .iter()
+                        .map(|s| Subsample {
+                            clear_bytes: s.bytes_of_clear_data,
+                            protected_bytes: s.bytes_of_protected_data,
+                        })
+                        .collect()
+                },
+            };
+
+            av_encryption_info_free(ptr);
+
+            Some(info)
+        }
+    }
+
+    /// Serialize as `AV_PKT_DATA_ENCRYPTION_INFO` side data, the returned
+    /// buffer is allocated with `av_malloc`.
+    pub unsafe fn to_side_data(&self) -> Result<(*mut u8, usize), Error> {
+        let ptr = av_encryption_info_alloc(
+            self.subsamples.len() as u32,
+            self.key_id.len() as u32,
+            self.iv.len() as u32,
+        );
+
+        if ptr.is_null() {
+            return Err(Error::Other { errno: ENOMEM });
+        }
+
+        (*ptr).scheme = self.scheme;
+        (*ptr).crypt_byte_block = self.crypt_byte_block;
+        (*ptr).skip_byte_block = self.skip_byte_block;
+        ptr::copy_nonoverlapping(self.key_id.as_ptr(), (*ptr).key_id, self.key_id.len());
+        ptr::copy_nonoverlapping(self.iv.as_ptr(), (*ptr).iv, self.iv.len());
+
+        for (index, subsample) in self.subsamples.iter().enumerate() {
+            let target = (*ptr).subsamples.add(index);
+
+            (*target).bytes_of_clear_data = subsample.clear_bytes;
+            (*target).bytes_of_protected_data = subsample.protected_bytes;
+        }
+
+        let mut size = 0;
+        let data = av_encryption_info_add_side_data(ptr, &mut size);
+
+        av_encryption_info_free(ptr);
+
+        if data.is_null() {
+            Err(Error::Other { errno: ENOMEM })
+        } else {
+            Ok((data, size as usize))
+        }
+    }
+}
+
+/// The initialization data of a DRM system, e.g. a `pssh` box.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct InitInfo {
+    pub system_id: Vec<u8>,
+    /// The key IDs, all of the same size.
+    pub key_ids: Vec<Vec<u8>>,
+    pub data: Vec<u8>,
+}
+
+impl InitInfo {
+    /// Parse `AV_PKT_DATA_ENCRYPTION_INIT_INFO` side data, which holds one
+    /// entry per DRM system.
+    pub fn from_side_data(data: &[u8]) -> Option<Vec<Self>> {
+        unsafe {
+            let head = av_encryption_init_info_get_side_data(data.as_ptr(), data.len() as size_t);
+
+            if head.is_null() {
+                return None;
+            }
+
+            let mut infos = Vec::new();
+            let mut ptr = head;
+
+            while !ptr.is_null() {
+                let key_ids = if (*ptr).num_key_ids == 0 {
+                    Vec::new()
+                } else {
+                    slice::from_raw_parts((*ptr).key_ids, (*ptr).num_key_ids as usize)
+                        .iter()
+                        .map(|&id| bytes(id, (*ptr).key_id_size))
+                        .collect()
+                };
+
+                infos.push(InitInfo {
+                    system_id: bytes((*ptr).system_id, (*ptr).system_id_size),
+                    key_ids,
+                    data: bytes((*ptr).data, (*ptr).data_size),
+                });
+
+                ptr = (*ptr).next;
+            }
+
+            av_encryption_init_info_free(head);
+
+            Some(infos)
+        }
+    }
+
+    /// Serialize entries as `AV_PKT_DATA_ENCRYPTION_INIT_INFO` side data, the
+    /// returned buffer is allocated with `av_malloc`.
+    pub unsafe fn to_side_data(infos: &[InitInfo]) -> Result<(*mut u8, usize), Error> {
+        let mut head: *mut AVEncryptionInitInfo = ptr::null_mut();
+        let mut tail: *mut AVEncryptionInitInfo = ptr::null_mut();
+
+        for info in infos {
+            let key_id_size = info.key_ids.first().map_or(0, |id| id.len());
+
+            if info.key_ids.iter().any(|id| id.len() != key_id_size) {
+                av_encryption_init_info_free(head);
+                return Err(Error::InvalidData);
+            }
+
+            let ptr = av_encryption_init_info_alloc(
+                info.system_id.len() as u32,
+                info.key_ids.len() as u32,
+                key_id_size as u32,
+                info.data.len() as u32,
+            );
+
+            if ptr.is_null() {
+                av_encryption_init_info_free(head);
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            ptr::copy_nonoverlapping(
+                info.system_id.as_ptr(),
+                (*ptr).system_id,
+                info.system_id.len(),
+            );
+            ptr::copy_nonoverlapping(info.data.as_ptr(), (*ptr).data, info.data.len());
+
+            for (index, id) in info.key_ids.iter().enumerate() {
+                ptr::copy_nonoverlapping(id.as_ptr(), *(*ptr).key_ids.add(index), id.len());
+            }
+
+            if tail.is_null() {
+                head = ptr;
+            } else {
+                (*tail).next = ptr;
+            }
+
+            tail = ptr;
+        }
+
+        if head.is_null() {
+            return Err(Error::InvalidData);
+        }
+
+        let mut size = 0;
+        let data = av_encryption_init_info_add_side_data(head, &mut size);
+
+        av_encryption_init_info_free(head);
+
+        if data.is_null() {
+            Err(Error::Other { errno: ENOMEM })
+        } else {
+            Ok((data, size as usize))
+        }
+    }
+}
+
+unsafe fn bytes(ptr: *const u8, size: u32) -> Vec<u8> {
+    if ptr.is_null() || size == 0 {
+        Vec::new()
+    } else {
+        slice::from_raw_parts(ptr, size as usize).to_vec()
+    }
+}
+
+// libavutil/encryption_info.h, which is not part of the generated bindings.
+mod raw {
+    use libc::{c_uint, size_t};
+
+    #[repr(C)]
+    pub struct AVSubsampleEncryptionInfo {
+        pub bytes_of_clear_data: c_uint,
+        pub bytes_of_protected_data: c_uint,
+    }
+
+    #[repr(C)]
+    pub struct AVEncryptionInfo {
+        pub scheme: u32,
+        pub crypt_byte_block: u32,
+        pub skip_byte_block: u32,
+        pub key_id: *mut u8,
+        pub key_id_size: u32,
+        pub iv: *mut u8,
+        pub iv_size: u32,
+        pub subsamples: *mut AVSubsampleEncryptionInfo,
+        pub subsample_count: u32,
+    }
+
+    #[repr(C)]
+    pub struct AVEncryptionInitInfo {
+        pub system_id: *mut u8,
+        pub system_id_size: u32,
+        pub key_ids: *mut *mut u8,
+        pub num_key_ids: u32,
+        pub key_id_size: u32,
+        pub data: *mut u8,
+        pub data_size: u32,
+        pub next: *mut AVEncryptionInitInfo,
+    }
+
+    extern "C" {
+        pub fn av_encryption_info_alloc(
+            subsample_count: u32,
+            key_id_size: u32,
+            iv_size: u32,
+        ) -> *mut AVEncryptionInfo;
+        pub fn av_encryption_info_free(info: *mut AVEncryptionInfo);
+        pub fn av_encryption_info_get_side_data(
+            side_data: *const u8,
+            side_data_size: size_t,
+        ) -> *mut AVEncryptionInfo;
+        pub fn av_encryption_info_add_side_data(
+            info: *const AVEncryptionInfo,
+            side_data_size: *mut size_t,
+        ) -> *mut u8;
+
+        pub fn av_encryption_init_info_alloc(
+            system_id_size: u32,
+            num_key_ids: u32,
+            key_id_size: u32,
+            data_size: u32,
+        ) -> *mut AVEncryptionInitInfo;
+        pub fn av_encryption_init_info_free(info: *mut AVEncryptionInitInfo);
+        pub fn av_encryption_init_info_get_side_data(
+            side_data: *const u8,
+            side_data_size: size_t,
+        ) -> *mut AVEncryptionInitInfo;
+        pub fn av_encryption_init_info_add_side_data(
+            info: *const AVEncryptionInitInfo,
+            side_data_size: *mut size_t,
+        ) -> *mut u8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ffi::av_free;
+    use std::slice;
+
+    #[test]
+    fn test_round_trip() {
+        let info = Info {
+            scheme: u32::from_be_bytes(*b"cbcs"),
+            crypt_byte_block: 1,
+            skip_byte_block: 9,
+            key_id: vec![0x11; 16],
+            iv: vec![0x22; 16],
+            subsamples: vec![
+                Subsample {
+                    clear_bytes: 5,
+                    protected_bytes: 128,
+                },
+                Subsample {
+                    clear_bytes: 7,
+                    protected_bytes: 0,
+                },
+            ],
+        };
+
+        unsafe {
+            let (data, size) = info.to_side_data().unwrap();
+            let parsed = Info::from_side_data(slice::from_raw_parts(data, size));
+            av_free(data as *mut _);
+
+            assert_eq!(parsed, Some(info));
+        }
+
+        let infos = vec![
+            InitInfo {
+                system_id: vec![0x33; 16],
+                key_ids: vec![vec![0x44; 16], vec![0x55; 16]],
+                data: b"pssh".to_vec(),
+            },
+            InitInfo {
+                system_id: vec![0x66; 16],
+                key_ids: Vec::new(),
+                data: Vec::new(),
+            },
+        ];
+
+        unsafe {
+            let (data, size) = InitInfo::to_side_data(&infos).unwrap();
+            let parsed = InitInfo::from_side_data(slice::from_raw_parts(data, size));
+            av_free(data as *mut _);
+
+            assert_eq!(parsed, Some(infos));
+        }
+
+        assert_eq!(Info::from_side_data(&[0; 4]), None);
+    }
+}
@@ -10,5 +10,8 @@ pub use self::borrow::Borrow;
 pub mod side_data;
 pub use self::side_data::SideData;
 
+#[cfg(feature = "ffmpeg_4_0")]
+pub mod encryption;
+
 pub mod flag;
 pub use self::flag::Flags;
@@ -1,11 +1,14 @@
 use std::marker::PhantomData;
 use std::mem;
+use std::ptr;
 use std::slice;
 
-use super::{Borrow, Flags, Mut, Ref, SideData};
+#[cfg(feature = "ffmpeg_4_0")]
+use super::encryption;
+use super::{side_data, Borrow, Flags, Mut, Ref, SideData};
 use ffi::*;
-use libc::c_int;
-use {format, Error, Rational};
+use libc::{c_int, c_void, ENOMEM};
+use {format, Buffer, Error, Rational};
 
 pub struct Packet(AVPacket);
 
@@ -53,6 +56,108 @@ impl Packet {
         packet
     }
 
+    /// Create a packet referencing `data` without copying it, e.g. from
+    /// `Arc<[u8]>` or `bytes::Bytes`, which is dropped along with the last
+    /// reference to the packet.
+    ///
+    /// The data must end with `AV_INPUT_BUFFER_PADDING_SIZE` zeroed bytes,
+    /// which are not part of the payload, as decoders read past the end.
+    pub fn from_buffer<T: AsRef<[u8]> + Send + 'static>(data: T) -> Result<Self, Error> {
+        unsafe {
+            let data = Box::into_raw(Box::new(data));
+            let (ptr, size) = {
+                let bytes = (*data).as_ref();
+                (bytes.as_ptr() as *mut u8, bytes.len())
+            };
+
+            if size < AV_INPUT_BUFFER_PADDING_SIZE as usize {
+                drop(Box::from_raw(data));
+                return Err(Error::InvalidData);
+            }
+
+            let buf = av_buffer_create(
+                ptr,
+                size as _,
+                Some(free::<T>),
+                data as *mut c_void,
+                AV_BUFFER_FLAG_READONLY,
+            );
+
+            if buf.is_null() {
+                drop(Box::from_raw(data));
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            let mut packet = Packet::empty();
+            packet.0.buf = buf;
+            packet.0.data = ptr;
+            packet.0.size = (size - AV_INPUT_BUFFER_PADDING_SIZE as usize) as c_int;
+
+            Ok(packet)
+        }
+    }
+
+    /// Create a packet referencing the first `size` bytes of a buffer,
+    /// which must be followed by `AV_INPUT_BUFFER_PADDING_SIZE` bytes.
+    pub fn with_buffer(buffer: Buffer, size: usize) -> Result<Self, Error> {
+        if size + AV_INPUT_BUFFER_PADDING_SIZE as usize > buffer.len() {
+            return Err(Error::InvalidData);
+        }
+
+        unsafe {
+            let mut packet = Packet::empty();
+            packet.0.data = (*buffer.as_ptr()).data;
+            packet.0.size = size as c_int;
+            packet.0.buf = buffer.into_raw();
+
+            Ok(packet)
+        }
+    }
+
+    /// Get a reference to the buffer of the packet, `None` if it does not
+    /// own its data.
+    pub fn buffer(&self) -> Option<Buffer> {
+        unsafe {
+            if self.0.buf.is_null() {
+                None
+            } else {
+                let ptr = av_buffer_ref(self.0.buf);
+
+                if ptr.is_null() {
+                    None
+                } else {
+                    Some(Buffer::wrap(ptr))
+                }
+            }
+        }
+    }
+
+    /// Take the content of a packet allocated with `av_packet_alloc`, e.g.
+    /// by `into_raw`, freeing it.
+    pub unsafe fn from_raw(mut ptr: *mut AVPacket) -> Self {
+        let mut packet = Packet::empty();
+        av_packet_move_ref(&mut packet.0, ptr);
+        av_packet_free(&mut ptr);
+
+        packet
+    }
+
+    /// Move the packet to one allocated with `av_packet_alloc`, to be
+    /// released with `av_packet_free`.
+    pub fn into_raw(mut self) -> Result<*mut AVPacket, Self> {
+        unsafe {
+            let ptr = av_packet_alloc();
+
+            if ptr.is_null() {
+                return Err(self);
+            }
+
+            av_packet_move_ref(ptr, &mut self.0);
+
+            Ok(ptr)
+        }
+    }
+
     #[inline]
     pub fn borrow(data: &[u8]) -> Borrow {
         Borrow::new(data)
@@ -168,6 +273,7 @@ impl Packet {
         self.0.pos = value as i64
     }
 
+    #[cfg(not(feature = "ffmpeg_5_0"))]
     #[inline]
     pub fn convergence(&self) -> isize {
         self.0.convergence_duration as isize
@@ -178,6 +284,77 @@ impl Packet {
         SideDataIter::new(&self.0)
     }
 
+    /// Add side data to the packet, e.g. stream parameters for consumers only
+    /// looking at packets.
+    pub fn add_side_data(&mut self, kind: side_data::Type, data: &[u8]) -> Result<(), Error> {
+        unsafe {
+            let ptr = av_packet_new_side_data(&mut self.0, kind.into(), data.len() as _);
+
+            if ptr.is_null() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            ptr::copy_nonoverlapping(data.as_ptr(), ptr, data.len());
+
+            Ok(())
+        }
+    }
+
+    /// Merge the side data into the payload of the packet, as some
+    /// consumers of old versions expected.
+    #[cfg(not(feature = "ffmpeg_5_0"))]
+    pub fn merge_side_data(&mut self) -> Result<(), Error> {
+        unsafe {
+            match av_packet_merge_side_data(&mut self.0) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Split the side data merged into the payload by `merge_side_data`
+    /// back out, returning whether there was any.
+    #[cfg(not(feature = "ffmpeg_5_0"))]
+    pub fn split_side_data(&mut self) -> Result<bool, Error> {
+        unsafe {
+            match av_packet_split_side_data(&mut self.0) {
+                e if e < 0 => Err(Error::from(e)),
+                n => Ok(n > 0),
+            }
+        }
+    }
+
+    /// Get the Common Encryption parameters of the packet, if any.
+    #[cfg(feature = "ffmpeg_4_0")]
+    pub fn encryption_info(&self) -> Option<encryption::Info> {
+        self.side_data()
+            .find(|side_data| side_data.kind() == side_data::Type::EncryptionInfo)
+            .and_then(|side_data| encryption::Info::from_side_data(side_data.data()))
+    }
+
+    /// Set the Common Encryption parameters of the packet, replacing any
+    /// previous ones.
+    #[cfg(feature = "ffmpeg_4_0")]
+    pub fn set_encryption_info(&mut self, info: &encryption::Info) -> Result<(), Error> {
+        unsafe {
+            let (data, size) = info.to_side_data()?;
+
+            match av_packet_add_side_data(
+                &mut self.0,
+                AVPacketSideDataType::AV_PKT_DATA_ENCRYPTION_INFO,
+                data,
+                size as _,
+            ) {
+                e if e < 0 => {
+                    av_free(data as *mut _);
+                    Err(Error::from(e))
+                }
+
+                _ => Ok(()),
+            }
+        }
+    }
+
     #[inline]
     pub fn data(&self) -> Option<&[u8]> {
         unsafe {
@@ -189,9 +366,17 @@ impl Packet {
         }
     }
 
+    /// Get the data of the packet, copied first if it is shared.
     #[inline]
     pub fn data_mut(&mut self) -> Option<&mut [u8]> {
         unsafe {
+            if !self.0.buf.is_null()
+                && av_buffer_is_writable(self.0.buf) == 0
+                && av_packet_make_writable(&mut self.0) < 0
+            {
+                return None;
+            }
+
             if self.0.data.is_null() {
                 None
             } else {
@@ -240,6 +425,12 @@ impl Packet {
     }
 }
 
+extern "C" fn free<T>(opaque: *mut c_void, _data: *mut u8) {
+    unsafe {
+        drop(Box::from_raw(opaque as *mut T));
+    }
+}
+
 impl Ref for Packet {
     fn as_ptr(&self) -> *const AVPacket {
         &self.0
@@ -261,12 +452,23 @@ impl Clone for Packet {
         pkt
     }
 
+    #[cfg(not(feature = "ffmpeg_4_0"))]
     #[inline]
     fn clone_from(&mut self, source: &Self) {
         unsafe {
             av_copy_packet(&mut self.0, &source.0);
         }
     }
+
+    #[cfg(feature = "ffmpeg_4_0")]
+    #[inline]
+    fn clone_from(&mut self, source: &Self) {
+        unsafe {
+            av_packet_unref(&mut self.0);
+            av_packet_ref(&mut self.0, &source.0);
+            av_packet_make_writable(&mut self.0);
+        }
+    }
 }
 
 impl Drop for Packet {
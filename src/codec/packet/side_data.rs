@@ -48,6 +48,21 @@ pub enum Type {
     ICC_PROFILE,
     #[cfg(feature = "ffmpeg_4_3")]
     DOVI_CONF,
+
+    #[cfg(feature = "ffmpeg_4_4")]
+    S12M_TIMECODE,
+
+    #[cfg(feature = "ffmpeg_5_0")]
+    DYNAMIC_HDR10_PLUS,
+
+    #[cfg(feature = "ffmpeg_7_0")]
+    IAMF_MIX_GAIN_PARAM,
+    #[cfg(feature = "ffmpeg_7_0")]
+    IAMF_DEMIXING_INFO_PARAM,
+    #[cfg(feature = "ffmpeg_7_0")]
+    IAMF_RECON_GAIN_INFO_PARAM,
+    #[cfg(feature = "ffmpeg_7_0")]
+    AMBIENT_VIEWING_ENVIRONMENT,
 }
 
 impl From<AVPacketSideDataType> for Type {
@@ -94,6 +109,21 @@ impl From<AVPacketSideDataType> for Type {
             AV_PKT_DATA_ICC_PROFILE => Type::ICC_PROFILE,
             #[cfg(feature = "ffmpeg_4_3")]
             AV_PKT_DATA_DOVI_CONF => Type::DOVI_CONF,
+
+            #[cfg(feature = "ffmpeg_4_4")]
+            AV_PKT_DATA_S12M_TIMECODE => Type::S12M_TIMECODE,
+
+            #[cfg(feature = "ffmpeg_5_0")]
+            AV_PKT_DATA_DYNAMIC_HDR10_PLUS => Type::DYNAMIC_HDR10_PLUS,
+
+            #[cfg(feature = "ffmpeg_7_0")]
+            AV_PKT_DATA_IAMF_MIX_GAIN_PARAM => Type::IAMF_MIX_GAIN_PARAM,
+            #[cfg(feature = "ffmpeg_7_0")]
+            AV_PKT_DATA_IAMF_DEMIXING_INFO_PARAM => Type::IAMF_DEMIXING_INFO_PARAM,
+            #[cfg(feature = "ffmpeg_7_0")]
+            AV_PKT_DATA_IAMF_RECON_GAIN_INFO_PARAM => Type::IAMF_RECON_GAIN_INFO_PARAM,
+            #[cfg(feature = "ffmpeg_7_0")]
+            AV_PKT_DATA_AMBIENT_VIEWING_ENVIRONMENT => Type::AMBIENT_VIEWING_ENVIRONMENT,
         }
     }
 }
@@ -142,6 +172,21 @@ impl Into<AVPacketSideDataType> for Type {
             Type::ICC_PROFILE => AV_PKT_DATA_ICC_PROFILE,
             #[cfg(feature = "ffmpeg_4_3")]
             Type::DOVI_CONF => AV_PKT_DATA_DOVI_CONF,
+
+            #[cfg(feature = "ffmpeg_4_4")]
+            Type::S12M_TIMECODE => AV_PKT_DATA_S12M_TIMECODE,
+
+            #[cfg(feature = "ffmpeg_5_0")]
+            Type::DYNAMIC_HDR10_PLUS => AV_PKT_DATA_DYNAMIC_HDR10_PLUS,
+
+            #[cfg(feature = "ffmpeg_7_0")]
+            Type::IAMF_MIX_GAIN_PARAM => AV_PKT_DATA_IAMF_MIX_GAIN_PARAM,
+            #[cfg(feature = "ffmpeg_7_0")]
+            Type::IAMF_DEMIXING_INFO_PARAM => AV_PKT_DATA_IAMF_DEMIXING_INFO_PARAM,
+            #[cfg(feature = "ffmpeg_7_0")]
+            Type::IAMF_RECON_GAIN_INFO_PARAM => AV_PKT_DATA_IAMF_RECON_GAIN_INFO_PARAM,
+            #[cfg(feature = "ffmpeg_7_0")]
+            Type::AMBIENT_VIEWING_ENVIRONMENT => AV_PKT_DATA_AMBIENT_VIEWING_ENVIRONMENT,
         }
     }
 }
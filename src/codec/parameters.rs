@@ -1,8 +1,39 @@
+use std::ffi::CStr;
+use std::mem;
+use std::ptr;
 use std::rc::Rc;
+use std::slice;
+use std::str::from_utf8_unchecked;
 
 use super::{Context, Id};
 use ffi::*;
-use media;
+use libc::{c_int, c_void, ENOMEM};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+#[cfg(feature = "ffmpeg_5_1")]
+use util::channel_layout::Layout;
+#[cfg(feature = "ffmpeg_6_1")]
+use Rational;
+use {format, media, ChannelLayout, Error};
+
+/// The main properties of a stream, e.g. to describe it as ffprobe does.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[derive(PartialEq, Clone, Debug)]
+pub struct Summary {
+    pub medium: media::Type,
+    pub id: Id,
+    pub profile: Option<&'static str>,
+    pub bit_rate: i64,
+
+    pub width: u32,
+    pub height: u32,
+    pub pixel_format: Option<format::Pixel>,
+
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub channel_layout: Option<ChannelLayout>,
+    pub sample_format: Option<format::Sample>,
+}
 
 pub struct Parameters {
     ptr: *mut AVCodecParameters,
@@ -35,13 +66,280 @@ impl Parameters {
         }
     }
 
+    /// Get the parameters of the codec `context`, e.g. of an opened encoder
+    /// to set on an output stream.
+    pub fn from_context(context: &Context) -> Result<Self, Error> {
+        let mut parameters = Parameters::new();
+
+        unsafe {
+            match avcodec_parameters_from_context(parameters.as_mut_ptr(), context.as_ptr()) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(parameters),
+            }
+        }
+    }
+
+    /// Start building parameters of the codec `id`, whose media type is set
+    /// from it.
+    pub fn builder(id: Id) -> Builder {
+        Builder::new(id)
+    }
+
     pub fn medium(&self) -> media::Type {
         unsafe { media::Type::from((*self.as_ptr()).codec_type) }
     }
 
+    pub fn set_medium(&mut self, value: media::Type) {
+        unsafe {
+            (*self.as_mut_ptr()).codec_type = value.into();
+        }
+    }
+
     pub fn id(&self) -> Id {
         unsafe { Id::from((*self.as_ptr()).codec_id) }
     }
+
+    pub fn set_id(&mut self, value: Id) {
+        unsafe {
+            (*self.as_mut_ptr()).codec_id = value.into();
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        unsafe { (*self.as_ptr()).width as u32 }
+    }
+
+    pub fn set_width(&mut self, value: u32) {
+        unsafe {
+            (*self.as_mut_ptr()).width = value as c_int;
+        }
+    }
+
+    pub fn height(&self) -> u32 {
+        unsafe { (*self.as_ptr()).height as u32 }
+    }
+
+    pub fn set_height(&mut self, value: u32) {
+        unsafe {
+            (*self.as_mut_ptr()).height = value as c_int;
+        }
+    }
+
+    /// Get the pixel format of video parameters, `Pixel::None` if unknown.
+    pub fn pixel_format(&self) -> format::Pixel {
+        unsafe {
+            match (*self.as_ptr()).format {
+                f if f < 0 || self.medium() != media::Type::Video => format::Pixel::None,
+                f => format::Pixel::from(mem::transmute::<c_int, AVPixelFormat>(f)),
+            }
+        }
+    }
+
+    pub fn set_pixel_format(&mut self, value: format::Pixel) {
+        let format: AVPixelFormat = value.into();
+
+        unsafe {
+            (*self.as_mut_ptr()).format = format as c_int;
+        }
+    }
+
+    /// Get the sample format of audio parameters, `Sample::None` if unknown.
+    pub fn sample_format(&self) -> format::Sample {
+        unsafe {
+            match (*self.as_ptr()).format {
+                f if f < 0 || self.medium() != media::Type::Audio => format::Sample::None,
+                f => format::Sample::from(mem::transmute::<c_int, AVSampleFormat>(f)),
+            }
+        }
+    }
+
+    pub fn set_sample_format(&mut self, value: format::Sample) {
+        let format: AVSampleFormat = value.into();
+
+        unsafe {
+            (*self.as_mut_ptr()).format = format as c_int;
+        }
+    }
+
+    /// Get the average bit rate in bits per second, 0 if unknown.
+    pub fn bit_rate(&self) -> i64 {
+        unsafe { (*self.as_ptr()).bit_rate }
+    }
+
+    pub fn set_bit_rate(&mut self, value: i64) {
+        unsafe {
+            (*self.as_mut_ptr()).bit_rate = value;
+        }
+    }
+
+    /// Get the frame rate of video parameters, only known by the parameters
+    /// since FFmpeg 6.1, the stream having it otherwise.
+    #[cfg(feature = "ffmpeg_6_1")]
+    pub fn frame_rate(&self) -> Option<Rational> {
+        unsafe {
+            match Rational::from((*self.as_ptr()).framerate) {
+                Rational(0, _) => None,
+                rate => Some(rate),
+            }
+        }
+    }
+
+    #[cfg(feature = "ffmpeg_6_1")]
+    pub fn set_frame_rate<R: Into<Rational>>(&mut self, value: Option<R>) {
+        unsafe {
+            (*self.as_mut_ptr()).framerate = match value.map(Into::into) {
+                Some(Rational(num, den)) => AVRational { num, den },
+                None => AVRational { num: 0, den: 1 },
+            };
+        }
+    }
+
+    /// Get the sample rate of audio parameters.
+    pub fn rate(&self) -> u32 {
+        unsafe { (*self.as_ptr()).sample_rate as u32 }
+    }
+
+    pub fn set_rate(&mut self, value: u32) {
+        unsafe {
+            (*self.as_mut_ptr()).sample_rate = value as c_int;
+        }
+    }
+
+    /// Get the main properties of the stream, those of other media types
+    /// being zero or `None`.
+    pub fn summary(&self) -> Summary {
+        unsafe {
+            let ptr = self.as_ptr();
+            let medium = self.medium();
+
+            let pixel_format = match medium {
+                media::Type::Video if (*ptr).format >= 0 => Some(format::Pixel::from(
+                    mem::transmute::<c_int, AVPixelFormat>((*ptr).format),
+                )),
+                _ => None,
+            };
+
+            let sample_format = match medium {
+                media::Type::Audio if (*ptr).format >= 0 => Some(format::Sample::from(
+                    mem::transmute::<c_int, AVSampleFormat>((*ptr).format),
+                )),
+                _ => None,
+            };
+
+            let profile = avcodec_profile_name((*ptr).codec_id, (*ptr).profile);
+
+            Summary {
+                medium,
+                id: self.id(),
+                profile: if profile.is_null() {
+                    None
+                } else {
+                    Some(from_utf8_unchecked(CStr::from_ptr(profile).to_bytes()))
+                },
+                bit_rate: (*ptr).bit_rate,
+
+                width: (*ptr).width as u32,
+                height: (*ptr).height as u32,
+                pixel_format,
+
+                sample_rate: (*ptr).sample_rate as u32,
+                channels: self.channels(),
+                channel_layout: self.channel_layout(),
+                sample_format,
+            }
+        }
+    }
+
+    #[cfg(not(feature = "ffmpeg_5_1"))]
+    pub fn channels(&self) -> u16 {
+        unsafe { (*self.as_ptr()).channels as u16 }
+    }
+
+    #[cfg(feature = "ffmpeg_5_1")]
+    pub fn channels(&self) -> u16 {
+        unsafe { (*self.as_ptr()).ch_layout.nb_channels as u16 }
+    }
+
+    /// Get the channel layout of audio parameters, `None` if unknown or not
+    /// a native one.
+    #[cfg(not(feature = "ffmpeg_5_1"))]
+    pub fn channel_layout(&self) -> Option<ChannelLayout> {
+        match unsafe { (*self.as_ptr()).channel_layout } {
+            0 => None,
+            bits => Some(ChannelLayout::from_bits_truncate(bits)),
+        }
+    }
+
+    /// Get the channel layout of audio parameters, `None` if unknown or not
+    /// a native one.
+    #[cfg(feature = "ffmpeg_5_1")]
+    pub fn channel_layout(&self) -> Option<ChannelLayout> {
+        unsafe {
+            let layout = &(*self.as_ptr()).ch_layout;
+
+            if layout.order == AVChannelOrder::AV_CHANNEL_ORDER_NATIVE && layout.u.mask != 0 {
+                Some(ChannelLayout::from_bits_truncate(layout.u.mask))
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Set the channel layout, and the number of channels from it.
+    #[cfg(not(feature = "ffmpeg_5_1"))]
+    pub fn set_channel_layout(&mut self, value: ChannelLayout) {
+        unsafe {
+            (*self.as_mut_ptr()).channel_layout = value.bits();
+            (*self.as_mut_ptr()).channels = value.channels();
+        }
+    }
+
+    /// Set the channel layout, and the number of channels from it.
+    #[cfg(feature = "ffmpeg_5_1")]
+    pub fn set_channel_layout(&mut self, value: ChannelLayout) {
+        unsafe {
+            // a native layout is copied without allocating, it cannot fail
+            av_channel_layout_copy(
+                &mut (*self.as_mut_ptr()).ch_layout,
+                Layout::native(value).as_ptr(),
+            );
+        }
+    }
+
+    /// Get the global headers of the codec, e.g. the SPS/PPS of H.264 or the
+    /// AudioSpecificConfig of AAC.
+    pub fn extradata(&self) -> &[u8] {
+        unsafe {
+            let ptr = self.as_ptr();
+
+            if (*ptr).extradata.is_null() || (*ptr).extradata_size <= 0 {
+                &[]
+            } else {
+                slice::from_raw_parts((*ptr).extradata, (*ptr).extradata_size as usize)
+            }
+        }
+    }
+
+    /// Set the global headers of the codec, replacing the previous ones.
+    pub fn set_extradata(&mut self, data: &[u8]) -> Result<(), Error> {
+        unsafe {
+            let ptr = self.as_mut_ptr();
+            let extradata =
+                av_mallocz(data.len() + AV_INPUT_BUFFER_PADDING_SIZE as usize) as *mut u8;
+
+            if extradata.is_null() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            ptr::copy_nonoverlapping(data.as_ptr(), extradata, data.len());
+
+            av_freep(&mut (*ptr).extradata as *mut *mut u8 as *mut c_void);
+            (*ptr).extradata = extradata;
+            (*ptr).extradata_size = data.len() as c_int;
+
+            Ok(())
+        }
+    }
 }
 
 impl Default for Parameters {
@@ -85,3 +383,82 @@ impl<C: AsRef<Context>> From<C> for Parameters {
         parameters
     }
 }
+
+/// Build the parameters of a stream, e.g. of an output stream whose packets
+/// are copied or encoded elsewhere.
+pub struct Builder {
+    parameters: Parameters,
+    extradata: Option<Vec<u8>>,
+}
+
+impl Builder {
+    pub fn new(id: Id) -> Self {
+        let mut parameters = Parameters::new();
+        parameters.set_medium(id.medium());
+        parameters.set_id(id);
+
+        Builder {
+            parameters,
+            extradata: None,
+        }
+    }
+
+    pub fn width(mut self, value: u32) -> Self {
+        self.parameters.set_width(value);
+        self
+    }
+
+    pub fn height(mut self, value: u32) -> Self {
+        self.parameters.set_height(value);
+        self
+    }
+
+    pub fn pixel_format(mut self, value: format::Pixel) -> Self {
+        self.parameters.set_pixel_format(value);
+        self
+    }
+
+    pub fn sample_format(mut self, value: format::Sample) -> Self {
+        self.parameters.set_sample_format(value);
+        self
+    }
+
+    pub fn bit_rate(mut self, value: i64) -> Self {
+        self.parameters.set_bit_rate(value);
+        self
+    }
+
+    #[cfg(feature = "ffmpeg_6_1")]
+    pub fn frame_rate<R: Into<Rational>>(mut self, value: R) -> Self {
+        self.parameters.set_frame_rate(Some(value));
+        self
+    }
+
+    pub fn rate(mut self, value: u32) -> Self {
+        self.parameters.set_rate(value);
+        self
+    }
+
+    pub fn channel_layout(mut self, value: ChannelLayout) -> Self {
+        self.parameters.set_channel_layout(value);
+        self
+    }
+
+    pub fn extradata(mut self, value: &[u8]) -> Self {
+        self.extradata = Some(value.to_vec());
+        self
+    }
+
+    pub fn build(self) -> Result<Parameters, Error> {
+        let Builder {
+            mut parameters,
+            extradata,
+        } = self;
+
+        if let Some(data) = extradata {
+            parameters.set_extradata(&data)?;
+        }
+
+        Ok(parameters)
+    }
+}
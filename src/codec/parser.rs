@@ -0,0 +1,141 @@
+//! Splitting of raw elementary streams (e.g. Annex-B H.264, ADTS AAC) into
+//! packets, for inputs not going through a demuxer.
+
+use std::ptr;
+use std::slice;
+
+use super::packet::{Flags, Packet};
+use super::{Context, Id};
+use ffi::*;
+use libc::c_int;
+
+pub struct Parser {
+    ptr: *mut AVCodecParserContext,
+}
+
+unsafe impl Send for Parser {}
+
+impl Parser {
+    pub unsafe fn as_ptr(&self) -> *const AVCodecParserContext {
+        self.ptr as *const _
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVCodecParserContext {
+        self.ptr
+    }
+}
+
+impl Parser {
+    /// Create the parser of a codec, `None` if it has none.
+    pub fn new(id: Id) -> Option<Self> {
+        unsafe {
+            let id: AVCodecID = id.into();
+            let ptr = av_parser_init(id as c_int);
+
+            if ptr.is_null() {
+                None
+            } else {
+                Some(Parser { ptr })
+            }
+        }
+    }
+
+    /// Parse the beginning of `data`, returning the number of bytes consumed
+    /// and the packet completed by them, if any.
+    ///
+    /// The timestamps and position are those of `data`, they are given to
+    /// the packet starting in it. The context is the one of the decoder,
+    /// some parsers filling it with the parameters of the stream.
+    pub fn parse(
+        &mut self,
+        context: &mut Context,
+        data: &[u8],
+        pts: Option<i64>,
+        dts: Option<i64>,
+        position: i64,
+    ) -> (usize, Option<Packet>) {
+        unsafe {
+            let mut out = ptr::null_mut();
+            let mut size = 0;
+
+            let consumed = av_parser_parse2(
+                self.as_mut_ptr(),
+                context.as_mut_ptr(),
+                &mut out,
+                &mut size,
+                if data.is_empty() {
+                    ptr::null()
+                } else {
+                    data.as_ptr()
+                },
+                data.len() as c_int,
+                pts.unwrap_or(AV_NOPTS_VALUE),
+                dts.unwrap_or(AV_NOPTS_VALUE),
+                position,
+            );
+
+            (consumed.max(0) as usize, self.packet(out, size))
+        }
+    }
+
+    /// Parse all of `data`, returning the packets completed by it.
+    pub fn split(&mut self, context: &mut Context, mut data: &[u8]) -> Vec<Packet> {
+        let mut packets = Vec::new();
+
+        while !data.is_empty() {
+            let (consumed, packet) = self.parse(context, data, None, None, -1);
+
+            if consumed == 0 && packet.is_none() {
+                break;
+            }
+
+            packets.extend(packet);
+
+            data = &data[consumed..];
+        }
+
+        packets
+    }
+
+    /// Get the last packet buffered by the parser, at the end of the stream.
+    pub fn flush(&mut self, context: &mut Context) -> Option<Packet> {
+        self.parse(context, &[], None, None, -1).1
+    }
+
+    /// Whether the last packet is a keyframe, `None` if unknown.
+    pub fn is_key(&self) -> Option<bool> {
+        unsafe {
+            match (*self.as_ptr()).key_frame {
+                -1 => None,
+                value => Some(value == 1),
+            }
+        }
+    }
+
+    unsafe fn packet(&self, data: *mut u8, size: c_int) -> Option<Packet> {
+        if data.is_null() || size <= 0 {
+            return None;
+        }
+
+        let mut packet = Packet::copy(slice::from_raw_parts(data, size as usize));
+        let parser = &*self.as_ptr();
+
+        packet.set_pts(Some(parser.pts).filter(|&pts| pts != AV_NOPTS_VALUE));
+        packet.set_dts(Some(parser.dts).filter(|&dts| dts != AV_NOPTS_VALUE));
+        packet.set_position(parser.pos as isize);
+
+        if parser.key_frame == 1 {
+            packet.set_flags(packet.flags() | Flags::KEY);
+        }
+
+        Some(packet)
+    }
+}
+
+impl Drop for Parser {
+    fn drop(&mut self) {
+        unsafe {
+            av_parser_close(self.as_mut_ptr());
+        }
+    }
+}
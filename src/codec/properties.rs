@@ -0,0 +1,11 @@
+use ffi::*;
+use libc::c_uint;
+
+bitflags! {
+    pub struct Properties: c_uint {
+        const LOSSLESS        = FF_CODEC_PROPERTY_LOSSLESS as c_uint;
+        const CLOSED_CAPTIONS = FF_CODEC_PROPERTY_CLOSED_CAPTIONS as c_uint;
+        #[cfg(feature = "ffmpeg_4_4")]
+        const FILM_GRAIN      = FF_CODEC_PROPERTY_FILM_GRAIN as c_uint;
+    }
+}
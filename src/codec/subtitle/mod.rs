@@ -9,10 +9,12 @@ pub use self::rect_mut::{AssMut, BitmapMut, RectMut, TextMut};
 
 use std::marker::PhantomData;
 use std::mem;
+use std::ptr;
 
 use ffi::AVSubtitleType::*;
 use ffi::*;
-use libc::{c_uint, size_t};
+use libc::{c_int, c_uint, size_t, ENOMEM};
+use Error;
 
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
 pub enum Type {
@@ -113,6 +115,95 @@ impl Subtitle {
             RectMut::wrap(rect)
         }
     }
+
+    /// Add a plain text rect.
+    pub fn add_text(&mut self, text: &str) -> TextMut {
+        match self.add_rect(Type::Text) {
+            RectMut::Text(mut rect) => {
+                rect.set(text);
+                rect
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Add an ASS rect showing `text` with the default style, line breaks
+    /// being turned into `\N`.
+    ///
+    /// The rect holds an ASS dialogue event without timing, as expected by
+    /// the encoders, e.g. `0,0,Default,,0,0,0,,text`; use `AssMut::set` for
+    /// other styles and margins.
+    pub fn add_ass(&mut self, text: &str) -> AssMut {
+        let index = self.0.num_rects;
+        let line = format!(
+            "{},0,Default,,0,0,0,,{}",
+            index,
+            text.replace("\r\n", "\\N").replace('\n', "\\N")
+        );
+
+        match self.add_rect(Type::Ass) {
+            RectMut::Ass(mut rect) => {
+                rect.set(&line);
+                rect
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Add a bitmap rect at `x`, `y` of `width` by `height` palette indices,
+    /// with a palette of up to 256 ARGB colors.
+    pub fn add_bitmap(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: u32,
+        height: u32,
+        data: &[u8],
+        palette: &[u32],
+    ) -> Result<BitmapMut, Error> {
+        // the line size and dimensions are stored as `c_int`
+        if width > c_int::max_value() as u32 || height > c_int::max_value() as u32 {
+            return Err(Error::InvalidData);
+        }
+
+        let size = (width as usize)
+            .checked_mul(height as usize)
+            .ok_or(Error::InvalidData)?;
+
+        if data.len() < size || palette.len() > 256 {
+            return Err(Error::InvalidData);
+        }
+
+        let mut rect = match self.add_rect(Type::Bitmap) {
+            RectMut::Bitmap(rect) => rect,
+            _ => unreachable!(),
+        };
+
+        rect.set_x(x);
+        rect.set_y(y);
+        rect.set_width(width);
+        rect.set_height(height);
+        rect.set_colors(palette.len());
+
+        unsafe {
+            let ptr = rect.as_mut_ptr();
+
+            (*ptr).data[0] = av_malloc(size.max(1) as size_t) as *mut u8;
+            (*ptr).data[1] = av_mallocz(AVPALETTE_SIZE as size_t) as *mut u8;
+
+            if (*ptr).data[0].is_null() || (*ptr).data[1].is_null() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            ptr::copy_nonoverlapping(data.as_ptr(), (*ptr).data[0], size);
+            ptr::copy_nonoverlapping(palette.as_ptr(), (*ptr).data[1] as *mut u32, palette.len());
+
+            (*ptr).linesize[0] = width as c_int;
+            (*ptr).linesize[1] = AVPALETTE_SIZE as c_int;
+        }
+
+        Ok(rect)
+    }
 }
 
 impl Default for Subtitle {
@@ -121,6 +212,14 @@ impl Default for Subtitle {
     }
 }
 
+impl Drop for Subtitle {
+    fn drop(&mut self) {
+        unsafe {
+            avsubtitle_free(&mut self.0);
+        }
+    }
+}
+
 pub struct RectIter<'a> {
     ptr: *const AVSubtitle,
     cur: c_uint,
@@ -1,9 +1,11 @@
 use std::ffi::CStr;
 use std::marker::PhantomData;
+use std::slice;
 use std::str::from_utf8_unchecked;
 
 use super::{Flags, Type};
 use ffi::*;
+#[cfg(not(feature = "ffmpeg_5_0"))]
 use {format, Picture};
 
 pub enum Rect<'a> {
@@ -86,7 +88,68 @@ impl<'a> Bitmap<'a> {
         unsafe { (*self.as_ptr()).nb_colors as usize }
     }
 
+    /// Get the size in bytes of a line of `data`.
+    pub fn stride(&self) -> usize {
+        unsafe { (*self.as_ptr()).linesize[0].max(0) as usize }
+    }
+
+    /// Get the palette indices of the pixels, line by line.
+    pub fn data(&self) -> &[u8] {
+        unsafe {
+            let ptr = (*self.as_ptr()).data[0];
+
+            if ptr.is_null() {
+                &[]
+            } else {
+                slice::from_raw_parts(ptr, self.stride() * self.height() as usize)
+            }
+        }
+    }
+
+    /// Get the palette, as ARGB colors in native endianness.
+    pub fn palette(&self) -> &[u32] {
+        unsafe {
+            let ptr = (*self.as_ptr()).data[1];
+
+            if ptr.is_null() {
+                &[]
+            } else {
+                slice::from_raw_parts(ptr as *const u32, self.colors().min(256))
+            }
+        }
+    }
+
+    /// Get the color of the pixel at `x`, `y`, as ARGB.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<u32> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+
+        let index = *self.data().get(y as usize * self.stride() + x as usize)?;
+        self.palette().get(index as usize).cloned()
+    }
+
+    /// Render the bitmap to packed RGBA, e.g. for display or OCR.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        let mut rgba = Vec::with_capacity(self.width() as usize * self.height() as usize * 4);
+
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let argb = self.pixel(x, y).unwrap_or(0);
+                rgba.extend_from_slice(&[
+                    (argb >> 16) as u8,
+                    (argb >> 8) as u8,
+                    argb as u8,
+                    (argb >> 24) as u8,
+                ]);
+            }
+        }
+
+        rgba
+    }
+
     // XXX: must split Picture and PictureMut
+    #[cfg(not(feature = "ffmpeg_5_0"))]
     pub fn picture(&self, format: format::Pixel) -> Picture<'a> {
         unsafe {
             Picture::wrap(
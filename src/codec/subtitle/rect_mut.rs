@@ -1,9 +1,10 @@
 use std::ffi::CString;
 use std::ops::Deref;
+use std::slice;
 
 use super::{Ass, Bitmap, Flags, Text, Type};
 use ffi::*;
-use libc::c_int;
+use libc::{c_int, c_void};
 
 pub enum RectMut<'a> {
     None(*mut AVSubtitleRect),
@@ -100,6 +101,32 @@ impl<'a> BitmapMut<'a> {
             (*self.as_mut_ptr()).nb_colors = value as c_int;
         }
     }
+
+    /// Get the palette indices of the pixels, line by line.
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        unsafe {
+            let ptr = (*self.as_mut_ptr()).data[0];
+
+            if ptr.is_null() {
+                &mut []
+            } else {
+                slice::from_raw_parts_mut(ptr, self.stride() * self.height() as usize)
+            }
+        }
+    }
+
+    /// Get the palette, as ARGB colors in native endianness.
+    pub fn palette_mut(&mut self) -> &mut [u32] {
+        unsafe {
+            let ptr = (*self.as_mut_ptr()).data[1];
+
+            if ptr.is_null() {
+                &mut []
+            } else {
+                slice::from_raw_parts_mut(ptr as *mut u32, self.colors().min(256))
+            }
+        }
+    }
 }
 
 impl<'a> Deref for BitmapMut<'a> {
@@ -131,6 +158,7 @@ impl<'a> TextMut<'a> {
         let value = CString::new(value).unwrap();
 
         unsafe {
+            av_free((*self.as_mut_ptr()).text as *mut c_void);
             (*self.as_mut_ptr()).text = av_strdup(value.as_ptr());
         }
     }
@@ -165,6 +193,7 @@ impl<'a> AssMut<'a> {
         let value = CString::new(value).unwrap();
 
         unsafe {
+            av_free((*self.as_mut_ptr()).ass as *mut c_void);
             (*self.as_mut_ptr()).ass = av_strdup(value.as_ptr());
         }
     }
@@ -1,8 +1,10 @@
+use std::cmp::Ordering;
 use std::ops::Deref;
 
 use super::codec::Codec;
 use ffi::*;
-use {format, Rational};
+use libc::c_int;
+use {format, rational, Rational};
 
 #[derive(PartialEq, Eq, Copy, Clone)]
 pub struct Video {
@@ -35,6 +37,22 @@ impl Video {
             }
         }
     }
+
+    /// Iterate over the frame rates supported by the codec.
+    ///
+    /// Unlike [rates()], a codec that accepts any frame rate yields an empty
+    /// iterator whose [best_match()] returns the requested rate unchanged.
+    ///
+    /// [rates()]: Self::rates
+    /// [best_match()]: RateIter::best_match
+    pub fn supported_framerates(&self) -> RateIter {
+        unsafe { RateIter::new((*self.codec.as_ptr()).supported_framerates) }
+    }
+
+    /// Iterate over the pixel formats supported by the codec.
+    pub fn supported_formats(&self) -> FormatIter {
+        unsafe { FormatIter::new((*self.codec.as_ptr()).pix_fmts) }
+    }
 }
 
 impl Deref for Video {
@@ -53,6 +71,22 @@ impl RateIter {
     pub fn new(ptr: *const AVRational) -> Self {
         RateIter { ptr }
     }
+
+    /// Get the supported frame rate nearest to `rate`. If the codec does
+    /// not restrict frame rates, `rate` is returned.
+    pub fn best_match<R: Into<Rational>>(self, rate: R) -> Rational {
+        let rate = rate.into();
+
+        if self.ptr.is_null() {
+            return rate;
+        }
+
+        self.fold(None, |best, cur| match best {
+            Some(best) if rational::nearer(rate, best, cur) != Ordering::Less => Some(best),
+            _ => Some(cur),
+        })
+        .unwrap_or(rate)
+    }
 }
 
 impl Iterator for RateIter {
@@ -60,7 +94,7 @@ impl Iterator for RateIter {
 
     fn next(&mut self) -> Option<<Self as Iterator>::Item> {
         unsafe {
-            if (*self.ptr).num == 0 && (*self.ptr).den == 0 {
+            if self.ptr.is_null() || ((*self.ptr).num == 0 && (*self.ptr).den == 0) {
                 return None;
             }
 
@@ -80,6 +114,33 @@ impl FormatIter {
     pub fn new(ptr: *const AVPixelFormat) -> Self {
         FormatIter { ptr }
     }
+
+    /// Get the supported format that loses the least information when
+    /// converting from `format`, as chosen by
+    /// `avcodec_find_best_pix_fmt_of_list`. If the codec does not restrict
+    /// formats, `format` is returned.
+    pub fn best_match(self, format: format::Pixel) -> format::Pixel {
+        if self.ptr.is_null() {
+            return format;
+        }
+
+        unsafe {
+            let has_alpha = format.descriptor().map_or(false, |d| {
+                (*d.as_ptr()).flags & AV_PIX_FMT_FLAG_ALPHA as u64 != 0
+            });
+            let mut loss = 0;
+
+            match avcodec_find_best_pix_fmt_of_list(
+                self.ptr,
+                format.into(),
+                has_alpha as c_int,
+                &mut loss,
+            ) {
+                AVPixelFormat::AV_PIX_FMT_NONE => format,
+                best => format::Pixel::from(best),
+            }
+        }
+    }
 }
 
 impl Iterator for FormatIter {
@@ -87,7 +148,7 @@ impl Iterator for FormatIter {
 
     fn next(&mut self) -> Option<<Self as Iterator>::Item> {
         unsafe {
-            if *self.ptr == AVPixelFormat::AV_PIX_FMT_NONE {
+            if self.ptr.is_null() || *self.ptr == AVPixelFormat::AV_PIX_FMT_NONE {
                 return None;
             }
 
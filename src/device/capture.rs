@@ -0,0 +1,215 @@
+//! Opening of capture devices (cameras, microphones, screens) without
+//! writing backend-specific URLs and options by hand.
+//!
+//! `device::register_all()` must have been called.
+
+use super::input;
+use format::{self, context};
+use util::format::Pixel;
+use {Dictionary, Error, Format, Rational};
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Backend {
+    /// Video4Linux2 cameras, the device is a path such as `/dev/video0`.
+    V4L2,
+    /// ALSA sound cards, the device is a name such as `hw:0`.
+    ALSA,
+    /// PulseAudio sources, the device is a source name or `default`.
+    Pulse,
+    /// X11 screens, the device is a display such as `:0.0`.
+    X11Grab,
+    /// macOS cameras, microphones and screens, by name or index.
+    AVFoundation,
+    /// DirectShow cameras and microphones, by name.
+    DShow,
+    /// Windows screens, the device is `desktop` or `title=<window title>`.
+    GDIGrab,
+}
+
+impl Backend {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Backend::V4L2 => "v4l2",
+            Backend::ALSA => "alsa",
+            Backend::Pulse => "pulse",
+            Backend::X11Grab => "x11grab",
+            Backend::AVFoundation => "avfoundation",
+            Backend::DShow => "dshow",
+            Backend::GDIGrab => "gdigrab",
+        }
+    }
+
+    /// Check whether the linked FFmpeg build provides the backend.
+    pub fn is_available(&self) -> bool {
+        input::find(self.name()).is_some()
+    }
+}
+
+pub struct Builder {
+    backend: Backend,
+    video: Option<String>,
+    audio: Option<String>,
+
+    size: Option<(u32, u32)>,
+    rate: Option<Rational>,
+    format: Option<Pixel>,
+    sample_rate: Option<u32>,
+    channels: Option<u16>,
+
+    options: Vec<(String, String)>,
+}
+
+impl Builder {
+    pub fn new(backend: Backend) -> Self {
+        Builder {
+            backend,
+            video: None,
+            audio: None,
+
+            size: None,
+            rate: None,
+            format: None,
+            sample_rate: None,
+            channels: None,
+
+            options: Vec::new(),
+        }
+    }
+
+    /// Set the video device (camera or screen).
+    pub fn video<S: Into<String>>(mut self, device: S) -> Self {
+        self.video = Some(device.into());
+        self
+    }
+
+    /// Set the audio device, backends that capture both at once
+    /// (`AVFoundation` and `DShow`) open them together.
+    pub fn audio<S: Into<String>>(mut self, device: S) -> Self {
+        self.audio = Some(device.into());
+        self
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+
+    pub fn rate<R: Into<Rational>>(mut self, rate: R) -> Self {
+        self.rate = Some(rate.into());
+        self
+    }
+
+    pub fn format(mut self, format: Pixel) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    pub fn sample_rate(mut self, rate: u32) -> Self {
+        self.sample_rate = Some(rate);
+        self
+    }
+
+    pub fn channels(mut self, channels: u16) -> Self {
+        self.channels = Some(channels);
+        self
+    }
+
+    /// Set a backend-specific option.
+    pub fn option<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.options.push((key.into(), value.into()));
+        self
+    }
+
+    /// Get the URL of the devices, as expected by the backend.
+    pub fn url(&self) -> Result<String, Error> {
+        match (self.backend, &self.video, &self.audio) {
+            (_, &None, &None) => Err(Error::InvalidData),
+
+            (Backend::DShow, video, audio) => Ok(video
+                .iter()
+                .map(|v| format!("video={}", v))
+                .chain(audio.iter().map(|a| format!("audio={}", a)))
+                .collect::<Vec<_>>()
+                .join(":")),
+
+            (Backend::AVFoundation, video, audio) => Ok(format!(
+                "{}:{}",
+                video.as_ref().map_or("none", |v| v),
+                audio.as_ref().map_or("none", |a| a)
+            )),
+
+            (_, &Some(_), &Some(_)) => Err(Error::InvalidData),
+            (_, &Some(ref device), &None) | (_, &None, &Some(ref device)) => Ok(device.clone()),
+        }
+    }
+
+    /// Get the demuxer options for the typed settings.
+    pub fn options(&self) -> Dictionary<'static> {
+        let mut options = Dictionary::new();
+
+        if let Some((width, height)) = self.size {
+            options.set("video_size", &format!("{}x{}", width, height));
+        }
+
+        if let Some(rate) = self.rate {
+            options.set("framerate", &rate.to_string());
+        }
+
+        if let Some(format) = self.format {
+            let key = match self.backend {
+                Backend::V4L2 => "input_format",
+                _ => "pixel_format",
+            };
+
+            options.set(key, format.descriptor().map_or("", |d| d.name()));
+        }
+
+        if let Some(rate) = self.sample_rate {
+            options.set("sample_rate", &rate.to_string());
+        }
+
+        if let Some(channels) = self.channels {
+            options.set("channels", &channels.to_string());
+        }
+
+        for &(ref key, ref value) in &self.options {
+            options.set(key, value);
+        }
+
+        options
+    }
+
+    /// Open the devices.
+    pub fn open(&self) -> Result<context::Input, Error> {
+        let format = input::find(self.backend.name()).ok_or(Error::DemuxerNotFound)?;
+
+        match format::open_with(&self.url()?, &Format::Input(format), self.options())? {
+            context::Context::Input(input) => Ok(input),
+            _ => Err(Error::Bug),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url() {
+        let builder = Builder::new(Backend::DShow)
+            .video("Integrated Camera")
+            .audio("Microphone");
+        assert_eq!(
+            builder.url().unwrap(),
+            "video=Integrated Camera:audio=Microphone"
+        );
+
+        let builder = Builder::new(Backend::AVFoundation).video("0");
+        assert_eq!(builder.url().unwrap(), "0:none");
+
+        let builder = Builder::new(Backend::V4L2).video("/dev/video0");
+        assert_eq!(builder.url().unwrap(), "/dev/video0");
+
+        assert!(Builder::new(Backend::V4L2).url().is_err());
+    }
+}
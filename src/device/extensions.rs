@@ -27,11 +27,17 @@ impl<'a> DeviceIter<'a> {
         match avdevice_list_devices(ctx as *mut _, &mut ptr) {
             n if n < 0 => Err(Error::from(n)),
 
-            _ => Ok(DeviceIter {
-                ptr,
-                cur: 0,
-                _marker: PhantomData,
-            }),
+            _ => Ok(DeviceIter::own(ptr)),
+        }
+    }
+
+    /// Take ownership of a device list, e.g. from
+    /// `avdevice_list_input_sources`.
+    pub unsafe fn own(ptr: *mut AVDeviceInfoList) -> Self {
+        DeviceIter {
+            ptr,
+            cur: 0,
+            _marker: PhantomData,
         }
     }
 }
@@ -1,8 +1,10 @@
+use std::ffi::CString;
 use std::ptr;
 
+use device::extensions::DeviceIter;
 use ffi::*;
 use format;
-use Format;
+use {Dictionary, Error, Format};
 
 pub struct AudioIter(*mut AVInputFormat);
 
@@ -11,7 +13,7 @@ impl Iterator for AudioIter {
 
     fn next(&mut self) -> Option<<Self as Iterator>::Item> {
         unsafe {
-            let ptr = av_input_audio_device_next(self.0);
+            let ptr = av_input_audio_device_next(self.0) as *mut _;
 
             if ptr.is_null() && !self.0.is_null() {
                 None
@@ -35,7 +37,7 @@ impl Iterator for VideoIter {
 
     fn next(&mut self) -> Option<<Self as Iterator>::Item> {
         unsafe {
-            let ptr = av_input_video_device_next(self.0);
+            let ptr = av_input_video_device_next(self.0) as *mut _;
 
             if ptr.is_null() && !self.0.is_null() {
                 None
@@ -51,3 +53,47 @@ impl Iterator for VideoIter {
 pub fn video() -> VideoIter {
     VideoIter(ptr::null_mut())
 }
+
+/// Find the input format named `name`, e.g. `v4l2` or `dshow`.
+pub fn find(name: &str) -> Option<format::Input> {
+    unsafe {
+        let name = CString::new(name).ok()?;
+        let ptr = av_find_input_format(name.as_ptr());
+
+        if ptr.is_null() {
+            None
+        } else {
+            Some(format::Input::wrap(ptr as *mut _))
+        }
+    }
+}
+
+/// List the capture devices (cameras, microphones, screens) of the `format` device, e.g. `dshow`, `avfoundation`,
+/// `v4l2` or `alsa`, optionally restricted to the `device` name.
+pub fn sources<'a>(
+    format: &format::Input,
+    device: Option<&str>,
+    options: Dictionary,
+) -> Result<DeviceIter<'a>, Error> {
+    unsafe {
+        let device = match device {
+            Some(device) => Some(CString::new(device).map_err(|_| Error::InvalidData)?),
+            None => None,
+        };
+        let mut ptr = ptr::null_mut();
+        let opts = options.disown();
+        let res = avdevice_list_input_sources(
+            format.as_ptr() as *mut _,
+            device.as_ref().map_or(ptr::null(), |d| d.as_ptr()),
+            opts,
+            &mut ptr,
+        );
+
+        Dictionary::own(opts);
+
+        match res {
+            e if e < 0 => Err(Error::from(e)),
+            _ => Ok(DeviceIter::own(ptr)),
+        }
+    }
+}
@@ -0,0 +1,347 @@
+//! Control messages between the application and interactive devices, such as
+//! pausing a capture or resizing the window of a playback device.
+//!
+//! Commands are sent with `Context::control`, while the devices report
+//! events, including the answers to `Command::GetVolume` and
+//! `Command::GetMute`, to the handler set with `Context::set_message_handler`.
+
+use std::mem;
+use std::panic;
+use std::process;
+use std::ptr;
+
+use ffi::AVAppToDevMessageType::*;
+use ffi::AVDevToAppMessageType::*;
+use ffi::*;
+use format::context::common::Context;
+use libc::{c_int, c_void, EBUSY, ENOSYS};
+use Error;
+
+type Handler = Box<dyn Fn(Event) -> Result<(), Error> + Send + Sync>;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+impl From<AVDeviceRect> for Rect {
+    fn from(value: AVDeviceRect) -> Self {
+        Rect::new(value.x, value.y, value.width, value.height)
+    }
+}
+
+impl From<Rect> for AVDeviceRect {
+    fn from(value: Rect) -> Self {
+        AVDeviceRect {
+            x: value.x,
+            y: value.y,
+            width: value.width,
+            height: value.height,
+        }
+    }
+}
+
+/// A message from the application to the device.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Command {
+    /// The window the device renders to has been resized.
+    WindowSize(Rect),
+    /// Repaint the window, or the given area of it.
+    WindowRepaint(Option<Rect>),
+
+    Pause,
+    Play,
+    TogglePause,
+
+    /// Set the volume, from 0.0 to 1.0.
+    SetVolume(f64),
+    Mute,
+    Unmute,
+    ToggleMute,
+
+    /// Ask for an `Event::VolumeLevelChanged`.
+    GetVolume,
+    /// Ask for an `Event::MuteStateChanged`.
+    GetMute,
+}
+
+/// A message from the device to the application.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum Event {
+    /// Create a window buffer, at the given position and size if specified.
+    CreateWindowBuffer(Option<Rect>),
+    /// Make the window buffer current before rendering.
+    PrepareWindowBuffer,
+    /// Display the rendered window buffer.
+    DisplayWindowBuffer,
+    DestroyWindowBuffer,
+
+    BufferOverflow,
+    BufferUnderflow,
+    /// Data is available to read, the number of bytes if known.
+    BufferReadable(Option<i64>),
+    /// Space is available to write, the number of bytes if known.
+    BufferWritable(Option<i64>),
+
+    MuteStateChanged(bool),
+    /// The volume changed, from 0.0 to 1.0.
+    VolumeLevelChanged(f64),
+}
+
+impl Context {
+    /// Send a command to the device.
+    ///
+    /// Fails with `ENOSYS` when the device does not support it.
+    pub fn control(&mut self, command: Command) -> Result<(), Error> {
+        let mut rect: AVDeviceRect;
+        let mut volume: f64;
+
+        let (kind, data, size) = match command {
+            Command::WindowSize(value) => {
+                rect = value.into();
+                (
+                    AV_APP_TO_DEV_WINDOW_SIZE,
+                    &mut rect as *mut _ as *mut c_void,
+                    mem::size_of::<AVDeviceRect>(),
+                )
+            }
+
+            Command::WindowRepaint(Some(value)) => {
+                rect = value.into();
+                (
+                    AV_APP_TO_DEV_WINDOW_REPAINT,
+                    &mut rect as *mut _ as *mut c_void,
+                    mem::size_of::<AVDeviceRect>(),
+                )
+            }
+
+            Command::WindowRepaint(None) => (AV_APP_TO_DEV_WINDOW_REPAINT, ptr::null_mut(), 0),
+
+            Command::Pause => (AV_APP_TO_DEV_PAUSE, ptr::null_mut(), 0),
+            Command::Play => (AV_APP_TO_DEV_PLAY, ptr::null_mut(), 0),
+            Command::TogglePause => (AV_APP_TO_DEV_TOGGLE_PAUSE, ptr::null_mut(), 0),
+
+            Command::SetVolume(value) => {
+                volume = value;
+                (
+                    AV_APP_TO_DEV_SET_VOLUME,
+                    &mut volume as *mut _ as *mut c_void,
+                    mem::size_of::<f64>(),
+                )
+            }
+
+            Command::Mute => (AV_APP_TO_DEV_MUTE, ptr::null_mut(), 0),
+            Command::Unmute => (AV_APP_TO_DEV_UNMUTE, ptr::null_mut(), 0),
+            Command::ToggleMute => (AV_APP_TO_DEV_TOGGLE_MUTE, ptr::null_mut(), 0),
+
+            Command::GetVolume => (AV_APP_TO_DEV_GET_VOLUME, ptr::null_mut(), 0),
+            Command::GetMute => (AV_APP_TO_DEV_GET_MUTE, ptr::null_mut(), 0),
+        };
+
+        unsafe {
+            match avdevice_app_to_dev_control_message(self.as_mut_ptr(), kind, data, size as _) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Set the handler of the events sent by the device, replacing the
+    /// previous one.
+    ///
+    /// The handler is owned by the context and may be called from the threads
+    /// of the device. It is reached through the `opaque` field of the context,
+    /// so this fails with `EBUSY` when the field is already in use, e.g. by an
+    /// `hls::Writer`.
+    pub fn set_message_handler<F>(&mut self, handler: F) -> Result<(), Error>
+    where
+        F: Fn(Event) -> Result<(), Error> + Send + Sync + 'static,
+    {
+        unsafe {
+            let dtor = self.destructor();
+            let ptr = self.as_mut_ptr();
+
+            if !(*ptr).opaque.is_null() && (*ptr).opaque != dtor.opaque() {
+                return Err(Error::Other { errno: EBUSY });
+            }
+
+            let handler: Box<Handler> = Box::new(Box::new(handler));
+            let handler = Box::into_raw(handler) as *mut c_void;
+
+            (*ptr).opaque = handler;
+            (*ptr).control_message_cb = Some(callback);
+            dtor.set_opaque(Some((handler, free)));
+        }
+
+        Ok(())
+    }
+
+    /// Remove the handler of the events sent by the device.
+    pub fn remove_message_handler(&mut self) {
+        unsafe {
+            let dtor = self.destructor();
+            let ptr = self.as_mut_ptr();
+
+            if !(*ptr).opaque.is_null() && (*ptr).opaque == dtor.opaque() {
+                (*ptr).opaque = ptr::null_mut();
+                (*ptr).control_message_cb = None;
+                dtor.set_opaque(None);
+            }
+        }
+    }
+
+    /// Send an event to the application on behalf of the device, calling
+    /// the handler if one is set.
+    pub fn notify(&mut self, event: Event) -> Result<(), Error> {
+        let mut rect: AVDeviceRect;
+        let mut bytes: i64;
+        let mut state: c_int;
+        let mut volume: f64;
+
+        let (kind, data, size) = match event {
+            Event::CreateWindowBuffer(Some(value)) => {
+                rect = value.into();
+                (
+                    AV_DEV_TO_APP_CREATE_WINDOW_BUFFER,
+                    &mut rect as *mut _ as *mut c_void,
+                    mem::size_of::<AVDeviceRect>(),
+                )
+            }
+
+            Event::CreateWindowBuffer(None) => {
+                (AV_DEV_TO_APP_CREATE_WINDOW_BUFFER, ptr::null_mut(), 0)
+            }
+
+            Event::PrepareWindowBuffer => (AV_DEV_TO_APP_PREPARE_WINDOW_BUFFER, ptr::null_mut(), 0),
+            Event::DisplayWindowBuffer => (AV_DEV_TO_APP_DISPLAY_WINDOW_BUFFER, ptr::null_mut(), 0),
+            Event::DestroyWindowBuffer => (AV_DEV_TO_APP_DESTROY_WINDOW_BUFFER, ptr::null_mut(), 0),
+
+            Event::BufferOverflow => (AV_DEV_TO_APP_BUFFER_OVERFLOW, ptr::null_mut(), 0),
+            Event::BufferUnderflow => (AV_DEV_TO_APP_BUFFER_UNDERFLOW, ptr::null_mut(), 0),
+
+            Event::BufferReadable(Some(value)) => {
+                bytes = value;
+                (
+                    AV_DEV_TO_APP_BUFFER_READABLE,
+                    &mut bytes as *mut _ as *mut c_void,
+                    mem::size_of::<i64>(),
+                )
+            }
+
+            Event::BufferReadable(None) => (AV_DEV_TO_APP_BUFFER_READABLE, ptr::null_mut(), 0),
+
+            Event::BufferWritable(Some(value)) => {
+                bytes = value;
+                (
+                    AV_DEV_TO_APP_BUFFER_WRITABLE,
+                    &mut bytes as *mut _ as *mut c_void,
+                    mem::size_of::<i64>(),
+                )
+            }
+
+            Event::BufferWritable(None) => (AV_DEV_TO_APP_BUFFER_WRITABLE, ptr::null_mut(), 0),
+
+            Event::MuteStateChanged(value) => {
+                state = value as c_int;
+                (
+                    AV_DEV_TO_APP_MUTE_STATE_CHANGED,
+                    &mut state as *mut _ as *mut c_void,
+                    mem::size_of::<c_int>(),
+                )
+            }
+
+            Event::VolumeLevelChanged(value) => {
+                volume = value;
+                (
+                    AV_DEV_TO_APP_VOLUME_LEVEL_CHANGED,
+                    &mut volume as *mut _ as *mut c_void,
+                    mem::size_of::<f64>(),
+                )
+            }
+        };
+
+        unsafe {
+            match avdevice_dev_to_app_control_message(self.as_mut_ptr(), kind, data, size as _) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+}
+
+unsafe fn event(kind: c_int, data: *mut c_void, size: usize) -> Option<Event> {
+    unsafe fn read<T: Copy>(data: *mut c_void, size: usize) -> Option<T> {
+        if data.is_null() || size < mem::size_of::<T>() {
+            None
+        } else {
+            Some(*(data as *const T))
+        }
+    }
+
+    let event = match kind {
+        k if k == AV_DEV_TO_APP_CREATE_WINDOW_BUFFER as c_int => {
+            Event::CreateWindowBuffer(read::<AVDeviceRect>(data, size).map(Rect::from))
+        }
+        k if k == AV_DEV_TO_APP_PREPARE_WINDOW_BUFFER as c_int => Event::PrepareWindowBuffer,
+        k if k == AV_DEV_TO_APP_DISPLAY_WINDOW_BUFFER as c_int => Event::DisplayWindowBuffer,
+        k if k == AV_DEV_TO_APP_DESTROY_WINDOW_BUFFER as c_int => Event::DestroyWindowBuffer,
+
+        k if k == AV_DEV_TO_APP_BUFFER_OVERFLOW as c_int => Event::BufferOverflow,
+        k if k == AV_DEV_TO_APP_BUFFER_UNDERFLOW as c_int => Event::BufferUnderflow,
+        k if k == AV_DEV_TO_APP_BUFFER_READABLE as c_int => {
+            Event::BufferReadable(read::<i64>(data, size))
+        }
+        k if k == AV_DEV_TO_APP_BUFFER_WRITABLE as c_int => {
+            Event::BufferWritable(read::<i64>(data, size))
+        }
+
+        k if k == AV_DEV_TO_APP_MUTE_STATE_CHANGED as c_int => {
+            Event::MuteStateChanged(read::<c_int>(data, size)? != 0)
+        }
+        k if k == AV_DEV_TO_APP_VOLUME_LEVEL_CHANGED as c_int => {
+            Event::VolumeLevelChanged(read::<f64>(data, size)?)
+        }
+
+        _ => return None,
+    };
+
+    Some(event)
+}
+
+unsafe fn free(handler: *mut c_void) {
+    drop(Box::from_raw(handler as *mut Handler));
+}
+
+unsafe extern "C" fn callback(
+    ctx: *mut AVFormatContext,
+    kind: c_int,
+    data: *mut c_void,
+    size: usize,
+) -> c_int {
+    let handler = (*ctx).opaque as *mut Handler;
+
+    let event = match event(kind, data, size) {
+        Some(event) if !handler.is_null() => event,
+        _ => return AVERROR(ENOSYS),
+    };
+
+    match panic::catch_unwind(panic::AssertUnwindSafe(|| (*handler)(event))) {
+        Ok(Ok(())) => 0,
+        Ok(Err(e)) => e.into(),
+        Err(_) => process::abort(),
+    }
+}
@@ -1,5 +1,7 @@
+pub mod capture;
 pub mod extensions;
 pub mod input;
+pub mod message;
 pub mod output;
 
 use std::ffi::CStr;
@@ -1,8 +1,10 @@
+use std::ffi::CString;
 use std::ptr;
 
+use device::extensions::DeviceIter;
 use ffi::*;
 use format;
-use Format;
+use {Dictionary, Error, Format};
 
 pub struct AudioIter(*mut AVOutputFormat);
 
@@ -11,7 +13,7 @@ impl Iterator for AudioIter {
 
     fn next(&mut self) -> Option<<Self as Iterator>::Item> {
         unsafe {
-            let ptr = av_output_audio_device_next(self.0);
+            let ptr = av_output_audio_device_next(self.0) as *mut _;
 
             if ptr.is_null() && !self.0.is_null() {
                 None
@@ -35,7 +37,7 @@ impl Iterator for VideoIter {
 
     fn next(&mut self) -> Option<<Self as Iterator>::Item> {
         unsafe {
-            let ptr = av_output_video_device_next(self.0);
+            let ptr = av_output_video_device_next(self.0) as *mut _;
 
             if ptr.is_null() && !self.0.is_null() {
                 None
@@ -51,3 +53,47 @@ impl Iterator for VideoIter {
 pub fn video() -> VideoIter {
     VideoIter(ptr::null_mut())
 }
+
+/// Find the output format named `name`, e.g. `alsa` or `pulse`.
+pub fn find(name: &str) -> Option<format::Output> {
+    unsafe {
+        let name = CString::new(name).ok()?;
+        let ptr = av_guess_format(name.as_ptr(), ptr::null(), ptr::null());
+
+        if ptr.is_null() {
+            None
+        } else {
+            Some(format::Output::wrap(ptr as *mut _))
+        }
+    }
+}
+
+/// List the playback devices of the `format` device, e.g. `dshow`, `avfoundation`,
+/// `v4l2` or `alsa`, optionally restricted to the `device` name.
+pub fn sinks<'a>(
+    format: &format::Output,
+    device: Option<&str>,
+    options: Dictionary,
+) -> Result<DeviceIter<'a>, Error> {
+    unsafe {
+        let device = match device {
+            Some(device) => Some(CString::new(device).map_err(|_| Error::InvalidData)?),
+            None => None,
+        };
+        let mut ptr = ptr::null_mut();
+        let opts = options.disown();
+        let res = avdevice_list_output_sinks(
+            format.as_ptr() as *mut _,
+            device.as_ref().map_or(ptr::null(), |d| d.as_ptr()),
+            opts,
+            &mut ptr,
+        );
+
+        Dictionary::own(opts);
+
+        match res {
+            e if e < 0 => Err(Error::from(e)),
+            _ => Ok(DeviceIter::own(ptr)),
+        }
+    }
+}
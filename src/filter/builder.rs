@@ -0,0 +1,333 @@
+use super::{find, Graph, Rebuilding};
+#[cfg(feature = "codec")]
+use codec::decoder;
+use ffi::*;
+use libc::c_int;
+use util::format;
+use util::hwcontext::{Device, Frames};
+use {ChannelLayout, Error, Rational};
+
+/// The parameters of a video buffer source.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct VideoInput {
+    pub width: u32,
+    pub height: u32,
+    pub format: format::Pixel,
+    pub time_base: Rational,
+    pub aspect_ratio: Rational,
+    pub frame_rate: Option<Rational>,
+}
+
+impl VideoInput {
+    pub fn new<R: Into<Rational>>(
+        width: u32,
+        height: u32,
+        format: format::Pixel,
+        time_base: R,
+    ) -> Self {
+        VideoInput {
+            width,
+            height,
+            format,
+            time_base: time_base.into(),
+            aspect_ratio: Rational(1, 1),
+            frame_rate: None,
+        }
+    }
+
+    /// Get the parameters of the frames produced by `decoder`, whose
+    /// timestamps are in `time_base` (usually the time base of the stream).
+    #[cfg(feature = "codec")]
+    pub fn from_decoder<R: Into<Rational>>(decoder: &decoder::Video, time_base: R) -> Self {
+        let aspect_ratio = decoder.aspect_ratio();
+
+        VideoInput {
+            width: decoder.width(),
+            height: decoder.height(),
+            format: decoder.format(),
+            time_base: time_base.into(),
+            aspect_ratio: if aspect_ratio.numerator() == 0 {
+                Rational(1, 1)
+            } else {
+                aspect_ratio
+            },
+            frame_rate: decoder.frame_rate(),
+        }
+    }
+
+    fn args(&self) -> String {
+        let format: AVPixelFormat = self.format.into();
+        let mut args = format!(
+            "video_size={}x{}:pix_fmt={}:time_base={}:pixel_aspect={}",
+            self.width, self.height, format as c_int, self.time_base, self.aspect_ratio
+        );
+
+        if let Some(rate) = self.frame_rate {
+            args.push_str(&format!(":frame_rate={}", rate));
+        }
+
+        args
+    }
+}
+
+/// The parameters of an audio buffer source.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct AudioInput {
+    pub rate: u32,
+    pub format: format::Sample,
+    pub channel_layout: ChannelLayout,
+    pub time_base: Rational,
+}
+
+impl AudioInput {
+    pub fn new<R: Into<Rational>>(
+        rate: u32,
+        format: format::Sample,
+        channel_layout: ChannelLayout,
+        time_base: R,
+    ) -> Self {
+        AudioInput {
+            rate,
+            format,
+            channel_layout,
+            time_base: time_base.into(),
+        }
+    }
+
+    /// Get the parameters of the frames produced by `decoder`, whose
+    /// timestamps are in `time_base` (usually the time base of the stream).
+    #[cfg(feature = "codec")]
+    pub fn from_decoder<R: Into<Rational>>(decoder: &decoder::Audio, time_base: R) -> Self {
+        let channel_layout = if decoder.channel_layout().is_empty() {
+            ChannelLayout::default(i32::from(decoder.channels()))
+        } else {
+            decoder.channel_layout()
+        };
+
+        AudioInput {
+            rate: decoder.rate(),
+            format: decoder.format(),
+            channel_layout,
+            time_base: time_base.into(),
+        }
+    }
+
+    fn args(&self) -> String {
+        format!(
+            "time_base={}:sample_rate={}:sample_fmt={}:channel_layout=0x{:x}",
+            self.time_base,
+            self.rate,
+            self.format.name(),
+            self.channel_layout.bits()
+        )
+    }
+}
+
+#[derive(Clone)]
+enum Input {
+    Video(VideoInput),
+    Audio(AudioInput),
+}
+
+#[derive(Clone)]
+enum Output {
+    Video(Option<format::Pixel>),
+    Audio(Option<(format::Sample, ChannelLayout, u32)>),
+}
+
+/// Build a filter graph from buffer sources, a filter chain description and
+/// buffer sinks.
+///
+/// Sources are named `in`, `in1`, `in2`... and sinks `out`, `out1`, `out2`...
+/// in the order they were added, graphs with several of them must refer to
+/// these labels in the description.
+#[derive(Clone)]
+pub struct Builder {
+    inputs: Vec<Input>,
+    outputs: Vec<Output>,
+    spec: String,
+
+    hw_device: Option<Device>,
+    hw_frames: Option<Frames>,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Builder {
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            spec: String::new(),
+
+            hw_device: None,
+            hw_frames: None,
+        }
+    }
+
+    pub fn video_input(mut self, input: VideoInput) -> Self {
+        self.inputs.push(Input::Video(input));
+        self
+    }
+
+    pub fn audio_input(mut self, input: AudioInput) -> Self {
+        self.inputs.push(Input::Audio(input));
+        self
+    }
+
+    /// Set the filter chain description, e.g. `scale=1280:720,hue=s=0`.
+    pub fn filters<S: Into<String>>(mut self, spec: S) -> Self {
+        self.spec = spec.into();
+        self
+    }
+
+    /// Add a video sink accepting any pixel format.
+    pub fn video_output(mut self) -> Self {
+        self.outputs.push(Output::Video(None));
+        self
+    }
+
+    /// Add a video sink converting to `format`.
+    pub fn video_output_as(mut self, format: format::Pixel) -> Self {
+        self.outputs.push(Output::Video(Some(format)));
+        self
+    }
+
+    /// Add an audio sink accepting any sample format, layout and rate.
+    pub fn audio_output(mut self) -> Self {
+        self.outputs.push(Output::Audio(None));
+        self
+    }
+
+    /// Add an audio sink converting to the given format, layout and rate.
+    pub fn audio_output_as(
+        mut self,
+        format: format::Sample,
+        channel_layout: ChannelLayout,
+        rate: u32,
+    ) -> Self {
+        self.outputs
+            .push(Output::Audio(Some((format, channel_layout, rate))));
+        self
+    }
+
+    /// Set the hardware device of the filters, needed by filters such as
+    /// `hwupload` or `scale_vaapi`.
+    pub fn hw_device(mut self, device: Device) -> Self {
+        self.hw_device = Some(device);
+        self
+    }
+
+    /// Set the pool of the hardware frames fed to the video sources, whose
+    /// format must be the hardware pixel format of the pool.
+    pub fn hw_frames(mut self, frames: Frames) -> Self {
+        self.hw_frames = Some(frames);
+        self
+    }
+
+    /// Create and configure the graph.
+    pub fn build(self) -> Result<Graph, Error> {
+        if self.inputs.is_empty() || self.outputs.is_empty() {
+            return Err(Error::InvalidData);
+        }
+
+        let mut graph = Graph::new();
+
+        for (index, input) in self.inputs.iter().enumerate() {
+            let (filter, args) = match *input {
+                Input::Video(ref input) => ("buffer", input.args()),
+                Input::Audio(ref input) => ("abuffer", input.args()),
+            };
+
+            let mut context = graph.add(
+                &find(filter).ok_or(Error::FilterNotFound)?,
+                &label("in", index),
+                &args,
+            )?;
+
+            if let (&Input::Video(_), Some(ref frames)) = (input, &self.hw_frames) {
+                context.source().set_hw_frames(frames)?;
+            }
+        }
+
+        for (index, output) in self.outputs.iter().enumerate() {
+            let name = label("out", index);
+
+            match *output {
+                Output::Video(format) => {
+                    let mut sink =
+                        graph.add(&find("buffersink").ok_or(Error::FilterNotFound)?, &name, "")?;
+
+                    if let Some(format) = format {
+                        sink.set_pixel_format(format);
+                    }
+                }
+
+                Output::Audio(definition) => {
+                    let mut sink = graph.add(
+                        &find("abuffersink").ok_or(Error::FilterNotFound)?,
+                        &name,
+                        "",
+                    )?;
+
+                    if let Some((format, channel_layout, rate)) = definition {
+                        sink.set_sample_format(format);
+                        sink.set_channel_layout(channel_layout);
+                        sink.set_sample_rate(rate);
+                    }
+                }
+            }
+        }
+
+        {
+            let spec = match (self.spec.is_empty(), &self.inputs[0]) {
+                (true, &Input::Video(_)) => "null",
+                (true, &Input::Audio(_)) => "anull",
+                (false, _) => &self.spec,
+            };
+
+            let mut parser = graph.output("in", 0)?;
+
+            for index in 1..self.inputs.len() {
+                parser = parser.output(&label("in", index), 0)?;
+            }
+
+            for index in 0..self.outputs.len() {
+                parser = parser.input(&label("out", index), 0)?;
+            }
+
+            parser.parse(spec)?;
+        }
+
+        if let Some(ref device) = self.hw_device {
+            graph.set_hw_device(device)?;
+        }
+
+        graph.validate()?;
+
+        Ok(graph)
+    }
+
+    /// Check whether sources were added.
+    pub fn has_inputs(&self) -> bool {
+        !self.inputs.is_empty()
+    }
+
+    /// Build the graph from the parameters of the frames it is fed instead
+    /// of the sources, rebuilding it when they change, see `filter::rebuild`.
+    pub fn rebuild_on_change<R: Into<Rational>>(self, time_base: R) -> Result<Rebuilding, Error> {
+        Rebuilding::new(self, time_base)
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn label(prefix: &str, index: usize) -> String {
+    if index == 0 {
+        prefix.to_owned()
+    } else {
+        format!("{}{}", prefix, index)
+    }
+}
@@ -1,9 +1,16 @@
+use std::ffi::CStr;
 use std::marker::PhantomData;
+use std::str::from_utf8_unchecked;
 
 use super::{Sink, Source};
 use ffi::*;
-use libc::c_void;
-use {format, option, ChannelLayout};
+use filter::link::LinkIter;
+use filter::Filter;
+use libc::{c_void, ENOMEM};
+#[cfg(feature = "ffmpeg_7_0")]
+use util::channel_layout::Layout;
+use util::hwcontext::Device;
+use {format, option, ChannelLayout, Error};
 
 pub struct Context<'a> {
     ptr: *mut AVFilterContext,
@@ -29,6 +36,41 @@ impl<'a> Context<'a> {
 }
 
 impl<'a> Context<'a> {
+    pub fn name(&self) -> &str {
+        unsafe { from_utf8_unchecked(CStr::from_ptr((*self.as_ptr()).name).to_bytes()) }
+    }
+
+    pub fn filter(&self) -> Filter {
+        unsafe { Filter::wrap((*self.as_ptr()).filter as *mut _) }
+    }
+
+    /// Get the links to the input pads, unconnected pads are skipped.
+    pub fn inputs(&self) -> LinkIter<'a> {
+        unsafe { LinkIter::new((*self.as_ptr()).inputs, (*self.as_ptr()).nb_inputs) }
+    }
+
+    /// Get the links from the output pads, unconnected pads are skipped.
+    pub fn outputs(&self) -> LinkIter<'a> {
+        unsafe { LinkIter::new((*self.as_ptr()).outputs, (*self.as_ptr()).nb_outputs) }
+    }
+
+    /// Set the hardware device used by filters creating hardware frames (e.g.
+    /// `hwupload`), before the graph is configured.
+    pub fn set_hw_device(&mut self, device: &Device) -> Result<(), Error> {
+        unsafe {
+            let ptr = av_buffer_ref(device.as_ptr() as *mut _);
+
+            if ptr.is_null() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            av_buffer_unref(&mut (*self.as_mut_ptr()).hw_device_ctx);
+            (*self.as_mut_ptr()).hw_device_ctx = ptr;
+
+            Ok(())
+        }
+    }
+
     pub fn source(&'a mut self) -> Source<'a> {
         unsafe { Source::wrap(self) }
     }
@@ -49,9 +91,16 @@ impl<'a> Context<'a> {
         let _ = option::Settable::set(self, "sample_rates", &i64::from(value));
     }
 
+    #[cfg(not(feature = "ffmpeg_7_0"))]
     pub fn set_channel_layout(&mut self, value: ChannelLayout) {
         let _ = option::Settable::set(self, "channel_layouts", &value.bits());
     }
+
+    #[cfg(feature = "ffmpeg_7_0")]
+    pub fn set_channel_layout(&mut self, value: ChannelLayout) {
+        let layout = Layout::native(value).description();
+        let _ = option::Settable::set_str(self, "ch_layouts", &layout);
+    }
 }
 
 unsafe impl<'a> option::Target for Context<'a> {
@@ -65,3 +114,7 @@ unsafe impl<'a> option::Target for Context<'a> {
 }
 
 impl<'a> option::Settable for Context<'a> {}
+
+impl<'a> option::Gettable for Context<'a> {}
+
+impl<'a> option::Iterable for Context<'a> {}
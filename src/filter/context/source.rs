@@ -2,6 +2,8 @@ use std::ptr;
 
 use super::Context;
 use ffi::*;
+use libc::ENOMEM;
+use util::hwcontext::Frames;
 use {Error, Frame};
 
 pub struct Source<'a> {
@@ -19,6 +21,29 @@ impl<'a> Source<'a> {
         unsafe { av_buffersrc_get_nb_failed_requests(self.ctx.as_ptr() as *mut _) as usize }
     }
 
+    /// Set the pool of the hardware frames the source receives, before the
+    /// graph is configured.
+    pub fn set_hw_frames(&mut self, frames: &Frames) -> Result<(), Error> {
+        unsafe {
+            let parameters = av_buffersrc_parameters_alloc();
+
+            if parameters.is_null() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            (*parameters).hw_frames_ctx = frames.as_ptr() as *mut _;
+
+            let res = av_buffersrc_parameters_set(self.ctx.as_mut_ptr(), parameters);
+
+            av_free(parameters as *mut _);
+
+            match res {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
     pub fn add(&mut self, frame: &Frame) -> Result<(), Error> {
         unsafe {
             match av_buffersrc_add_frame(self.ctx.as_mut_ptr(), frame.as_ptr() as *mut _) {
@@ -1,9 +1,12 @@
 use std::ffi::CStr;
 use std::marker::PhantomData;
+use std::ptr;
 use std::str::from_utf8_unchecked;
 
 use super::{Flags, Pad};
 use ffi::*;
+#[cfg(feature = "ffmpeg_4_0")]
+use libc::c_void;
 
 pub struct Filter {
     ptr: *mut AVFilter,
@@ -47,7 +50,12 @@ impl Filter {
             if ptr.is_null() {
                 None
             } else {
-                Some(PadIter::new((*self.as_ptr()).inputs))
+                #[cfg(not(feature = "ffmpeg_5_0"))]
+                let count = avfilter_pad_count(ptr) as isize;
+                #[cfg(feature = "ffmpeg_5_0")]
+                let count = (*self.as_ptr()).nb_inputs as isize;
+
+                Some(PadIter::new(ptr, count))
             }
         }
     }
@@ -59,7 +67,12 @@ impl Filter {
             if ptr.is_null() {
                 None
             } else {
-                Some(PadIter::new((*self.as_ptr()).outputs))
+                #[cfg(not(feature = "ffmpeg_5_0"))]
+                let count = avfilter_pad_count(ptr) as isize;
+                #[cfg(feature = "ffmpeg_5_0")]
+                let count = (*self.as_ptr()).nb_outputs as isize;
+
+                Some(PadIter::new(ptr, count))
             }
         }
     }
@@ -71,15 +84,17 @@ impl Filter {
 
 pub struct PadIter<'a> {
     ptr: *const AVFilterPad,
+    count: isize,
     cur: isize,
 
     _marker: PhantomData<&'a ()>,
 }
 
 impl<'a> PadIter<'a> {
-    pub fn new(ptr: *const AVFilterPad) -> Self {
+    pub fn new(ptr: *const AVFilterPad, count: isize) -> Self {
         PadIter {
             ptr,
+            count,
             cur: 0,
             _marker: PhantomData,
         }
@@ -91,7 +106,7 @@ impl<'a> Iterator for PadIter<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         unsafe {
-            if self.cur >= avfilter_pad_count(self.ptr) as isize {
+            if self.cur >= self.count {
                 return None;
             }
 
@@ -101,4 +116,59 @@ impl<'a> Iterator for PadIter<'a> {
             Some(pad)
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let length = (self.count - self.cur) as usize;
+
+        (length, Some(length))
+    }
+}
+
+impl<'a> ExactSizeIterator for PadIter<'a> {}
+
+/// Iterator over the registered filters.
+pub struct Iter {
+    #[cfg(feature = "ffmpeg_4_0")]
+    opaque: *mut c_void,
+    #[cfg(not(feature = "ffmpeg_4_0"))]
+    ptr: *const AVFilter,
+}
+
+impl Iter {
+    pub fn new() -> Self {
+        Iter {
+            #[cfg(feature = "ffmpeg_4_0")]
+            opaque: ptr::null_mut(),
+            #[cfg(not(feature = "ffmpeg_4_0"))]
+            ptr: ptr::null(),
+        }
+    }
+}
+
+impl Default for Iter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for Iter {
+    type Item = Filter;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        unsafe {
+            #[cfg(feature = "ffmpeg_4_0")]
+            let ptr = av_filter_iterate(&mut self.opaque);
+            #[cfg(not(feature = "ffmpeg_4_0"))]
+            let ptr = {
+                self.ptr = avfilter_next(self.ptr);
+                self.ptr
+            };
+
+            if ptr.is_null() {
+                None
+            } else {
+                Some(Filter::wrap(ptr as *mut _))
+            }
+        }
+    }
 }
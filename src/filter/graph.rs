@@ -2,9 +2,10 @@ use std::ffi::{CStr, CString};
 use std::ptr;
 use std::str::from_utf8_unchecked;
 
-use super::{Context, Filter};
+use super::{Builder, Context, Filter};
 use ffi::*;
-use libc::c_int;
+use libc::{c_char, c_int};
+use util::hwcontext::Device;
 use Error;
 
 pub struct Graph {
@@ -41,6 +42,10 @@ impl Graph {
         }
     }
 
+    pub fn builder() -> Builder {
+        Builder::new()
+    }
+
     pub fn validate(&mut self) -> Result<(), Error> {
         unsafe {
             match avfilter_graph_config(self.as_mut_ptr(), ptr::null_mut()) {
@@ -94,6 +99,21 @@ impl Graph {
         }
     }
 
+    /// Get the filters of the graph, in creation order.
+    pub fn filters(&self) -> ContextIter {
+        ContextIter::new(self)
+    }
+
+    /// Set the hardware device of all the filters of the graph, before it is
+    /// configured.
+    pub fn set_hw_device(&mut self, device: &Device) -> Result<(), Error> {
+        for mut filter in self.filters() {
+            filter.set_hw_device(device)?;
+        }
+
+        Ok(())
+    }
+
     pub fn dump(&self) -> String {
         unsafe {
             let ptr = avfilter_graph_dump(self.as_ptr() as *mut _, ptr::null());
@@ -106,6 +126,66 @@ impl Graph {
         }
     }
 
+    /// Send a command to the filters named `target` (or `all`), e.g.
+    /// `volume=0.5` to a `volume` filter, and get their response.
+    pub fn send_command(
+        &mut self,
+        target: &str,
+        command: &str,
+        arg: &str,
+    ) -> Result<String, Error> {
+        unsafe {
+            let target = CString::new(target).unwrap();
+            let command = CString::new(command).unwrap();
+            let arg = CString::new(arg).unwrap();
+            let mut response = [0 as c_char; 4096];
+
+            match avfilter_graph_send_command(
+                self.as_mut_ptr(),
+                target.as_ptr(),
+                command.as_ptr(),
+                arg.as_ptr(),
+                response.as_mut_ptr(),
+                response.len() as c_int,
+                0,
+            ) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(
+                    String::from_utf8_lossy(CStr::from_ptr(response.as_ptr()).to_bytes())
+                        .into_owned(),
+                ),
+            }
+        }
+    }
+
+    /// Queue a command to be run by the filters named `target` (or `all`)
+    /// once they process a frame at or after `time`, in seconds.
+    pub fn queue_command(
+        &mut self,
+        target: &str,
+        command: &str,
+        arg: &str,
+        time: f64,
+    ) -> Result<(), Error> {
+        unsafe {
+            let target = CString::new(target).unwrap();
+            let command = CString::new(command).unwrap();
+            let arg = CString::new(arg).unwrap();
+
+            match avfilter_graph_queue_command(
+                self.as_mut_ptr(),
+                target.as_ptr(),
+                command.as_ptr(),
+                arg.as_ptr(),
+                0,
+                time,
+            ) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
     pub fn input(&mut self, name: &str, pad: usize) -> Result<Parser, Error> {
         Parser::new(self).input(name, pad)
     }
@@ -127,6 +207,46 @@ impl Drop for Graph {
     }
 }
 
+pub struct ContextIter<'a> {
+    graph: &'a Graph,
+    cur: u32,
+}
+
+impl<'a> ContextIter<'a> {
+    pub fn new(graph: &'a Graph) -> Self {
+        ContextIter { graph, cur: 0 }
+    }
+}
+
+impl<'a> Iterator for ContextIter<'a> {
+    type Item = Context<'a>;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        unsafe {
+            let ptr = self.graph.as_ptr();
+
+            if self.cur >= (*ptr).nb_filters {
+                return None;
+            }
+
+            let context = Context::wrap(*(*ptr).filters.offset(self.cur as isize));
+            self.cur += 1;
+
+            Some(context)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        unsafe {
+            let length = ((*self.graph.as_ptr()).nb_filters - self.cur) as usize;
+
+            (length, Some(length))
+        }
+    }
+}
+
+impl<'a> ExactSizeIterator for ContextIter<'a> {}
+
 pub struct Parser<'a> {
     graph: &'a mut Graph,
     inputs: *mut AVFilterInOut,
@@ -161,7 +281,13 @@ impl<'a> Parser<'a> {
             if self.inputs.is_null() {
                 self.inputs = input;
             } else {
-                (*self.inputs).next = input;
+                let mut last = self.inputs;
+
+                while !(*last).next.is_null() {
+                    last = (*last).next;
+                }
+
+                (*last).next = input;
             }
         }
 
@@ -187,7 +313,13 @@ impl<'a> Parser<'a> {
             if self.outputs.is_null() {
                 self.outputs = output;
             } else {
-                (*self.outputs).next = output;
+                let mut last = self.outputs;
+
+                while !(*last).next.is_null() {
+                    last = (*last).next;
+                }
+
+                (*last).next = output;
             }
         }
 
@@ -0,0 +1,156 @@
+use std::marker::PhantomData;
+use std::mem;
+
+use super::{Context, Pad};
+use ffi::*;
+use util::format;
+use {media, ChannelLayout, Rational};
+
+/// A link between an output pad and an input pad of two filters, holding the
+/// negotiated properties once the graph is configured.
+pub struct Link<'a> {
+    ptr: *mut AVFilterLink,
+
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> Link<'a> {
+    pub unsafe fn wrap(ptr: *mut AVFilterLink) -> Self {
+        Link {
+            ptr,
+            _marker: PhantomData,
+        }
+    }
+
+    pub unsafe fn as_ptr(&self) -> *const AVFilterLink {
+        self.ptr as *const _
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVFilterLink {
+        self.ptr
+    }
+}
+
+impl<'a> Link<'a> {
+    pub fn source(&self) -> Context<'a> {
+        unsafe { Context::wrap((*self.as_ptr()).src) }
+    }
+
+    pub fn source_pad(&self) -> Pad<'a> {
+        unsafe { Pad::wrap((*self.as_ptr()).srcpad, 0) }
+    }
+
+    pub fn destination(&self) -> Context<'a> {
+        unsafe { Context::wrap((*self.as_ptr()).dst) }
+    }
+
+    pub fn destination_pad(&self) -> Pad<'a> {
+        unsafe { Pad::wrap((*self.as_ptr()).dstpad, 0) }
+    }
+
+    pub fn medium(&self) -> media::Type {
+        unsafe { media::Type::from((*self.as_ptr()).type_) }
+    }
+
+    /// Get the negotiated pixel format, if this is a video link.
+    pub fn pixel_format(&self) -> Option<format::Pixel> {
+        unsafe {
+            if self.medium() != media::Type::Video || (*self.as_ptr()).format < 0 {
+                return None;
+            }
+
+            Some(format::Pixel::from(mem::transmute::<_, AVPixelFormat>(
+                (*self.as_ptr()).format,
+            )))
+        }
+    }
+
+    /// Get the negotiated sample format, if this is an audio link.
+    pub fn sample_format(&self) -> Option<format::Sample> {
+        unsafe {
+            if self.medium() != media::Type::Audio || (*self.as_ptr()).format < 0 {
+                return None;
+            }
+
+            Some(format::Sample::from(mem::transmute::<_, AVSampleFormat>(
+                (*self.as_ptr()).format,
+            )))
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        unsafe { (*self.as_ptr()).w as u32 }
+    }
+
+    pub fn height(&self) -> u32 {
+        unsafe { (*self.as_ptr()).h as u32 }
+    }
+
+    pub fn aspect_ratio(&self) -> Rational {
+        unsafe { Rational::from((*self.as_ptr()).sample_aspect_ratio) }
+    }
+
+    pub fn rate(&self) -> u32 {
+        unsafe { (*self.as_ptr()).sample_rate as u32 }
+    }
+
+    #[cfg(not(feature = "ffmpeg_7_0"))]
+    pub fn channel_layout(&self) -> ChannelLayout {
+        unsafe { ChannelLayout::from_bits_truncate((*self.as_ptr()).channel_layout) }
+    }
+
+    #[cfg(feature = "ffmpeg_7_0")]
+    pub fn channel_layout(&self) -> ChannelLayout {
+        unsafe {
+            let layout = &(*self.as_ptr()).ch_layout;
+
+            if layout.order == AVChannelOrder::AV_CHANNEL_ORDER_NATIVE {
+                ChannelLayout::from_bits_truncate(layout.u.mask)
+            } else {
+                ChannelLayout::empty()
+            }
+        }
+    }
+
+    pub fn time_base(&self) -> Rational {
+        unsafe { Rational::from((*self.as_ptr()).time_base) }
+    }
+}
+
+pub struct LinkIter<'a> {
+    ptr: *const *mut AVFilterLink,
+    count: isize,
+    cur: isize,
+
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> LinkIter<'a> {
+    pub fn new(ptr: *const *mut AVFilterLink, count: u32) -> Self {
+        LinkIter {
+            ptr,
+            count: count as isize,
+            cur: 0,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a> Iterator for LinkIter<'a> {
+    type Item = Link<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unsafe {
+            while self.cur < self.count {
+                let ptr = *self.ptr.offset(self.cur);
+                self.cur += 1;
+
+                if !ptr.is_null() {
+                    return Some(Link::wrap(ptr));
+                }
+            }
+
+            None
+        }
+    }
+}
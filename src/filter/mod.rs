@@ -5,26 +5,38 @@ pub mod pad;
 pub use self::pad::Pad;
 
 pub mod filter;
-pub use self::filter::Filter;
+pub use self::filter::{Filter, Iter};
 
 pub mod context;
 pub use self::context::{Context, Sink, Source};
 
+pub mod link;
+pub use self::link::Link;
+
 pub mod graph;
 pub use self::graph::Graph;
 
+pub mod builder;
+pub use self::builder::{AudioInput, Builder, VideoInput};
+
+pub mod rebuild;
+pub use self::rebuild::Rebuilding;
+
 use std::ffi::{CStr, CString};
 use std::str::from_utf8_unchecked;
 
 use ffi::*;
+#[cfg(not(feature = "ffmpeg_5_0"))]
 use Error;
 
+#[cfg(not(feature = "ffmpeg_5_0"))]
 pub fn register_all() {
     unsafe {
         avfilter_register_all();
     }
 }
 
+#[cfg(not(feature = "ffmpeg_5_0"))]
 pub fn register(filter: &Filter) -> Result<(), Error> {
     unsafe {
         match avfilter_register(filter.as_ptr() as *mut _) {
@@ -46,6 +58,12 @@ pub fn license() -> &'static str {
     unsafe { from_utf8_unchecked(CStr::from_ptr(avfilter_license()).to_bytes()) }
 }
 
+/// List the available filters, see `Filter::inputs()`, `Filter::outputs()`
+/// and `Filter::flags()` for their pads and capabilities.
+pub fn iter() -> Iter {
+    Iter::new()
+}
+
 pub fn find(name: &str) -> Option<Filter> {
     unsafe {
         let name = CString::new(name).unwrap();
@@ -76,4 +94,13 @@ mod tests {
             vec!("main", "overlay")
         );
     }
+
+    #[test]
+    fn test_iter() {
+        register_all();
+        let overlay = iter().find(|filter| filter.name() == "overlay").unwrap();
+
+        assert_eq!(overlay.inputs().unwrap().len(), 2);
+        assert_eq!(overlay.outputs().unwrap().len(), 1);
+    }
 }
@@ -0,0 +1,168 @@
+//! Filter graphs rebuilt when the parameters of their input frames change,
+//! as the ffmpeg tool does, e.g. for live sources switching resolution.
+//!
+//! The graph is built from a `Builder` given without source, the source
+//! being added from the parameters of the first frame, and again from those
+//! of the first frame of each change. The frames still buffered in the
+//! previous graphs are drained before the ones of the new graph.
+
+use std::collections::VecDeque;
+
+use super::{AudioInput, Builder, Graph, VideoInput};
+use {frame, ChannelLayout, Error, Frame, Rational};
+
+/// A change of the parameters of the input frames, which rebuilt the graph.
+#[derive(PartialEq, Clone, Debug)]
+pub enum Change {
+    Video { from: VideoInput, to: VideoInput },
+    Audio { from: AudioInput, to: AudioInput },
+}
+
+#[derive(PartialEq, Clone, Debug)]
+enum Input {
+    Video(VideoInput),
+    Audio(AudioInput),
+}
+
+pub struct Rebuilding {
+    builder: Builder,
+    time_base: Rational,
+
+    input: Option<Input>,
+    graph: Option<Graph>,
+    // the flushed graphs of the previous changes, oldest first
+    previous: VecDeque<Graph>,
+}
+
+impl Rebuilding {
+    /// Create the graph from `builder`, which must have no source, for
+    /// frames whose timestamps are in `time_base`.
+    pub fn new<R: Into<Rational>>(builder: Builder, time_base: R) -> Result<Self, Error> {
+        if builder.has_inputs() {
+            return Err(Error::InvalidData);
+        }
+
+        Ok(Rebuilding {
+            builder,
+            time_base: time_base.into(),
+
+            input: None,
+            graph: None,
+            previous: VecDeque::new(),
+        })
+    }
+
+    /// Get the current graph, `None` until the first frame is added.
+    pub fn graph(&mut self) -> Option<&mut Graph> {
+        self.graph.as_mut()
+    }
+
+    /// Add a video frame, rebuilding the graph first if its parameters
+    /// changed, in which case the change is returned.
+    pub fn add_video(&mut self, frame: &frame::Video) -> Result<Option<Change>, Error> {
+        let aspect_ratio = frame.aspect_ratio();
+        let mut input = VideoInput::new(
+            frame.width(),
+            frame.height(),
+            frame.format(),
+            self.time_base,
+        );
+
+        if aspect_ratio.numerator() != 0 {
+            input.aspect_ratio = aspect_ratio;
+        }
+
+        // the frame rate is not known from the frames, keep the previous one
+        if let Some(Input::Video(ref previous)) = self.input {
+            input.frame_rate = previous.frame_rate;
+        }
+
+        let change = self.update(Input::Video(input))?;
+        self.add(frame)?;
+
+        Ok(change)
+    }
+
+    /// Add an audio frame, rebuilding the graph first if its parameters
+    /// changed, in which case the change is returned.
+    pub fn add_audio(&mut self, frame: &frame::Audio) -> Result<Option<Change>, Error> {
+        let channel_layout = if frame.channel_layout().is_empty() {
+            ChannelLayout::default(i32::from(frame.channels()))
+        } else {
+            frame.channel_layout()
+        };
+
+        let input = AudioInput::new(frame.rate(), frame.format(), channel_layout, self.time_base);
+
+        let change = self.update(Input::Audio(input))?;
+        self.add(frame)?;
+
+        Ok(change)
+    }
+
+    /// Signal the end of the input.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        match self.graph {
+            Some(ref mut graph) => graph.get("in").unwrap().source().flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Get a filtered frame, from the previous graphs first if they still
+    /// have some after changes.
+    pub fn frame(&mut self, frame: &mut Frame) -> Result<(), Error> {
+        while let Some(mut previous) = self.previous.pop_front() {
+            match previous.get("out").unwrap().sink().frame(frame) {
+                Err(Error::Eof) => (),
+                result => {
+                    self.previous.push_front(previous);
+                    return result;
+                }
+            }
+        }
+
+        match self.graph {
+            Some(ref mut graph) => graph.get("out").unwrap().sink().frame(frame),
+            None => Err(Error::Eof),
+        }
+    }
+
+    fn update(&mut self, input: Input) -> Result<Option<Change>, Error> {
+        if self.input.as_ref() == Some(&input) {
+            return Ok(None);
+        }
+
+        let builder = match input {
+            Input::Video(input) => self.builder.clone().video_input(input),
+            Input::Audio(input) => self.builder.clone().audio_input(input),
+        };
+
+        let graph = builder.build()?;
+
+        let change = match (self.input.take(), &input) {
+            (Some(Input::Video(from)), &Input::Video(to)) => Some(Change::Video { from, to }),
+            (Some(Input::Audio(from)), &Input::Audio(to)) => Some(Change::Audio { from, to }),
+            _ => None,
+        };
+
+        if let Some(mut previous) = self.graph.take() {
+            previous.get("in").unwrap().source().flush()?;
+            self.previous.push_back(previous);
+        }
+
+        self.input = Some(input);
+        self.graph = Some(graph);
+
+        Ok(change)
+    }
+
+    fn add(&mut self, frame: &Frame) -> Result<(), Error> {
+        self.graph
+            .as_mut()
+            .unwrap()
+            .get("in")
+            .unwrap()
+            .source()
+            .add(frame)
+    }
+}
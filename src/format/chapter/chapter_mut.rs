@@ -55,6 +55,10 @@ impl<'a> ChapterMut<'a> {
         }
     }
 
+    pub fn set_title<S: AsRef<str>>(&mut self, value: S) {
+        self.set_metadata("title", value);
+    }
+
     pub fn set_metadata<K: AsRef<str>, V: AsRef<str>>(&mut self, key: K, value: V) {
         // dictionary.set() allocates the AVDictionary the first time a key/value is inserted
         // so we want to update the metadata dictionary afterwards
@@ -5,8 +5,10 @@ use std::rc::Rc;
 
 use super::destructor::{self, Destructor};
 use ffi::*;
-use libc::{c_int, c_uint};
-use {media, Chapter, ChapterMut, DictionaryRef, Stream, StreamMut};
+use format::program::{Program, ProgramMut};
+use format::stream::Attachment;
+use libc::{c_int, c_uint, c_void};
+use {media, option, Chapter, ChapterMut, DictionaryRef, Stream, StreamMut};
 
 pub struct Context {
     ptr: *mut AVFormatContext,
@@ -34,6 +36,21 @@ impl Context {
     pub unsafe fn destructor(&self) -> Rc<Destructor> {
         Rc::clone(&self.dtor)
     }
+
+    /// Give up the ownership of the context, returned back if values still
+    /// reference it, e.g. the `codec::Parameters` of its streams.
+    pub fn into_raw(self) -> Result<*mut AVFormatContext, Self> {
+        let ptr = self.ptr;
+
+        match Rc::try_unwrap(self.dtor) {
+            Ok(dtor) => {
+                mem::forget(dtor);
+                Ok(ptr)
+            }
+
+            Err(dtor) => Err(Context { ptr, dtor }),
+        }
+    }
 }
 
 impl Context {
@@ -76,6 +93,10 @@ impl Context {
         StreamIterMut::new(self)
     }
 
+    pub fn attachments(&self) -> AttachmentIter {
+        AttachmentIter::new(self)
+    }
+
     pub fn bit_rate(&self) -> i64 {
         unsafe { (*self.as_ptr()).bit_rate }
     }
@@ -123,6 +144,45 @@ impl Context {
         ChapterIterMut::new(self)
     }
 
+    #[inline]
+    pub fn nb_programs(&self) -> u32 {
+        unsafe { (*self.as_ptr()).nb_programs }
+    }
+
+    pub fn program<'a, 'b>(&'a self, index: usize) -> Option<Program<'b>>
+    where
+        'a: 'b,
+    {
+        unsafe {
+            if index >= self.nb_programs() as usize {
+                None
+            } else {
+                Some(Program::wrap(self, index))
+            }
+        }
+    }
+
+    pub fn program_mut<'a, 'b>(&'a mut self, index: usize) -> Option<ProgramMut<'b>>
+    where
+        'a: 'b,
+    {
+        unsafe {
+            if index >= self.nb_programs() as usize {
+                None
+            } else {
+                Some(ProgramMut::wrap(self, index))
+            }
+        }
+    }
+
+    pub fn programs(&self) -> ProgramIter {
+        ProgramIter::new(self)
+    }
+
+    pub fn programs_mut(&mut self) -> ProgramIterMut {
+        ProgramIterMut::new(self)
+    }
+
     pub fn metadata(&self) -> DictionaryRef {
         unsafe { DictionaryRef::wrap((*self.as_ptr()).metadata) }
     }
@@ -251,6 +311,32 @@ impl<'a> Iterator for StreamIter<'a> {
 
 impl<'a> ExactSizeIterator for StreamIter<'a> {}
 
+pub struct AttachmentIter<'a> {
+    streams: StreamIter<'a>,
+}
+
+impl<'a> AttachmentIter<'a> {
+    pub fn new<'s, 'c: 's>(context: &'c Context) -> AttachmentIter<'s> {
+        AttachmentIter {
+            streams: StreamIter::new(context),
+        }
+    }
+}
+
+impl<'a> Iterator for AttachmentIter<'a> {
+    type Item = Attachment<'a>;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        for stream in &mut self.streams {
+            if let Some(attachment) = Attachment::new(stream) {
+                return Some(attachment);
+            }
+        }
+
+        None
+    }
+}
+
 pub struct StreamIterMut<'a> {
     context: &'a mut Context,
     current: c_uint,
@@ -383,12 +469,118 @@ impl<'a> Iterator for ChapterIterMut<'a> {
 
 impl<'a> ExactSizeIterator for ChapterIterMut<'a> {}
 
+pub struct ProgramIter<'a> {
+    context: &'a Context,
+    current: c_uint,
+}
+
+impl<'a> ProgramIter<'a> {
+    pub fn new<'s, 'c: 's>(context: &'c Context) -> ProgramIter<'s> {
+        ProgramIter {
+            context,
+            current: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for ProgramIter<'a> {
+    type Item = Program<'a>;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        unsafe {
+            if self.current >= (*self.context.as_ptr()).nb_programs {
+                return None;
+            }
+
+            self.current += 1;
+
+            Some(Program::wrap(self.context, (self.current - 1) as usize))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        unsafe {
+            let length = (*self.context.as_ptr()).nb_programs as usize;
+
+            (
+                length - self.current as usize,
+                Some(length - self.current as usize),
+            )
+        }
+    }
+}
+
+impl<'a> ExactSizeIterator for ProgramIter<'a> {}
+
+pub struct ProgramIterMut<'a> {
+    context: &'a mut Context,
+    current: c_uint,
+}
+
+impl<'a> ProgramIterMut<'a> {
+    pub fn new<'s, 'c: 's>(context: &'c mut Context) -> ProgramIterMut<'s> {
+        ProgramIterMut {
+            context,
+            current: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for ProgramIterMut<'a> {
+    type Item = ProgramMut<'a>;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        unsafe {
+            if self.current >= (*self.context.as_ptr()).nb_programs {
+                return None;
+            }
+
+            self.current += 1;
+
+            Some(ProgramMut::wrap(
+                mem::transmute_copy(&self.context),
+                (self.current - 1) as usize,
+            ))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        unsafe {
+            let length = (*self.context.as_ptr()).nb_programs as usize;
+
+            (
+                length - self.current as usize,
+                Some(length - self.current as usize),
+            )
+        }
+    }
+}
+
+impl<'a> ExactSizeIterator for ProgramIterMut<'a> {}
+
+unsafe impl option::Target for Context {
+    fn as_ptr(&self) -> *const c_void {
+        self.ptr as *const _
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut c_void {
+        self.ptr as *mut _
+    }
+}
+
+impl option::Settable for Context {}
+
+impl option::Gettable for Context {}
+
+impl option::Iterable for Context {}
+
 impl fmt::Debug for Context {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         let mut s = fmt.debug_struct("AVFormatContext");
         s.field("bit_rate", &self.bit_rate());
         s.field("duration", &self.duration());
         s.field("nb_chapters", &self.nb_chapters());
+        s.field("nb_programs", &self.nb_programs());
         s.field("nb_streams", &self.nb_streams());
         s.finish()
     }
@@ -1,19 +1,53 @@
+use std::cell::Cell;
+use std::ptr;
+
 use ffi::*;
+use libc::c_void;
 
 #[derive(Copy, Clone, Debug)]
 pub enum Mode {
     Input,
     Output,
+    /// An output writing through a custom I/O context, whose opaque value
+    /// is released with the given function.
+    OutputCustom(unsafe fn(*mut c_void)),
 }
 
 pub struct Destructor {
     ptr: *mut AVFormatContext,
     mode: Mode,
+    opaque: Cell<Option<(*mut c_void, unsafe fn(*mut c_void))>>,
 }
 
 impl Destructor {
     pub unsafe fn new(ptr: *mut AVFormatContext, mode: Mode) -> Self {
-        Destructor { ptr, mode }
+        Destructor {
+            ptr,
+            mode,
+            opaque: Cell::new(None),
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// The value owned on behalf of the context, e.g. the message handler
+    /// of a device, or null.
+    pub fn opaque(&self) -> *mut c_void {
+        match self.opaque.get() {
+            Some((value, _)) => value,
+            None => ptr::null_mut(),
+        }
+    }
+
+    /// Replace the value owned on behalf of the context, releasing the
+    /// previous one with its function; the last one is released once the
+    /// context has been freed.
+    pub unsafe fn set_opaque(&self, value: Option<(*mut c_void, unsafe fn(*mut c_void))>) {
+        if let Some((value, free)) = self.opaque.replace(value) {
+            free(value);
+        }
     }
 }
 
@@ -27,7 +61,22 @@ impl Drop for Destructor {
                     avio_close((*self.ptr).pb);
                     avformat_free_context(self.ptr);
                 }
+
+                Mode::OutputCustom(free) => {
+                    let mut pb = (*self.ptr).pb;
+
+                    if !pb.is_null() {
+                        avio_flush(pb);
+                        av_freep(&mut (*pb).buffer as *mut _ as *mut c_void);
+                        free((*pb).opaque);
+                        avio_context_free(&mut pb);
+                    }
+
+                    avformat_free_context(self.ptr);
+                }
             }
+
+            self.set_opaque(None);
         }
     }
 }
@@ -1,12 +1,30 @@
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::mem;
 use std::ops::{Deref, DerefMut};
+use std::ptr;
 
-use super::common::Context;
+use super::common::{Context, StreamIter};
 use super::destructor;
+use codec::packet::Mut;
 use ffi::*;
+use format::editlist::{self, EditList};
+use libc::{c_char, c_int, c_void, EIO};
 use util::range::Range;
-use {format, Codec, Error, Packet, Stream};
+use {format, log, Codec, Error, Packet, Stream};
+
+bitflags! {
+    pub struct SeekFlags: c_int {
+        /// Seek to the closest position before the timestamp.
+        const BACKWARD = AVSEEK_FLAG_BACKWARD;
+        /// The timestamp is a byte offset.
+        const BYTE     = AVSEEK_FLAG_BYTE;
+        /// Seek to any frame, not only keyframes.
+        const ANY      = AVSEEK_FLAG_ANY;
+        /// The timestamp is a frame number.
+        const FRAME    = AVSEEK_FLAG_FRAME;
+    }
+}
+
 /// The input context which is used to receive
 /// input stream/file.
 pub struct Input {
@@ -31,11 +49,25 @@ impl Input {
     pub unsafe fn as_mut_ptr(&mut self) -> *mut AVFormatContext {
         self.ptr
     }
+
+    /// Take the ownership of a context, e.g. from `into_raw`.
+    pub unsafe fn from_raw(ptr: *mut AVFormatContext) -> Self {
+        Input::wrap(ptr)
+    }
+
+    /// Give up the ownership of the context, to be released with
+    /// `avformat_close_input`. The context is returned back if values
+    /// still reference it, e.g. the `codec::Parameters` of its streams.
+    pub fn into_raw(self) -> Result<*mut AVFormatContext, Self> {
+        let ptr = self.ptr;
+
+        self.ctx.into_raw().map_err(|ctx| Input { ptr, ctx })
+    }
 }
 
 impl Input {
     pub fn format(&self) -> format::Input {
-        unsafe { format::Input::wrap((*self.as_ptr()).iformat) }
+        unsafe { format::Input::wrap((*self.as_ptr()).iformat as *mut _) }
     }
     /// Get the video codec for input stream/file,
     /// return `None` if it's not a video stream/file
@@ -43,12 +75,12 @@ impl Input {
     /// `Some(Code)` will be returned if codec matches.
     pub fn video_codec(&self) -> Option<Codec> {
         unsafe {
-            let ptr = av_format_get_video_codec(self.as_ptr());
+            let ptr = (*self.as_ptr()).video_codec;
 
             if ptr.is_null() {
                 None
             } else {
-                Some(Codec::wrap(ptr))
+                Some(Codec::wrap(ptr as *mut _))
             }
         }
     }
@@ -59,12 +91,12 @@ impl Input {
     /// `Some(Code)` will be returned if codec matches.
     pub fn audio_codec(&self) -> Option<Codec> {
         unsafe {
-            let ptr = av_format_get_audio_codec(self.as_ptr());
+            let ptr = (*self.as_ptr()).audio_codec;
 
             if ptr.is_null() {
                 None
             } else {
-                Some(Codec::wrap(ptr))
+                Some(Codec::wrap(ptr as *mut _))
             }
         }
     }
@@ -72,12 +104,12 @@ impl Input {
     /// this method is used to get subtitle codec.
     pub fn subtitle_codec(&self) -> Option<Codec> {
         unsafe {
-            let ptr = av_format_get_subtitle_codec(self.as_ptr());
+            let ptr = (*self.as_ptr()).subtitle_codec;
 
             if ptr.is_null() {
                 None
             } else {
-                Some(Codec::wrap(ptr))
+                Some(Codec::wrap(ptr as *mut _))
             }
         }
     }
@@ -85,12 +117,12 @@ impl Input {
     /// this method is used to get data codec.
     pub fn data_codec(&self) -> Option<Codec> {
         unsafe {
-            let ptr = av_format_get_data_codec(self.as_ptr());
+            let ptr = (*self.as_ptr()).data_codec;
 
             if ptr.is_null() {
                 None
             } else {
-                Some(Codec::wrap(ptr))
+                Some(Codec::wrap(ptr as *mut _))
             }
         }
     }
@@ -99,10 +131,108 @@ impl Input {
     pub fn probe_score(&self) -> i32 {
         unsafe { av_format_get_probe_score(self.as_ptr()) }
     }
+
+    /// Get the value of an option of the demuxer, private or generic, e.g.
+    /// to check the options it was opened with.
+    pub fn option(&self, name: &str) -> Option<String> {
+        unsafe {
+            let name = CString::new(name).ok()?;
+            let mut value = ptr::null_mut();
+
+            match av_opt_get(
+                self.as_ptr() as *mut c_void,
+                name.as_ptr(),
+                AV_OPT_SEARCH_CHILDREN,
+                &mut value,
+            ) {
+                0 if !value.is_null() => {
+                    let result = CStr::from_ptr(value as *const c_char)
+                        .to_string_lossy()
+                        .into_owned();
+                    av_free(value as *mut c_void);
+
+                    Some(result)
+                }
+
+                _ => None,
+            }
+        }
+    }
+
+    /// Get the edit list of a stream of an MP4/MOV input, `None` if it has
+    /// none, see `format::editlist`.
+    ///
+    /// The file is read again from the I/O context, which must be seekable,
+    /// its position being restored afterwards.
+    pub fn edit_list(&mut self, stream: usize) -> Result<Option<EditList>, Error> {
+        let id = self.stream(stream).ok_or(Error::StreamNotFound)?.id() as u32;
+
+        unsafe {
+            let mut reader = editlist::Reader::wrap((*self.as_mut_ptr()).pb)?;
+            let lists = editlist::read(&mut reader).map_err(|e| Error::Other {
+                errno: e.raw_os_error().unwrap_or(EIO),
+            })?;
+
+            Ok(lists
+                .into_iter()
+                .find(|&(track, _)| track == id)
+                .map(|(_, list)| list))
+        }
+    }
+
+    /// Add the side data of the streams (e.g. display matrix, palette) to
+    /// their first packets, as the ffmpeg tool does, for consumers only
+    /// looking at packets.
+    pub fn inject_global_side_data(&mut self) {
+        unsafe {
+            av_format_inject_global_side_data(self.as_mut_ptr());
+        }
+    }
+
+    /// Read the next packet into `packet`, releasing its previous content,
+    /// so that the same packet can be reused for the whole input.
+    pub fn read_packet_into(&mut self, packet: &mut Packet) -> Result<(), Error> {
+        unsafe {
+            av_packet_unref(packet.as_mut_ptr());
+
+            match av_read_frame(self.as_mut_ptr(), packet.as_mut_ptr()) {
+                0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Get the attached pictures of the input, e.g. the cover art of MP3,
+    /// FLAC and M4A files, along with their streams.
+    pub fn attached_pictures(&self) -> AttachedPictureIter {
+        AttachedPictureIter::new(self)
+    }
+
     /// Get all packets in input context.
     pub fn packets(&mut self) -> PacketIter {
         PacketIter::new(self)
     }
+
+    /// Like [packets()], but yielding the errors met while reading instead
+    /// of skipping them, e.g. to stop on I/O errors of network streams. The
+    /// iteration may go on after an error.
+    ///
+    /// The index of the stream of each packet is yielded, see [stream()].
+    ///
+    /// [packets()]: self::packets
+    /// [stream()]: self::stream
+    pub fn try_packets(&mut self) -> TryPacketIter {
+        TryPacketIter::new(self)
+    }
+
+    /// Like [packets()], but taking the context, and yielding the index of
+    /// the stream of each packet.
+    ///
+    /// [packets()]: self::packets
+    pub fn into_packets(self) -> IntoPackets {
+        IntoPackets { context: self }
+    }
+
     /// Pause the network-basd stream.
     ///
     /// To resume it, see: [play()].
@@ -146,6 +276,63 @@ impl Input {
             }
         }
     }
+
+    /// Seek to timestamp `ts` of a stream, in its time base, or of the
+    /// default stream in `AV_TIME_BASE` units if `stream` is `None`.
+    pub fn seek_stream(
+        &mut self,
+        stream: Option<usize>,
+        ts: i64,
+        flags: SeekFlags,
+    ) -> Result<(), Error> {
+        let index = stream.map_or(-1, |index| index as c_int);
+
+        unsafe {
+            match av_seek_frame(self.as_mut_ptr(), index, ts, flags.bits()) {
+                s if s >= 0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Like [seek_stream()], but to a timestamp within `range`, as close as
+    /// possible to `ts`.
+    ///
+    /// [seek_stream()]: self::seek_stream
+    pub fn seek_stream_in<R: Range<i64>>(
+        &mut self,
+        stream: Option<usize>,
+        ts: i64,
+        range: R,
+        flags: SeekFlags,
+    ) -> Result<(), Error> {
+        let index = stream.map_or(-1, |index| index as c_int);
+
+        unsafe {
+            match avformat_seek_file(
+                self.as_mut_ptr(),
+                index,
+                range.start().cloned().unwrap_or(i64::min_value()),
+                ts,
+                range.end().cloned().unwrap_or(i64::max_value()),
+                flags.bits(),
+            ) {
+                s if s >= 0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Discard the data buffered by the demuxer, e.g. after seeking the
+    /// underlying I/O context directly.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        unsafe {
+            match avformat_flush(self.as_mut_ptr()) {
+                s if s >= 0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
 }
 
 impl Deref for Input {
@@ -195,6 +382,105 @@ impl<'a> Iterator for PacketIter<'a> {
     }
 }
 
+pub struct TryPacketIter<'a> {
+    context: &'a mut Input,
+}
+
+impl<'a> TryPacketIter<'a> {
+    pub fn new(context: &mut Input) -> TryPacketIter {
+        TryPacketIter { context }
+    }
+}
+
+impl<'a> Iterator for TryPacketIter<'a> {
+    type Item = Result<(usize, Packet), Error>;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        let mut packet = Packet::empty();
+
+        match packet.read(self.context) {
+            Ok(..) => Some(Ok((packet.stream(), packet))),
+
+            Err(Error::Eof) => None,
+
+            Err(error) => Some(Err(error)),
+        }
+    }
+}
+
+pub struct AttachedPictureIter<'a> {
+    streams: StreamIter<'a>,
+}
+
+impl<'a> AttachedPictureIter<'a> {
+    pub fn new(context: &Input) -> AttachedPictureIter {
+        AttachedPictureIter {
+            streams: StreamIter::new(context),
+        }
+    }
+}
+
+impl<'a> Iterator for AttachedPictureIter<'a> {
+    type Item = (Stream<'a>, Packet);
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        for stream in &mut self.streams {
+            if let Ok(Some(packet)) = stream.attached_picture() {
+                return Some((stream, packet));
+            }
+        }
+
+        None
+    }
+}
+
+impl IntoIterator for Input {
+    type Item = (usize, Packet);
+    type IntoIter = IntoPackets;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_packets()
+    }
+}
+
+/// An iterator over the packets of an input, owning it.
+pub struct IntoPackets {
+    context: Input,
+}
+
+impl IntoPackets {
+    /// Get the input, e.g. to look at its streams.
+    pub fn input(&self) -> &Input {
+        &self.context
+    }
+
+    pub fn input_mut(&mut self) -> &mut Input {
+        &mut self.context
+    }
+
+    pub fn into_input(self) -> Input {
+        self.context
+    }
+}
+
+impl Iterator for IntoPackets {
+    type Item = (usize, Packet);
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        let mut packet = Packet::empty();
+
+        loop {
+            match packet.read(&mut self.context) {
+                Ok(..) => return Some((packet.stream(), packet)),
+
+                Err(Error::Eof) => return None,
+
+                Err(..) => (),
+            }
+        }
+    }
+}
+
 /// Dump out the detail infomation of input format, basicially
 /// including duration, bitrate, streams, metadata, etc.
 /// # Parameters
@@ -217,3 +503,9 @@ pub fn dump(ctx: &Input, index: i32, url: Option<&str>) {
         );
     }
 }
+
+/// Like [dump], but return the information instead of logging it, e.g. to
+/// display it.
+pub fn dump_to_string(ctx: &Input, index: i32, url: Option<&str>) -> String {
+    log::capture(|| dump(ctx, index, url)).1
+}
@@ -2,7 +2,7 @@ pub mod destructor;
 pub use self::destructor::Destructor;
 
 pub mod input;
-pub use self::input::Input;
+pub use self::input::{Input, SeekFlags};
 
 pub mod output;
 pub use self::output::Output;
@@ -1,28 +1,42 @@
 use std::ffi::CString;
-use std::mem::size_of;
+use std::mem::{self, size_of};
 use std::ops::{Deref, DerefMut};
 use std::ptr;
 
-use libc;
+use libc::{self, ENOMEM};
 
 use super::common::Context;
 use super::destructor;
-use codec::traits;
+use codec::packet::Mut;
+use codec::{self, traits};
 use ffi::*;
-use {format, ChapterMut, Dictionary, Error, Rational, StreamMut};
+use format::stream::attachment;
+use {
+    format, log, media, rescale, ChapterMut, Dictionary, Error, Packet, ProgramMut, Rational,
+    StreamMut,
+};
 
 pub struct Output {
     ptr: *mut AVFormatContext,
     ctx: Context,
+
+    // whether the header was written but not the trailer
+    pending_trailer: bool,
 }
 
 unsafe impl Send for Output {}
 
 impl Output {
     pub unsafe fn wrap(ptr: *mut AVFormatContext) -> Self {
+        Output::wrap_with(ptr, destructor::Mode::Output)
+    }
+
+    pub unsafe fn wrap_with(ptr: *mut AVFormatContext, mode: destructor::Mode) -> Self {
         Output {
             ptr,
-            ctx: Context::wrap(ptr, destructor::Mode::Output),
+            ctx: Context::wrap(ptr, mode),
+
+            pending_trailer: false,
         }
     }
 
@@ -33,18 +47,54 @@ impl Output {
     pub unsafe fn as_mut_ptr(&mut self) -> *mut AVFormatContext {
         self.ptr
     }
+
+    /// Take the ownership of a context, e.g. from `into_raw`.
+    pub unsafe fn from_raw(ptr: *mut AVFormatContext) -> Self {
+        Output::wrap(ptr)
+    }
+
+    /// Give up the ownership of the context, to be released with
+    /// `avformat_free_context` after closing its I/O context. The context
+    /// is returned back if values still reference it, e.g. the
+    /// `codec::Parameters` of its streams, or if it writes through a custom
+    /// I/O context, which only this crate knows how to release.
+    ///
+    /// The trailer is not written, even if the header was.
+    pub fn into_raw(self) -> Result<*mut AVFormatContext, Self> {
+        if let destructor::Mode::OutputCustom(..) = unsafe { self.ctx.destructor() }.mode() {
+            return Err(self);
+        }
+
+        let ptr = self.ptr;
+        let pending_trailer = self.pending_trailer;
+
+        // `Output` is dropped without writing the trailer
+        let ctx = unsafe { ptr::read(&self.ctx) };
+        mem::forget(self);
+
+        ctx.into_raw().map_err(|ctx| Output {
+            ptr,
+            ctx,
+            pending_trailer,
+        })
+    }
 }
 
 impl Output {
     pub fn format(&self) -> format::Output {
-        unsafe { format::Output::wrap((*self.as_ptr()).oformat) }
+        unsafe { format::Output::wrap((*self.as_ptr()).oformat as *mut _) }
     }
 
+    /// Write the header, after which the trailer is written by `finish()`,
+    /// `write_trailer()` or at the latest when the output is dropped.
     pub fn write_header(&mut self) -> Result<(), Error> {
         unsafe {
             match avformat_write_header(self.as_mut_ptr(), ptr::null_mut()) {
-                0 => Ok(()),
-                e => Err(Error::from(e)),
+                e if e < 0 => Err(Error::from(e)),
+                _ => {
+                    self.pending_trailer = true;
+                    Ok(())
+                }
             }
         }
     }
@@ -55,13 +105,19 @@ impl Output {
             let res = avformat_write_header(self.as_mut_ptr(), &mut opts);
 
             match res {
-                0 => Ok(Dictionary::own(opts)),
-                e => Err(Error::from(e)),
+                e if e < 0 => Err(Error::from(e)),
+                _ => {
+                    self.pending_trailer = true;
+                    Ok(Dictionary::own(opts))
+                }
             }
         }
     }
 
     pub fn write_trailer(&mut self) -> Result<(), Error> {
+        // the muxer is deinitialized even on failure
+        self.pending_trailer = false;
+
         unsafe {
             match av_write_trailer(self.as_mut_ptr()) {
                 0 => Ok(()),
@@ -70,6 +126,68 @@ impl Output {
         }
     }
 
+    /// Write a packet right away, bypassing the interleaving queue, the
+    /// packets of all the streams having to be given in `dts` order.
+    pub fn write_frame(&mut self, packet: &Packet) -> Result<(), Error> {
+        packet.write(self).map(|_| ())
+    }
+
+    /// Flush the data buffered by the muxer, for those supporting it, and
+    /// by the I/O context, e.g. to send a packet of a live stream without
+    /// waiting for the next ones. Returns whether there is no more data to
+    /// flush.
+    pub fn flush(&mut self) -> Result<bool, Error> {
+        unsafe {
+            let flushed = match av_write_frame(self.as_mut_ptr(), ptr::null_mut()) {
+                e if e < 0 => return Err(Error::from(e)),
+                e => e == 1,
+            };
+
+            if !(*self.as_ptr()).pb.is_null() {
+                avio_flush((*self.as_ptr()).pb);
+            }
+
+            Ok(flushed)
+        }
+    }
+
+    /// Write all the packets waiting in the interleaving queue.
+    pub fn flush_interleaved(&mut self) -> Result<(), Error> {
+        unsafe {
+            match av_interleaved_write_frame(self.as_mut_ptr(), ptr::null_mut()) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Get the maximum buffering duration for interleaving, in
+    /// microseconds, 0 meaning unlimited.
+    pub fn max_interleave_delta(&self) -> i64 {
+        unsafe { (*self.as_ptr()).max_interleave_delta }
+    }
+
+    /// Set the maximum buffering duration for interleaving, in
+    /// microseconds, after which packets are written even if a stream has
+    /// none queued, e.g. lower for low-latency streaming; 0 waits for a
+    /// packet of every stream.
+    pub fn set_max_interleave_delta(&mut self, value: i64) {
+        unsafe {
+            (*self.as_mut_ptr()).max_interleave_delta = value;
+        }
+    }
+
+    /// Write the trailer if the header was written, reporting the errors
+    /// which dropping the output would ignore, e.g. a file system being full
+    /// while an MP4 index is written.
+    pub fn finish(mut self) -> Result<(), Error> {
+        if self.pending_trailer {
+            self.write_trailer()
+        } else {
+            Ok(())
+        }
+    }
+
     pub fn add_stream<E: traits::Encoder>(&mut self, codec: E) -> Result<StreamMut, Error> {
         unsafe {
             let codec = codec.encoder();
@@ -86,6 +204,140 @@ impl Output {
         }
     }
 
+    /// Add an attachment stream holding a file, e.g. a font for ASS subtitles.
+    ///
+    /// Only supported by the Matroska muxer.
+    pub fn add_attachment<S: AsRef<str>, M: AsRef<str>>(
+        &mut self,
+        filename: S,
+        mime_type: M,
+        data: &[u8],
+    ) -> Result<StreamMut, Error> {
+        unsafe {
+            // allocated first, so that no stream is left without its data
+            let extradata =
+                av_mallocz(data.len() + AV_INPUT_BUFFER_PADDING_SIZE as usize) as *mut u8;
+
+            if extradata.is_null() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            ptr::copy_nonoverlapping(data.as_ptr(), extradata, data.len());
+
+            let ptr = avformat_new_stream(self.as_mut_ptr(), ptr::null());
+
+            if ptr.is_null() {
+                av_free(extradata as *mut libc::c_void);
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            // extradata will be freed by `avformat_free_context`
+            let par = (*ptr).codecpar;
+            (*par).codec_type = media::Type::Attachment.into();
+            (*par).codec_id = attachment::codec(mime_type.as_ref()).into();
+            (*par).extradata = extradata;
+            (*par).extradata_size = data.len() as libc::c_int;
+
+            let mut metadata = Dictionary::new();
+            metadata.set("filename", filename.as_ref());
+            metadata.set("mimetype", mime_type.as_ref());
+
+            let index = (*self.ctx.as_ptr()).nb_streams - 1;
+            let mut stream = StreamMut::wrap(&mut self.ctx, index as usize);
+            stream.set_metadata(metadata);
+
+            Ok(stream)
+        }
+    }
+
+    /// Add an attached picture stream holding an image, e.g. the cover art
+    /// of an MP3, FLAC or M4A file, `id` being the codec of the image, e.g.
+    /// `codec::Id::MJPEG` for JPEG images or `codec::Id::PNG`.
+    ///
+    /// The image is written by `write_attached_pictures()`, after the header.
+    pub fn add_attached_picture(&mut self, id: codec::Id, data: &[u8]) -> Result<StreamMut, Error> {
+        unsafe {
+            // allocated first, so that no stream is left without its image
+            let mut picture: AVPacket = mem::zeroed();
+
+            match av_new_packet(&mut picture, data.len() as libc::c_int) {
+                e if e < 0 => return Err(Error::from(e)),
+                _ => (),
+            }
+
+            ptr::copy_nonoverlapping(data.as_ptr(), picture.data, data.len());
+
+            let ptr = avformat_new_stream(self.as_mut_ptr(), ptr::null());
+
+            if ptr.is_null() {
+                av_packet_unref(&mut picture);
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            let par = (*ptr).codecpar;
+            (*par).codec_type = media::Type::Video.into();
+            (*par).codec_id = id.into();
+            (*ptr).disposition |= AV_DISPOSITION_ATTACHED_PIC;
+
+            picture.stream_index = (*ptr).index;
+            picture.flags |= AV_PKT_FLAG_KEY;
+            picture.pts = 0;
+            picture.dts = 0;
+
+            // the packet will be freed by `avformat_free_context`
+            av_packet_move_ref(&mut (*ptr).attached_pic, &mut picture);
+
+            let index = (*self.ctx.as_ptr()).nb_streams - 1;
+
+            Ok(StreamMut::wrap(&mut self.ctx, index as usize))
+        }
+    }
+
+    /// Write the images of the attached picture streams, as muxers expect
+    /// them as packets, right after `write_header()`.
+    pub fn write_attached_pictures(&mut self) -> Result<(), Error> {
+        let mut pictures = Vec::new();
+
+        for stream in self.streams() {
+            if let Some(packet) = stream.attached_picture()? {
+                pictures.push(packet);
+            }
+        }
+
+        for mut packet in pictures {
+            unsafe {
+                match av_write_frame(self.as_mut_ptr(), packet.as_mut_ptr()) {
+                    e if e < 0 => return Err(Error::from(e)),
+                    _ => (),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Add a program, or get the one already having `id`, e.g. a service of
+    /// an MPEG-TS output, `id` being its service id.
+    ///
+    /// The MPEG-TS muxer takes the PIDs of the streams from their id, see
+    /// `StreamMut::set_id()`, and the name of the service from the
+    /// `service_name` and `service_provider` metadata of the program.
+    pub fn add_program(&mut self, id: i32) -> Result<ProgramMut, Error> {
+        unsafe {
+            let ptr = av_new_program(self.as_mut_ptr(), id);
+
+            if ptr.is_null() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            let index = (0..(*self.as_ptr()).nb_programs as usize)
+                .find(|&index| *(*self.as_ptr()).programs.add(index) == ptr)
+                .ok_or(Error::Bug)?;
+
+            Ok(ProgramMut::wrap(&mut self.ctx, index))
+        }
+    }
+
     pub fn add_chapter<R: Into<Rational>, S: AsRef<str>>(
         &mut self,
         id: i32,
@@ -94,61 +346,205 @@ impl Output {
         end: i64,
         title: S,
     ) -> Result<ChapterMut, Error> {
-        // avpriv_new_chapter is private (libavformat/internal.h)
-
         if start > end {
             return Err(Error::InvalidData);
         }
 
-        let mut existing = None;
-        for chapter in self.chapters() {
-            if chapter.id() == id {
-                existing = Some(chapter.index());
-                break;
+        let index = self.chapter_index(id)?;
+        let mut chapter = self.chapter_mut(index).ok_or(Error::Bug)?;
+
+        chapter.set_id(id);
+        chapter.set_time_base(time_base);
+        chapter.set_start(start);
+        chapter.set_end(end);
+        chapter.set_metadata("title", title);
+
+        Ok(chapter)
+    }
+
+    /// Build a chapter from `start` to `end`, in microseconds unless another
+    /// time base is given, with the next free id unless another one is.
+    ///
+    /// Building fails with `Error::InvalidData` if the next id would
+    /// overflow, in which case one has to be given.
+    pub fn new_chapter(&mut self, start: i64, end: i64) -> ChapterBuilder {
+        ChapterBuilder {
+            output: self,
+            id: None,
+            time_base: rescale::TIME_BASE,
+            start,
+            end,
+            metadata: Dictionary::new(),
+        }
+    }
+
+    // get the index of the chapter with `id`, added if there is none
+    fn chapter_index(&mut self, id: i32) -> Result<usize, Error> {
+        // avpriv_new_chapter is private (libavformat/internal.h)
+
+        if let Some(chapter) = self.chapters().find(|chapter| chapter.id() == id) {
+            return Ok(chapter.index());
+        }
+
+        unsafe {
+            let ptr = av_mallocz(size_of::<AVChapter>());
+
+            if ptr.is_null() {
+                return Err(Error::Other { errno: ENOMEM });
             }
+
+            let mut nb_chapters = (*self.as_ptr()).nb_chapters as i32;
+
+            // chapters array will be freed by `avformat_free_context`
+            av_dynarray_add(
+                &mut (*self.as_mut_ptr()).chapters as *mut _ as *mut libc::c_void,
+                &mut nb_chapters,
+                ptr,
+            );
+
+            if nb_chapters > 0 {
+                (*self.as_mut_ptr()).nb_chapters = nb_chapters as u32;
+                Ok(nb_chapters as usize - 1)
+            } else {
+                // failed to add the chapter, the array being freed
+                (*self.as_mut_ptr()).nb_chapters = 0;
+                av_free(ptr);
+                Err(Error::Other { errno: ENOMEM })
+            }
+        }
+    }
+
+    /// Toggle reproducible output: sets the bitexact flag on the muxer and on
+    /// the codec contexts of the streams added so far, so neither writes
+    /// version-dependent data (e.g. the `encoder` tag).
+    ///
+    /// Must be called after adding the streams, and before opening their
+    /// encoders and writing the header: the muxer reads the flag when the
+    /// header is written, so `Error::InvalidData` is returned once it is.
+    /// Streams have no codec context since FFmpeg 5.0, set
+    /// `codec::Flags::BITEXACT` on the encoders there.
+    pub fn set_bitexact(&mut self, value: bool) -> Result<(), Error> {
+        if self.pending_trailer {
+            return Err(Error::InvalidData);
         }
 
-        let index = match existing {
-            Some(index) => index,
-            None => unsafe {
-                let ptr = av_mallocz(size_of::<AVChapter>())
-                    .as_mut()
-                    .ok_or(Error::Bug)?;
-                let mut nb_chapters = (*self.as_ptr()).nb_chapters as i32;
+        unsafe {
+            let ptr = self.as_mut_ptr();
+
+            if value {
+                (*ptr).flags |= AVFMT_FLAG_BITEXACT;
+            } else {
+                (*ptr).flags &= !AVFMT_FLAG_BITEXACT;
+            }
+
+            #[cfg(not(feature = "ffmpeg_5_0"))]
+            for index in 0..(*ptr).nb_streams as usize {
+                let codec = (**(*ptr).streams.add(index)).codec;
 
-                // chapters array will be freed by `avformat_free_context`
-                av_dynarray_add(
-                    &mut (*self.as_mut_ptr()).chapters as *mut _ as *mut libc::c_void,
-                    &mut nb_chapters,
-                    ptr,
-                );
+                if codec.is_null() {
+                    continue;
+                }
 
-                if nb_chapters > 0 {
-                    (*self.as_mut_ptr()).nb_chapters = nb_chapters as u32;
-                    let index = (*self.ctx.as_ptr()).nb_chapters - 1;
-                    index as usize
+                if value {
+                    (*codec).flags |= AV_CODEC_FLAG_BITEXACT;
                 } else {
-                    // failed to add the chapter
-                    av_freep(ptr);
-                    return Err(Error::Bug);
+                    (*codec).flags &= !AV_CODEC_FLAG_BITEXACT;
                 }
-            },
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn is_bitexact(&self) -> bool {
+        unsafe { (*self.as_ptr()).flags & AVFMT_FLAG_BITEXACT != 0 }
+    }
+
+    pub fn set_metadata(&mut self, dictionary: Dictionary) {
+        unsafe {
+            (*self.as_mut_ptr()).metadata = dictionary.disown();
+        }
+    }
+}
+
+/// A chapter being built, see `Output::new_chapter()`.
+pub struct ChapterBuilder<'a> {
+    output: &'a mut Output,
+    id: Option<i32>,
+    time_base: Rational,
+    start: i64,
+    end: i64,
+    metadata: Dictionary<'static>,
+}
+
+impl<'a> ChapterBuilder<'a> {
+    /// Set the id of the chapter, replacing the chapter having it if any.
+    pub fn id(mut self, value: i32) -> Self {
+        self.id = Some(value);
+        self
+    }
+
+    /// Set the time base of the start and end of the chapter.
+    pub fn time_base<R: Into<Rational>>(mut self, value: R) -> Self {
+        self.time_base = value.into();
+        self
+    }
+
+    pub fn title<S: AsRef<str>>(self, value: S) -> Self {
+        self.metadata("title", value)
+    }
+
+    pub fn metadata<K: AsRef<str>, V: AsRef<str>>(mut self, key: K, value: V) -> Self {
+        self.metadata.set(key.as_ref(), value.as_ref());
+        self
+    }
+
+    pub fn build(self) -> Result<ChapterMut<'a>, Error> {
+        let ChapterBuilder {
+            output,
+            id,
+            time_base,
+            start,
+            end,
+            metadata,
+        } = self;
+
+        if start > end || time_base.numerator() <= 0 || time_base.denominator() <= 0 {
+            return Err(Error::InvalidData);
+        }
+
+        let id = match id {
+            Some(id) => id,
+            None => output
+                .chapters()
+                .map(|chapter| chapter.id())
+                .max()
+                .map_or(Some(1), |id| id.checked_add(1))
+                .ok_or(Error::InvalidData)?,
         };
 
-        let mut chapter = self.chapter_mut(index).ok_or(Error::Bug)?;
+        let index = output.chapter_index(id)?;
+        let mut chapter = output.chapter_mut(index).ok_or(Error::Bug)?;
 
         chapter.set_id(id);
         chapter.set_time_base(time_base);
         chapter.set_start(start);
         chapter.set_end(end);
-        chapter.set_metadata("title", title);
+
+        unsafe {
+            let ptr = chapter.as_mut_ptr();
+            av_dict_free(&mut (*ptr).metadata);
+            (*ptr).metadata = metadata.disown();
+        }
 
         Ok(chapter)
     }
+}
 
-    pub fn set_metadata(&mut self, dictionary: Dictionary) {
-        unsafe {
-            (*self.as_mut_ptr()).metadata = dictionary.disown();
+impl Drop for Output {
+    fn drop(&mut self) {
+        if self.pending_trailer {
+            let _ = self.write_trailer();
         }
     }
 }
@@ -167,7 +563,6 @@ impl DerefMut for Output {
     }
 }
 
-
 /// Dump out the detail infomation of output format, basicially
 /// including duration, bitrate, streams, metadata, etc.
 /// # Parameters
@@ -191,3 +586,9 @@ pub fn dump(ctx: &Output, index: i32, url: Option<&str>) {
         );
     }
 }
+
+/// Like [dump], but return the information instead of logging it, e.g. to
+/// display it.
+pub fn dump_to_string(ctx: &Output, index: i32, url: Option<&str>) -> String {
+    log::capture(|| dump(ctx, index, url)).1
+}
@@ -0,0 +1,159 @@
+//! Typed private options of frequently tuned demuxers, for
+//! `format::input_with_options`.
+//!
+//! The Matroska demuxer has no private options, `live` is an option of the
+//! WebM DASH manifest demuxer.
+//!
+//! `OpenOptions` holds options of the generic format context instead, which
+//! can be combined with the ones of a demuxer.
+
+/// Generic options restricting the components an input may invoke, for
+/// untrusted files.
+///
+/// The codec whitelist applies to the decoders opened when probing the
+/// streams, decoders opened afterwards take it again as their
+/// `codec_whitelist` option.
+#[derive(Clone, Debug, Default)]
+pub struct OpenOptions {
+    options: Vec<(String, String)>,
+}
+
+impl OpenOptions {
+    /// Allow only the given demuxers, e.g. `["mov", "matroska"]`.
+    pub fn format_whitelist(self, names: &[&str]) -> Self {
+        self.set("format_whitelist", names.join(","))
+    }
+
+    /// Allow only the given decoders, e.g. `["h264", "aac"]`.
+    pub fn codec_whitelist(self, names: &[&str]) -> Self {
+        self.set("codec_whitelist", names.join(","))
+    }
+
+    /// Allow only the given protocols, e.g. `["file"]`, also applied to the
+    /// files referenced by the input (playlists, references of MOV).
+    pub fn protocol_whitelist(self, names: &[&str]) -> Self {
+        self.set("protocol_whitelist", names.join(","))
+    }
+
+    pub fn protocol_blacklist(self, names: &[&str]) -> Self {
+        self.set("protocol_blacklist", names.join(","))
+    }
+
+    /// Add the options of a demuxer.
+    pub fn with<I: IntoIterator<Item = (String, String)>>(mut self, options: I) -> Self {
+        self.options.extend(options);
+        self
+    }
+}
+
+options!(OpenOptions);
+
+/// Private options of the MP4/MOV demuxer.
+#[derive(Clone, Debug, Default)]
+pub struct Mov {
+    options: Vec<(String, String)>,
+}
+
+options!(Mov);
+
+impl Mov {
+    /// Ignore the edit lists, exposing all the samples with their original
+    /// timestamps.
+    pub fn ignore_editlist(self, value: bool) -> Self {
+        self.set("ignore_editlist", if value { "1" } else { "0" })
+    }
+
+    /// Apply the edit lists exactly, even when they are not aligned to
+    /// keyframes, the default.
+    pub fn advanced_editlist(self, value: bool) -> Self {
+        self.set("advanced_editlist", if value { "1" } else { "0" })
+    }
+
+    /// Export the unrecognized metadata atoms as metadata.
+    pub fn export_all(self, value: bool) -> Self {
+        self.set("export_all", if value { "1" } else { "0" })
+    }
+
+    pub fn ignore_chapters(self, value: bool) -> Self {
+        self.set("ignore_chapters", if value { "1" } else { "0" })
+    }
+
+    /// Follow the data references to external files, disabled by default
+    /// as they are a security concern.
+    pub fn enable_drefs(self, value: bool) -> Self {
+        self.set("enable_drefs", if value { "1" } else { "0" })
+    }
+}
+
+/// Private options of the WebM DASH manifest demuxer.
+#[derive(Clone, Debug, Default)]
+pub struct WebmDashManifest {
+    options: Vec<(String, String)>,
+}
+
+options!(WebmDashManifest);
+
+impl WebmDashManifest {
+    /// Read the stream as a live profile.
+    pub fn live(self, value: bool) -> Self {
+        self.set("live", if value { "1" } else { "0" })
+    }
+}
+
+/// Private options of the FLV demuxer.
+#[derive(Clone, Debug, Default)]
+pub struct Flv {
+    options: Vec<(String, String)>,
+}
+
+options!(Flv);
+
+impl Flv {
+    /// Create the streams from the `onMetaData` array instead of waiting
+    /// for their first packets.
+    pub fn metadata(self, value: bool) -> Self {
+        self.set("flv_metadata", if value { "1" } else { "0" })
+    }
+
+    /// Export all the content of the `onMetaData` array as metadata.
+    pub fn full_metadata(self, value: bool) -> Self {
+        self.set("flv_full_metadata", if value { "1" } else { "0" })
+    }
+
+    /// Ignore the size of the previous tags, which some encoders get wrong.
+    pub fn ignore_prevtag(self, value: bool) -> Self {
+        self.set("flv_ignore_prevtag", if value { "1" } else { "0" })
+    }
+}
+
+/// Private options of the MPEG-TS demuxer.
+#[derive(Clone, Debug, Default)]
+pub struct MpegTs {
+    options: Vec<(String, String)>,
+}
+
+options!(MpegTs);
+
+impl MpegTs {
+    /// Scan all the program map tables instead of stopping at the first
+    /// program, e.g. for multi-program streams.
+    pub fn scan_all_pmts(self, value: bool) -> Self {
+        self.set("scan_all_pmts", if value { "1" } else { "0" })
+    }
+
+    /// Skip the programs announced in a PMT but missing from the PAT.
+    pub fn skip_unknown_pmt(self, value: bool) -> Self {
+        self.set("skip_unknown_pmt", if value { "1" } else { "0" })
+    }
+
+    /// Keep the same streams when a PMT changes version, instead of
+    /// creating new ones.
+    pub fn merge_pmt_versions(self, value: bool) -> Self {
+        self.set("merge_pmt_versions", if value { "1" } else { "0" })
+    }
+
+    /// Compute the timestamps of teletext packets from the PCR.
+    pub fn fix_teletext_pts(self, value: bool) -> Self {
+        self.set("fix_teletext_pts", if value { "1" } else { "0" })
+    }
+}
@@ -0,0 +1,206 @@
+//! Directory listing through the protocol layer (local files, SMB, FTP,
+//! SFTP, ...).
+
+use std::ffi::{CStr, CString};
+use std::ptr;
+
+use ffi::AVIODirEntryType::*;
+use ffi::*;
+use libc::c_int;
+use {Dictionary, Error};
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Type {
+    Unknown,
+    BlockDevice,
+    CharacterDevice,
+    Directory,
+    NamedPipe,
+    SymbolicLink,
+    Socket,
+    File,
+    Server,
+    Share,
+    Workgroup,
+}
+
+impl Type {
+    fn from_raw(value: c_int) -> Self {
+        match value {
+            v if v == AVIO_ENTRY_BLOCK_DEVICE as c_int => Type::BlockDevice,
+            v if v == AVIO_ENTRY_CHARACTER_DEVICE as c_int => Type::CharacterDevice,
+            v if v == AVIO_ENTRY_DIRECTORY as c_int => Type::Directory,
+            v if v == AVIO_ENTRY_NAMED_PIPE as c_int => Type::NamedPipe,
+            v if v == AVIO_ENTRY_SYMBOLIC_LINK as c_int => Type::SymbolicLink,
+            v if v == AVIO_ENTRY_SOCKET as c_int => Type::Socket,
+            v if v == AVIO_ENTRY_FILE as c_int => Type::File,
+            v if v == AVIO_ENTRY_SERVER as c_int => Type::Server,
+            v if v == AVIO_ENTRY_SHARE as c_int => Type::Share,
+            v if v == AVIO_ENTRY_WORKGROUP as c_int => Type::Workgroup,
+            _ => Type::Unknown,
+        }
+    }
+}
+
+impl From<AVIODirEntryType> for Type {
+    fn from(value: AVIODirEntryType) -> Self {
+        match value {
+            AVIO_ENTRY_UNKNOWN => Type::Unknown,
+            AVIO_ENTRY_BLOCK_DEVICE => Type::BlockDevice,
+            AVIO_ENTRY_CHARACTER_DEVICE => Type::CharacterDevice,
+            AVIO_ENTRY_DIRECTORY => Type::Directory,
+            AVIO_ENTRY_NAMED_PIPE => Type::NamedPipe,
+            AVIO_ENTRY_SYMBOLIC_LINK => Type::SymbolicLink,
+            AVIO_ENTRY_SOCKET => Type::Socket,
+            AVIO_ENTRY_FILE => Type::File,
+            AVIO_ENTRY_SERVER => Type::Server,
+            AVIO_ENTRY_SHARE => Type::Share,
+            AVIO_ENTRY_WORKGROUP => Type::Workgroup,
+        }
+    }
+}
+
+impl Into<AVIODirEntryType> for Type {
+    fn into(self) -> AVIODirEntryType {
+        match self {
+            Type::Unknown => AVIO_ENTRY_UNKNOWN,
+            Type::BlockDevice => AVIO_ENTRY_BLOCK_DEVICE,
+            Type::CharacterDevice => AVIO_ENTRY_CHARACTER_DEVICE,
+            Type::Directory => AVIO_ENTRY_DIRECTORY,
+            Type::NamedPipe => AVIO_ENTRY_NAMED_PIPE,
+            Type::SymbolicLink => AVIO_ENTRY_SYMBOLIC_LINK,
+            Type::Socket => AVIO_ENTRY_SOCKET,
+            Type::File => AVIO_ENTRY_FILE,
+            Type::Server => AVIO_ENTRY_SERVER,
+            Type::Share => AVIO_ENTRY_SHARE,
+            Type::Workgroup => AVIO_ENTRY_WORKGROUP,
+        }
+    }
+}
+
+/// A directory entry, fields the protocol does not provide are `None`.
+///
+/// Timestamps are in microseconds since the Unix epoch.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Entry {
+    pub name: String,
+    pub kind: Type,
+    pub size: Option<i64>,
+
+    pub modified: Option<i64>,
+    pub accessed: Option<i64>,
+    pub status_changed: Option<i64>,
+
+    pub user_id: Option<i64>,
+    pub group_id: Option<i64>,
+    pub mode: Option<i64>,
+}
+
+impl Entry {
+    unsafe fn new(entry: *const AVIODirEntry) -> Self {
+        fn known(value: i64) -> Option<i64> {
+            if value < 0 {
+                None
+            } else {
+                Some(value)
+            }
+        }
+
+        Entry {
+            name: CStr::from_ptr((*entry).name).to_string_lossy().into_owned(),
+            kind: Type::from_raw((*entry).type_),
+            size: known((*entry).size),
+
+            modified: known((*entry).modification_timestamp),
+            accessed: known((*entry).access_timestamp),
+            status_changed: known((*entry).status_change_timestamp),
+
+            user_id: known((*entry).user_id),
+            group_id: known((*entry).group_id),
+            mode: known((*entry).filemode),
+        }
+    }
+}
+
+/// An open directory, iterating over its entries.
+pub struct Dir {
+    ptr: *mut AVIODirContext,
+}
+
+unsafe impl Send for Dir {}
+
+impl Dir {
+    pub unsafe fn as_ptr(&self) -> *const AVIODirContext {
+        self.ptr as *const _
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVIODirContext {
+        self.ptr
+    }
+}
+
+impl Iterator for Dir {
+    type Item = Result<Entry, Error>;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        unsafe {
+            if self.ptr.is_null() {
+                return None;
+            }
+
+            let mut entry = ptr::null_mut();
+
+            match avio_read_dir(self.as_mut_ptr(), &mut entry) {
+                e if e < 0 => {
+                    avio_close_dir(&mut self.ptr);
+                    Some(Err(Error::from(e)))
+                }
+
+                _ if entry.is_null() => {
+                    avio_close_dir(&mut self.ptr);
+                    None
+                }
+
+                _ => {
+                    let result = Entry::new(entry);
+                    avio_free_directory_entry(&mut entry);
+
+                    Some(Ok(result))
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Dir {
+    fn drop(&mut self) {
+        unsafe {
+            if !self.ptr.is_null() {
+                avio_close_dir(&mut self.ptr);
+            }
+        }
+    }
+}
+
+/// List the entries of the directory at `url`.
+pub fn read<S: AsRef<str>>(url: S) -> Result<Dir, Error> {
+    read_with(url, Dictionary::new())
+}
+
+/// List the entries of the directory at `url`, with protocol options (e.g.
+/// credentials or timeouts).
+pub fn read_with<S: AsRef<str>>(url: S, options: Dictionary) -> Result<Dir, Error> {
+    unsafe {
+        let url = CString::new(url.as_ref()).map_err(|_| Error::InvalidData)?;
+        let mut ptr = ptr::null_mut();
+        let mut opts = options.disown();
+        let res = avio_open_dir(&mut ptr, url.as_ptr(), &mut opts);
+
+        Dictionary::own(opts);
+
+        match res {
+            e if e < 0 => Err(Error::from(e)),
+            _ => Ok(Dir { ptr }),
+        }
+    }
+}
@@ -0,0 +1,307 @@
+//! Edit lists of MP4/MOV tracks, read from the `elst` boxes of the file as
+//! the demuxer does not export them.
+//!
+//! By default the demuxer applies the edit lists, shifting the timestamps
+//! and discarding the samples outside of them, e.g. the priming samples of
+//! AAC. Opening with `demuxer::Mov::ignore_editlist` exposes all the samples
+//! with their original timestamps instead, `EditList::offset` then gives the
+//! shift to subtract to get the same timeline.
+
+use std::io::{self, Read, Seek, SeekFrom};
+
+use ffi::*;
+use libc::{c_int, EIO, ESPIPE};
+use {Error, Rational};
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct Edit {
+    /// The duration of the edit, in the time base of the movie.
+    pub duration: i64,
+    /// The start of the edit in the track, in the time base of the track,
+    /// `None` for an empty edit delaying the following ones.
+    pub media_time: Option<i64>,
+    pub rate: f64,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct EditList {
+    pub movie_time_base: Rational,
+    pub time_base: Rational,
+    pub edits: Vec<Edit>,
+}
+
+impl EditList {
+    /// Get the shift the edit list applies to the timestamps of the track,
+    /// in its time base: the start of the first edit, minus the duration of
+    /// the empty edits before it.
+    pub fn offset(&self) -> i64 {
+        let mut delay = 0;
+
+        for edit in &self.edits {
+            match edit.media_time {
+                Some(time) => {
+                    return time - rescale(delay, self.movie_time_base, self.time_base);
+                }
+                None => delay += edit.duration,
+            }
+        }
+
+        0
+    }
+}
+
+fn rescale(value: i64, source: Rational, destination: Rational) -> i64 {
+    unsafe { av_rescale_q(value, source.into(), destination.into()) }
+}
+
+/// Read the edit lists of the tracks of an MP4/MOV file, by track id (the id
+/// of the streams).
+pub fn read<R: Read + Seek>(reader: &mut R) -> io::Result<Vec<(u32, EditList)>> {
+    let end = reader.seek(SeekFrom::End(0))?;
+    let mut position = reader.seek(SeekFrom::Start(0))?;
+    let mut header = [0u8; 16];
+
+    while position + 8 <= end {
+        reader.read_exact(&mut header[..8])?;
+
+        let (size, offset) = match be32(&header) {
+            0 => (end - position, 8),
+            1 => {
+                reader.read_exact(&mut header[8..])?;
+                (be64(&header[8..]), 16)
+            }
+            size => (u64::from(size), 8),
+        };
+
+        if size < offset || position + size > end {
+            break;
+        }
+
+        if &header[4..8] == b"moov" {
+            let mut data = vec![0; (size - offset) as usize];
+            reader.read_exact(&mut data)?;
+
+            return Ok(parse(&data));
+        }
+
+        position = reader.seek(SeekFrom::Start(position + size))?;
+    }
+
+    Ok(Vec::new())
+}
+
+/// Parse the edit lists in the content of a `moov` box.
+pub fn parse(moov: &[u8]) -> Vec<(u32, EditList)> {
+    let movie_scale = child(moov, b"mvhd").and_then(|mvhd| timescale(mvhd, 12, 20));
+
+    children(moov)
+        .filter(|&(kind, _)| kind == b"trak")
+        .filter_map(|(_, trak)| {
+            let tkhd = child(trak, b"tkhd")?;
+            let id = be32(tkhd.get(if *tkhd.first()? == 1 { 20 } else { 12 }..)?);
+
+            let mdhd = child(child(trak, b"mdia")?, b"mdhd")?;
+            let scale = timescale(mdhd, 12, 20)?;
+
+            let elst = child(child(trak, b"edts")?, b"elst")?;
+            let count = be32(elst.get(4..)?) as usize;
+            let size = if elst[0] == 1 { 20 } else { 12 };
+
+            let edits = (0..count)
+                .map(|i| {
+                    let entry = elst.get(8 + i * size..8 + (i + 1) * size)?;
+                    let (duration, time) = if elst[0] == 1 {
+                        (be64(entry) as i64, be64(&entry[8..]) as i64)
+                    } else {
+                        (i64::from(be32(entry)), i64::from(be32(&entry[4..]) as i32))
+                    };
+                    let rate = &entry[size - 4..];
+
+                    Some(Edit {
+                        duration,
+                        media_time: if time < 0 { None } else { Some(time) },
+                        rate: f64::from(be16(rate) as i16) + f64::from(be16(&rate[2..])) / 65536.0,
+                    })
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            Some((
+                id,
+                EditList {
+                    movie_time_base: Rational(1, movie_scale? as i32),
+                    time_base: Rational(1, scale as i32),
+                    edits,
+                },
+            ))
+        })
+        .collect()
+}
+
+fn be16(data: &[u8]) -> u16 {
+    u16::from(data[0]) << 8 | u16::from(data[1])
+}
+
+fn be32(data: &[u8]) -> u32 {
+    u32::from(be16(data)) << 16 | u32::from(be16(&data[2..]))
+}
+
+fn be64(data: &[u8]) -> u64 {
+    u64::from(be32(data)) << 32 | u64::from(be32(&data[4..]))
+}
+
+// get the timescale of a full box, at `v0` or `v1` depending on its version
+fn timescale(data: &[u8], v0: usize, v1: usize) -> Option<u32> {
+    let offset = if *data.first()? == 1 { v1 } else { v0 };
+
+    match be32(data.get(offset..offset + 4)?) {
+        0 => None,
+        scale => Some(scale),
+    }
+}
+
+fn child<'a>(data: &'a [u8], kind: &[u8]) -> Option<&'a [u8]> {
+    children(data).find(|&(k, _)| k == kind).map(|(_, d)| d)
+}
+
+fn children(data: &[u8]) -> Boxes {
+    Boxes { data }
+}
+
+// the boxes in the content of a box, as type and content
+struct Boxes<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for Boxes<'a> {
+    type Item = (&'a [u8], &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.len() < 8 {
+            return None;
+        }
+
+        let size = match be32(self.data) as usize {
+            0 => self.data.len(),
+            size if size >= 8 && size <= self.data.len() => size,
+            _ => return None,
+        };
+
+        let (current, rest) = self.data.split_at(size);
+        self.data = rest;
+
+        Some((&current[4..8], &current[8..]))
+    }
+}
+
+/// The I/O context of a demuxer, with its position restored when dropped.
+pub struct Reader {
+    ptr: *mut AVIOContext,
+    position: i64,
+}
+
+impl Reader {
+    pub unsafe fn wrap(ptr: *mut AVIOContext) -> Result<Self, Error> {
+        if ptr.is_null() || (*ptr).seekable == 0 {
+            return Err(Error::Other { errno: ESPIPE });
+        }
+
+        Ok(Reader {
+            ptr,
+            position: avio_seek(ptr, 0, 1),
+        })
+    }
+}
+
+impl Read for Reader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        unsafe {
+            match avio_read(self.ptr, buf.as_mut_ptr(), buf.len() as c_int) {
+                n if n >= 0 => Ok(n as usize),
+                e if e == AVERROR_EOF => Ok(0),
+                e => Err(io::Error::from(Error::from(e))),
+            }
+        }
+    }
+}
+
+impl Seek for Reader {
+    fn seek(&mut self, position: SeekFrom) -> io::Result<u64> {
+        let (offset, whence) = match position {
+            SeekFrom::Start(offset) => (offset as i64, 0),
+            SeekFrom::Current(offset) => (offset, 1),
+            SeekFrom::End(offset) => {
+                let size = unsafe { avio_size(self.ptr) };
+
+                if size < 0 {
+                    return Err(io::Error::from_raw_os_error(EIO));
+                }
+
+                (size + offset, 0)
+            }
+        };
+
+        unsafe {
+            match avio_seek(self.ptr, offset, whence) {
+                n if n >= 0 => Ok(n as u64),
+                e => Err(io::Error::from(Error::from(e as c_int))),
+            }
+        }
+    }
+}
+
+impl Drop for Reader {
+    fn drop(&mut self) {
+        unsafe {
+            avio_seek(self.ptr, self.position, 0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn boxed(kind: &[u8], content: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&(content.len() as u32 + 8).to_be_bytes());
+        data.extend_from_slice(kind);
+        data.extend_from_slice(content);
+        data
+    }
+
+    #[test]
+    fn test_parse() {
+        let mut mvhd = vec![0; 100];
+        mvhd[12..16].copy_from_slice(&1000u32.to_be_bytes());
+
+        let mut tkhd = vec![0; 84];
+        tkhd[12..16].copy_from_slice(&2u32.to_be_bytes());
+
+        let mut mdhd = vec![0; 24];
+        mdhd[12..16].copy_from_slice(&48000u32.to_be_bytes());
+
+        let mut elst = vec![0, 0, 0, 0, 0, 0, 0, 2];
+        elst.extend_from_slice(&[0, 0, 0, 100, 0xff, 0xff, 0xff, 0xff, 0, 1, 0, 0]);
+        elst.extend_from_slice(&[0, 0, 0x27, 0x10, 0, 0, 0x04, 0x00, 0, 1, 0, 0]);
+
+        let trak = [
+            boxed(b"tkhd", &tkhd),
+            boxed(b"mdia", &boxed(b"mdhd", &mdhd)),
+            boxed(b"edts", &boxed(b"elst", &elst)),
+        ]
+        .concat();
+        let moov = [boxed(b"mvhd", &mvhd), boxed(b"trak", &trak)].concat();
+
+        let lists = parse(&moov);
+        assert_eq!(lists.len(), 1);
+        assert_eq!(lists[0].0, 2);
+
+        let list = &lists[0].1;
+        assert_eq!(list.edits.len(), 2);
+        assert_eq!(list.edits[0].media_time, None);
+        assert_eq!(list.edits[1].duration, 10000);
+        assert_eq!(list.edits[1].media_time, Some(1024));
+        assert_eq!(list.edits[1].rate, 1.0);
+        assert_eq!(list.offset(), 1024 - 4800);
+    }
+}
@@ -2,11 +2,18 @@ use std::ptr;
 
 use super::{Format, Input, Output};
 use ffi::*;
+#[cfg(feature = "ffmpeg_4_0")]
+use libc::c_void;
 
 pub struct Iter {
     input: *mut AVInputFormat,
     output: *mut AVOutputFormat,
     step: Step,
+
+    #[cfg(feature = "ffmpeg_4_0")]
+    demuxers: *mut c_void,
+    #[cfg(feature = "ffmpeg_4_0")]
+    muxers: *mut c_void,
 }
 
 enum Step {
@@ -21,8 +28,33 @@ impl Iter {
             input: ptr::null_mut(),
             output: ptr::null_mut(),
             step: Step::Input,
+
+            #[cfg(feature = "ffmpeg_4_0")]
+            demuxers: ptr::null_mut(),
+            #[cfg(feature = "ffmpeg_4_0")]
+            muxers: ptr::null_mut(),
         }
     }
+
+    #[cfg(not(feature = "ffmpeg_4_0"))]
+    unsafe fn next_input(&mut self) -> *mut AVInputFormat {
+        av_iformat_next(self.input)
+    }
+
+    #[cfg(not(feature = "ffmpeg_4_0"))]
+    unsafe fn next_output(&mut self) -> *mut AVOutputFormat {
+        av_oformat_next(self.output)
+    }
+
+    #[cfg(feature = "ffmpeg_4_0")]
+    unsafe fn next_input(&mut self) -> *mut AVInputFormat {
+        av_demuxer_iterate(&mut self.demuxers) as *mut _
+    }
+
+    #[cfg(feature = "ffmpeg_4_0")]
+    unsafe fn next_output(&mut self) -> *mut AVOutputFormat {
+        av_muxer_iterate(&mut self.muxers) as *mut _
+    }
 }
 
 impl Default for Iter {
@@ -38,7 +70,7 @@ impl Iterator for Iter {
         unsafe {
             match self.step {
                 Step::Input => {
-                    let ptr = av_iformat_next(self.input);
+                    let ptr = self.next_input();
 
                     if ptr.is_null() && !self.input.is_null() {
                         self.step = Step::Output;
@@ -52,7 +84,7 @@ impl Iterator for Iter {
                 }
 
                 Step::Output => {
-                    let ptr = av_oformat_next(self.output);
+                    let ptr = self.next_output();
 
                     if ptr.is_null() && !self.output.is_null() {
                         self.step = Step::Done;
@@ -5,7 +5,7 @@ mod input;
 pub use self::input::Input;
 
 mod output;
-pub use self::output::Output;
+pub use self::output::{Output, Support};
 
 mod iter;
 pub use self::iter::Iter;
@@ -8,6 +8,15 @@ use super::Flags;
 use ffi::*;
 use {codec, media};
 
+/// Whether a muxer can hold a codec, see `Output::query_codec`.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Support {
+    Supported,
+    Unsupported,
+    /// The muxer does not tell, it may fail when writing the header.
+    Unknown,
+}
+
 pub struct Output {
     ptr: *mut AVOutputFormat,
 }
@@ -63,6 +72,8 @@ impl Output {
         }
     }
 
+    /// Guess the codec of `kind` streams written to `path`, e.g. from its
+    /// extension for image sequences, or the default codec of the muxer.
     pub fn codec<P: AsRef<Path>>(&self, path: &P, kind: media::Type) -> codec::Id {
         // XXX: use to_cstring when stable
         let path = CString::new(path.as_ref().as_os_str().to_str().unwrap()).unwrap();
@@ -78,6 +89,33 @@ impl Output {
         }
     }
 
+    /// Get whether the muxer can hold `id` with the given strictness, e.g.
+    /// `Compliance::Normal` to exclude experimental mappings.
+    pub fn query_codec(&self, id: codec::Id, compliance: codec::Compliance) -> Support {
+        unsafe {
+            match avformat_query_codec(self.as_ptr(), id.into(), compliance.into()) {
+                1 => Support::Supported,
+                0 => Support::Unsupported,
+                _ => Support::Unknown,
+            }
+        }
+    }
+
+    /// Get the default video codec of the muxer.
+    pub fn video_codec(&self) -> codec::Id {
+        unsafe { codec::Id::from((*self.as_ptr()).video_codec) }
+    }
+
+    /// Get the default audio codec of the muxer.
+    pub fn audio_codec(&self) -> codec::Id {
+        unsafe { codec::Id::from((*self.as_ptr()).audio_codec) }
+    }
+
+    /// Get the default subtitle codec of the muxer.
+    pub fn subtitle_codec(&self) -> codec::Id {
+        unsafe { codec::Id::from((*self.as_ptr()).subtitle_codec) }
+    }
+
     pub fn flags(&self) -> Flags {
         unsafe { Flags::from_bits_truncate((*self.as_ptr()).flags) }
     }
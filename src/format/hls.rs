@@ -0,0 +1,354 @@
+//! HLS outputs, written by the HLS muxer as a playlist and its segments.
+//!
+//! `Writer` configures the muxer from `Options` and reports the segments
+//! and playlists it writes, e.g. to upload them or notify players.
+
+use std::ffi::{CStr, CString};
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
+use std::ptr;
+use std::time::Duration;
+
+use super::context::Output;
+use super::options;
+use ffi::*;
+use libc::{c_char, c_int, c_void};
+use Error;
+
+bitflags! {
+    /// The `hls_flags` option of the muxer.
+    #[derive(Default)]
+    pub struct Flags: u32 {
+        /// Write all the segments in a single file, as byte ranges.
+        const SINGLE_FILE          = 1 << 0;
+        /// Write the playlist and segments to temporary files renamed once
+        /// complete.
+        const TEMP_FILE            = 1 << 1;
+        /// Delete the segments no longer in the playlist.
+        const DELETE_SEGMENTS      = 1 << 2;
+        const ROUND_DURATIONS      = 1 << 3;
+        const DISCONT_START        = 1 << 4;
+        /// Do not end the playlist once the output is finished.
+        const OMIT_ENDLIST         = 1 << 5;
+        /// Start segments at any frame rather than at keyframes.
+        const SPLIT_BY_TIME        = 1 << 6;
+        /// Append the segments to an existing playlist.
+        const APPEND_LIST          = 1 << 7;
+        const PROGRAM_DATE_TIME    = 1 << 8;
+        const PERIODIC_REKEY       = 1 << 9;
+        const INDEPENDENT_SEGMENTS = 1 << 10;
+    }
+}
+
+const NAMES: [(Flags, &str); 11] = [
+    (Flags::SINGLE_FILE, "single_file"),
+    (Flags::TEMP_FILE, "temp_file"),
+    (Flags::DELETE_SEGMENTS, "delete_segments"),
+    (Flags::ROUND_DURATIONS, "round_durations"),
+    (Flags::DISCONT_START, "discont_start"),
+    (Flags::OMIT_ENDLIST, "omit_endlist"),
+    (Flags::SPLIT_BY_TIME, "split_by_time"),
+    (Flags::APPEND_LIST, "append_list"),
+    (Flags::PROGRAM_DATE_TIME, "program_date_time"),
+    (Flags::PERIODIC_REKEY, "periodic_rekey"),
+    (Flags::INDEPENDENT_SEGMENTS, "independent_segments"),
+];
+
+impl Flags {
+    /// Get the value of the `hls_flags` option, e.g. `+delete_segments`.
+    pub fn to_option(&self) -> String {
+        options::flags(
+            NAMES
+                .iter()
+                .map(|&(flag, name)| (self.contains(flag), name)),
+        )
+    }
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum SegmentType {
+    MpegTs,
+    /// Fragmented MP4 segments, following an initialization segment.
+    Fmp4,
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum PlaylistType {
+    /// Segments are only appended to the playlist.
+    Event,
+    /// The playlist is only written once complete.
+    Vod,
+}
+
+#[derive(PartialEq, Eq, Clone, Default, Debug)]
+pub struct Options {
+    segment_duration: Option<Duration>,
+    list_size: Option<u32>,
+    flags: Flags,
+    segment_filename: Option<String>,
+    segment_type: Option<SegmentType>,
+    playlist_type: Option<PlaylistType>,
+}
+
+impl Options {
+    pub fn new() -> Self {
+        Options::default()
+    }
+
+    /// Set the target duration of the segments, which start at keyframes
+    /// unless `Flags::SPLIT_BY_TIME` is set.
+    pub fn segment_duration(mut self, value: Duration) -> Self {
+        self.segment_duration = Some(value);
+        self
+    }
+
+    /// Set the maximum number of segments in the playlist, 0 keeping all.
+    pub fn list_size(mut self, value: u32) -> Self {
+        self.list_size = Some(value);
+        self
+    }
+
+    /// Add `flags` to the flags of the muxer.
+    pub fn flags(mut self, flags: Flags) -> Self {
+        self.flags |= flags;
+        self
+    }
+
+    /// Set the pattern of the names of the segments, e.g. `segment%03d.ts`.
+    pub fn segment_filename<S: Into<String>>(mut self, value: S) -> Self {
+        self.segment_filename = Some(value.into());
+        self
+    }
+
+    pub fn segment_type(mut self, value: SegmentType) -> Self {
+        self.segment_type = Some(value);
+        self
+    }
+
+    pub fn playlist_type(mut self, value: PlaylistType) -> Self {
+        self.playlist_type = Some(value);
+        self
+    }
+
+    /// Set the options on the muxer of `output`, before writing the header.
+    pub fn apply(&self, output: &mut Output) -> Result<(), Error> {
+        options::apply(self.values(), output)
+    }
+
+    fn values(&self) -> Vec<(String, String)> {
+        let mut values = Vec::new();
+
+        if let Some(value) = self.segment_duration {
+            values.push((
+                "hls_time".into(),
+                format!("{}.{:06}", value.as_secs(), value.subsec_micros()),
+            ));
+        }
+
+        if let Some(value) = self.list_size {
+            values.push(("hls_list_size".into(), value.to_string()));
+        }
+
+        if !self.flags.is_empty() {
+            values.push(("hls_flags".into(), self.flags.to_option()));
+        }
+
+        if let Some(ref value) = self.segment_filename {
+            values.push(("hls_segment_filename".into(), value.clone()));
+        }
+
+        if let Some(value) = self.segment_type {
+            let value = match value {
+                SegmentType::MpegTs => "mpegts",
+                SegmentType::Fmp4 => "fmp4",
+            };
+
+            values.push(("hls_segment_type".into(), value.into()));
+        }
+
+        if let Some(value) = self.playlist_type {
+            let value = match value {
+                PlaylistType::Event => "event",
+                PlaylistType::Vod => "vod",
+            };
+
+            values.push(("hls_playlist_type".into(), value.into()));
+        }
+
+        values
+    }
+}
+
+into_dictionary!(Options, |value: Options| value.values());
+
+/// A file written by the muxer, once closed.
+///
+/// With `Flags::TEMP_FILE`, the files are reported by their final names,
+/// which they are renamed to right after.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub enum Event {
+    /// The playlist was written again, e.g. with a new segment.
+    Playlist(String),
+    /// A segment was written, or the initialization segment of fMP4
+    /// segments.
+    Segment(String),
+}
+
+impl Event {
+    fn new(mut url: String) -> Self {
+        if url.ends_with(".tmp") {
+            let len = url.len() - ".tmp".len();
+            url.truncate(len);
+        }
+
+        if url.ends_with(".m3u8") {
+            Event::Playlist(url)
+        } else {
+            Event::Segment(url)
+        }
+    }
+}
+
+type Handler = Box<dyn FnMut(Event) + Send>;
+
+struct State {
+    files: Vec<(*mut AVIOContext, String)>,
+    handler: Option<Handler>,
+}
+
+/// An HLS output, writing the playlist at its path and the segments next
+/// to it unless another pattern is given.
+pub struct Writer {
+    // dropped first, as the muxer closes files when writing the trailer
+    output: Output,
+    state: Box<State>,
+}
+
+unsafe impl Send for Writer {}
+
+impl Writer {
+    pub fn new<P: AsRef<Path>>(path: &P, options: &Options) -> Result<Self, Error> {
+        unsafe {
+            let mut ps = ptr::null_mut();
+            let path = CString::new(path.as_ref().as_os_str().to_str().unwrap()).unwrap();
+            let format = CString::new("hls").unwrap();
+
+            match avformat_alloc_output_context2(
+                &mut ps,
+                ptr::null_mut(),
+                format.as_ptr(),
+                path.as_ptr(),
+            ) {
+                0 => (),
+                e => return Err(Error::from(e)),
+            }
+
+            let mut writer = Writer {
+                output: Output::wrap(ps),
+                state: Box::new(State {
+                    files: Vec::new(),
+                    handler: None,
+                }),
+            };
+
+            (*ps).opaque = &mut *writer.state as *mut State as *mut c_void;
+            (*ps).io_open = Some(io_open);
+            set_io_close(ps);
+
+            options.apply(&mut writer.output)?;
+
+            Ok(writer)
+        }
+    }
+
+    /// Call `handler` with each playlist and segment once written.
+    pub fn on_event<F>(&mut self, handler: F)
+    where
+        F: FnMut(Event) + Send + 'static,
+    {
+        self.state.handler = Some(Box::new(handler));
+    }
+
+    /// Write the trailer, ending the playlist, see `Output::finish`.
+    pub fn finish(self) -> Result<(), Error> {
+        let Writer { output, state } = self;
+        let result = output.finish();
+        drop(state);
+
+        result
+    }
+}
+
+impl Deref for Writer {
+    type Target = Output;
+
+    fn deref(&self) -> &Self::Target {
+        &self.output
+    }
+}
+
+impl DerefMut for Writer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.output
+    }
+}
+
+unsafe extern "C" fn io_open(
+    s: *mut AVFormatContext,
+    pb: *mut *mut AVIOContext,
+    url: *const c_char,
+    flags: c_int,
+    options: *mut *mut AVDictionary,
+) -> c_int {
+    let result = avio_open2(pb, url, flags, &(*s).interrupt_callback, options);
+
+    // the nested muxers of the segments share the opaque value
+    if result >= 0 && !(*s).opaque.is_null() {
+        let state = &mut *((*s).opaque as *mut State);
+        let url = CStr::from_ptr(url).to_string_lossy().into_owned();
+
+        state.files.push((*pb, url));
+    }
+
+    result
+}
+
+unsafe fn close(s: *mut AVFormatContext, pb: *mut AVIOContext) -> c_int {
+    let file = pb;
+    let mut pb = pb;
+    let result = avio_closep(&mut pb);
+
+    if (*s).opaque.is_null() {
+        return result;
+    }
+
+    let state = &mut *((*s).opaque as *mut State);
+
+    if let Some(index) = state.files.iter().position(|&(opened, _)| opened == file) {
+        let (_, url) = state.files.swap_remove(index);
+
+        if let (true, Some(handler)) = (result >= 0, state.handler.as_mut()) {
+            handler(Event::new(url));
+        }
+    }
+
+    result
+}
+
+#[cfg(not(feature = "ffmpeg_5_1"))]
+unsafe fn set_io_close(ps: *mut AVFormatContext) {
+    unsafe extern "C" fn io_close(s: *mut AVFormatContext, pb: *mut AVIOContext) {
+        close(s, pb);
+    }
+
+    (*ps).io_close = Some(io_close);
+}
+
+#[cfg(feature = "ffmpeg_5_1")]
+unsafe fn set_io_close(ps: *mut AVFormatContext) {
+    unsafe extern "C" fn io_close2(s: *mut AVFormatContext, pb: *mut AVIOContext) -> c_int {
+        close(s, pb)
+    }
+
+    (*ps).io_close2 = Some(io_close2);
+}
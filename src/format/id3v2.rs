@@ -0,0 +1,203 @@
+//! ID3v2 tags, as written by the MP3 muxer.
+//!
+//! The muxer writes the chapters of the output as `CHAP` frames, listed in
+//! a top-level `CTOC` frame, so `add_chapters` is all it takes to write
+//! podcast chapters. It has no way to write arbitrary frames though, so
+//! synchronized lyrics are read-only: `SyncedLyrics::parse` decodes a `SYLT`
+//! frame read from a tag.
+
+use format::context::Output;
+use {Dictionary, Error};
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Version {
+    V3,
+    V4,
+}
+
+/// Get the muxer options selecting the tag version, to pass to
+/// `Output::write_header_with`.
+pub fn options<'a>(version: Version) -> Dictionary<'a> {
+    let mut options = Dictionary::new();
+
+    match version {
+        Version::V3 => options.set("id3v2_version", "3"),
+        Version::V4 => options.set("id3v2_version", "4"),
+    }
+
+    options
+}
+
+/// A chapter, with times in milliseconds.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Chapter {
+    pub start: i64,
+    pub end: i64,
+    pub title: String,
+}
+
+impl Chapter {
+    pub fn new<S: Into<String>>(start: i64, end: i64, title: S) -> Self {
+        Chapter {
+            start,
+            end,
+            title: title.into(),
+        }
+    }
+}
+
+/// Add `chapters` to `output`, numbered from 1 in order.
+pub fn add_chapters(output: &mut Output, chapters: &[Chapter]) -> Result<(), Error> {
+    for (index, chapter) in chapters.iter().enumerate() {
+        output.add_chapter(
+            index as i32 + 1,
+            (1, 1000),
+            chapter.start,
+            chapter.end,
+            &chapter.title,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// The kind of content of synchronized lyrics.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum ContentType {
+    Other,
+    Lyrics,
+    Transcription,
+    Movement,
+    Events,
+    Chord,
+    Trivia,
+    WebPages,
+    Images,
+}
+
+/// Synchronized lyrics, with times in milliseconds.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct SyncedLyrics {
+    /// ISO 639-2 language code.
+    pub language: [u8; 3],
+    pub content_type: ContentType,
+    pub description: String,
+    pub lines: Vec<(u32, String)>,
+}
+
+impl SyncedLyrics {
+    pub fn new(language: [u8; 3]) -> Self {
+        SyncedLyrics {
+            language,
+            content_type: ContentType::Lyrics,
+            description: String::new(),
+            lines: Vec::new(),
+        }
+    }
+
+    pub fn push<S: Into<String>>(&mut self, time: u32, text: S) -> &mut Self {
+        self.lines.push((time, text.into()));
+        self
+    }
+
+    /// Decode a complete `SYLT` frame, header included, returning `None`
+    /// if it is malformed or not timed in milliseconds.
+    pub fn parse(frame: &[u8], version: Version) -> Option<Self> {
+        if frame.len() < 10 || &frame[0..4] != b"SYLT" {
+            return None;
+        }
+
+        let size = match version {
+            Version::V3 => u32::from_be_bytes([frame[4], frame[5], frame[6], frame[7]]),
+            Version::V4 => frame[4..8]
+                .iter()
+                .fold(0, |size, &byte| size << 7 | u32::from(byte & 0x7f)),
+        };
+        let body = frame.get(10..10 + size as usize)?;
+
+        if body.len() < 6 || body[4] != 2 {
+            return None;
+        }
+
+        let encoding = body[0];
+        let mut lyrics = SyncedLyrics::new([body[1], body[2], body[3]]);
+        lyrics.content_type = ContentType::from(body[5]);
+
+        let (description, mut rest) = get_text(&body[6..], encoding)?;
+        lyrics.description = description;
+
+        while !rest.is_empty() {
+            let (text, after) = get_text(rest, encoding)?;
+            let time = after.get(0..4)?;
+
+            lyrics.push(
+                u32::from_be_bytes([time[0], time[1], time[2], time[3]]),
+                text,
+            );
+            rest = &after[4..];
+        }
+
+        Some(lyrics)
+    }
+}
+
+impl From<u8> for ContentType {
+    fn from(value: u8) -> Self {
+        match value {
+            1 => ContentType::Lyrics,
+            2 => ContentType::Transcription,
+            3 => ContentType::Movement,
+            4 => ContentType::Events,
+            5 => ContentType::Chord,
+            6 => ContentType::Trivia,
+            7 => ContentType::WebPages,
+            8 => ContentType::Images,
+            _ => ContentType::Other,
+        }
+    }
+}
+
+// read a terminated string in `encoding`, returning it with what follows
+fn get_text(data: &[u8], encoding: u8) -> Option<(String, &[u8])> {
+    match encoding {
+        0 | 3 => {
+            let end = data.iter().position(|&byte| byte == 0)?;
+            let text = if encoding == 0 {
+                data[..end].iter().map(|&byte| char::from(byte)).collect()
+            } else {
+                String::from_utf8_lossy(&data[..end]).into_owned()
+            };
+
+            Some((text, &data[end + 1..]))
+        }
+
+        1 | 2 => {
+            let end = data
+                .chunks(2)
+                .position(|unit| unit == [0, 0])
+                .map(|index| index * 2)?;
+            let (mut units, mut big_endian) = (&data[..end], encoding == 2);
+
+            if units.starts_with(&[0xfe, 0xff]) || units.starts_with(&[0xff, 0xfe]) {
+                big_endian = units[0] == 0xfe;
+                units = &units[2..];
+            }
+
+            let units = units
+                .chunks(2)
+                .filter(|unit| unit.len() == 2)
+                .map(|unit| {
+                    if big_endian {
+                        u16::from_be_bytes([unit[0], unit[1]])
+                    } else {
+                        u16::from_le_bytes([unit[0], unit[1]])
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            Some((String::from_utf16_lossy(&units), &data[end + 2..]))
+        }
+
+        _ => None,
+    }
+}
@@ -0,0 +1,215 @@
+//! Image sequences, e.g. `frame%04d.png`, read by the image2 demuxer and
+//! written by the image2 muxer, one file per frame.
+
+use std::ffi::CString;
+use std::path::Path;
+use std::ptr;
+
+use super::context::{Input, Output};
+use codec::Context;
+use ffi::*;
+use option::Settable;
+use util::error::EAGAIN;
+use {encoder, format, frame, media, Dictionary, Error, Packet, Rational};
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum PatternType {
+    /// A pattern numbering the files, e.g. `frame%04d.png`.
+    Sequence,
+    /// A glob pattern, e.g. `*.png`, unless FFmpeg is built without glob
+    /// support, as on Windows.
+    Glob,
+    /// A single file, whose name may contain `%`.
+    None,
+}
+
+/// Options of image sequence inputs.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Options {
+    rate: Rational,
+    start_number: Option<u32>,
+    pattern_type: Option<PatternType>,
+    looping: bool,
+}
+
+impl Options {
+    /// Read the images at `rate` frames per second.
+    pub fn new<R: Into<Rational>>(rate: R) -> Self {
+        Options {
+            rate: rate.into(),
+            start_number: None,
+            pattern_type: None,
+            looping: false,
+        }
+    }
+
+    /// Set the number of the first file, otherwise looked for from 0 to 4.
+    pub fn start_number(mut self, value: u32) -> Self {
+        self.start_number = Some(value);
+        self
+    }
+
+    pub fn pattern_type(mut self, value: PatternType) -> Self {
+        self.pattern_type = Some(value);
+        self
+    }
+
+    /// Read the images again once the last one is read.
+    pub fn looping(mut self, value: bool) -> Self {
+        self.looping = value;
+        self
+    }
+}
+
+impl<'a> From<Options> for Dictionary<'a> {
+    fn from(value: Options) -> Self {
+        let mut options = Dictionary::new();
+        options.set("framerate", &value.rate.to_string());
+
+        if let Some(number) = value.start_number {
+            options.set("start_number", &number.to_string());
+        }
+
+        match value.pattern_type {
+            Some(PatternType::Sequence) => options.set("pattern_type", "sequence"),
+            Some(PatternType::Glob) => options.set("pattern_type", "glob"),
+            Some(PatternType::None) => options.set("pattern_type", "none"),
+            None => (),
+        }
+
+        if value.looping {
+            options.set("loop", "1");
+        }
+
+        options
+    }
+}
+
+/// Open the image sequence matching `pattern`.
+pub fn input<P: AsRef<Path>>(pattern: &P, options: Options) -> Result<Input, Error> {
+    unsafe {
+        let name = CString::new("image2").unwrap();
+        let ptr = av_find_input_format(name.as_ptr());
+
+        if ptr.is_null() {
+            return Err(Error::DemuxerNotFound);
+        }
+
+        let demuxer = format::Format::Input(format::Input::wrap(ptr as *mut _));
+
+        format::open_with(pattern, &demuxer, options.into()).map(|context| context.input())
+    }
+}
+
+/// Create an image sequence output writing the files named from `pattern`,
+/// e.g. `frame%04d.png`, numbered from `start_number`.
+///
+/// The muxer opens each file itself, so unlike `format::output` the
+/// pattern is not opened as a file.
+pub fn output<P: AsRef<Path>>(pattern: &P, start_number: u32) -> Result<Output, Error> {
+    unsafe {
+        let mut ps = ptr::null_mut();
+        let path = CString::new(pattern.as_ref().as_os_str().to_str().unwrap()).unwrap();
+        let format = CString::new("image2").unwrap();
+
+        match avformat_alloc_output_context2(
+            &mut ps,
+            ptr::null_mut(),
+            format.as_ptr(),
+            path.as_ptr(),
+        ) {
+            0 => (),
+            e => return Err(Error::from(e)),
+        }
+
+        let mut output = Output::wrap(ps);
+        output.set_int("start_number", i64::from(start_number))?;
+
+        Ok(output)
+    }
+}
+
+/// Writes frames as an image sequence, encoded by the codec matching the
+/// extension of the pattern, e.g. PNG or TIFF.
+pub struct Writer {
+    output: Output,
+    encoder: encoder::video::Encoder,
+    pts: i64,
+}
+
+impl Writer {
+    /// Create a writer of frames of the given size and format, which the
+    /// encoder has to support, e.g. `Pixel::RGB24` or `Pixel::RGB48BE` for
+    /// PNG.
+    pub fn new<P: AsRef<Path>>(
+        pattern: &P,
+        start_number: u32,
+        width: u32,
+        height: u32,
+        format: format::Pixel,
+    ) -> Result<Self, Error> {
+        let mut output = output(pattern, start_number)?;
+        let id = output.format().codec(pattern, media::Type::Video);
+        let codec = encoder::find(id).ok_or(Error::EncoderNotFound)?;
+        let time_base = Rational(1, 1);
+
+        let mut encoder = Context::new().encoder().video()?;
+        encoder.set_width(width);
+        encoder.set_height(height);
+        encoder.set_format(format);
+        encoder.set_time_base(time_base);
+
+        let encoder = encoder.open_as(codec)?;
+
+        {
+            let mut stream = output.add_stream(codec)?;
+            stream.set_parameters(&encoder);
+            stream.set_time_base(time_base);
+        }
+
+        output.write_header()?;
+
+        Ok(Writer {
+            output,
+            encoder,
+            pts: 0,
+        })
+    }
+
+    /// Write a frame as the next file of the sequence.
+    pub fn write(&mut self, frame: &frame::Video) -> Result<(), Error> {
+        self.encoder.send_frame(frame)?;
+        self.receive()
+    }
+
+    /// Write the frames still buffered by the encoder, if any.
+    pub fn finish(mut self) -> Result<(), Error> {
+        self.encoder.send_eof()?;
+        self.receive()?;
+
+        self.output.write_trailer()
+    }
+
+    fn receive(&mut self) -> Result<(), Error> {
+        let time_base = self.output.stream(0).ok_or(Error::Bug)?.time_base();
+        let mut packet = Packet::empty();
+
+        loop {
+            match self.encoder.receive_packet(&mut packet) {
+                Ok(()) => (),
+                Err(Error::Eof) => return Ok(()),
+                Err(Error::Other { errno }) if errno == EAGAIN => return Ok(()),
+                Err(e) => return Err(e),
+            }
+
+            // image encoders have no delay, numbering the packets in order
+            packet.set_stream(0);
+            packet.set_pts(Some(self.pts));
+            packet.set_dts(Some(self.pts));
+            packet.rescale_ts(Rational(1, 1), time_base);
+            self.pts += 1;
+
+            packet.write_interleaved(&mut self.output)?;
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! Owned descriptions of inputs, their streams and chapters, e.g. to report
+//! them as ffprobe does, serializable with the `serde` feature.
+
+use std::path::Path;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use codec;
+use format::context::Input;
+use {Chapter, Dictionary, Error, Rational, Stream};
+
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[derive(Clone, Debug)]
+pub struct StreamInfo {
+    pub index: usize,
+    pub id: i32,
+    pub codec: codec::Summary,
+
+    pub time_base: Rational,
+    pub start_time: i64,
+    pub duration: i64,
+    pub frames: i64,
+    pub frame_rate: Rational,
+    pub avg_frame_rate: Rational,
+
+    pub metadata: Dictionary<'static>,
+}
+
+impl<'a, 'b> From<&'b Stream<'a>> for StreamInfo {
+    fn from(stream: &'b Stream<'a>) -> Self {
+        StreamInfo {
+            index: stream.index(),
+            id: stream.id(),
+            codec: stream.parameters().summary(),
+
+            time_base: stream.time_base(),
+            start_time: stream.start_time(),
+            duration: stream.duration(),
+            frames: stream.frames(),
+            frame_rate: stream.rate(),
+            avg_frame_rate: stream.avg_frame_rate(),
+
+            metadata: stream.metadata().to_owned(),
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[derive(Clone, Debug)]
+pub struct ChapterInfo {
+    pub id: i32,
+    pub time_base: Rational,
+    pub start: i64,
+    pub end: i64,
+
+    pub metadata: Dictionary<'static>,
+}
+
+impl<'a, 'b> From<&'b Chapter<'a>> for ChapterInfo {
+    fn from(chapter: &'b Chapter<'a>) -> Self {
+        ChapterInfo {
+            id: chapter.id(),
+            time_base: chapter.time_base(),
+            start: chapter.start(),
+            end: chapter.end(),
+
+            metadata: chapter.metadata().to_owned(),
+        }
+    }
+}
+
+/// The description of the container.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[derive(Clone, Debug)]
+pub struct FormatInfo {
+    pub name: String,
+    pub description: String,
+    pub probe_score: i32,
+
+    /// The start time and duration in `AV_TIME_BASE` units.
+    pub start_time: i64,
+    pub duration: i64,
+    pub bit_rate: i64,
+
+    pub metadata: Dictionary<'static>,
+}
+
+impl<'a> From<&'a Input> for FormatInfo {
+    fn from(input: &'a Input) -> Self {
+        let format = input.format();
+
+        FormatInfo {
+            name: format.name().to_owned(),
+            description: format.description().to_owned(),
+            probe_score: input.probe_score(),
+
+            start_time: unsafe { (*input.as_ptr()).start_time },
+            duration: input.duration(),
+            bit_rate: input.bit_rate(),
+
+            metadata: input.metadata().to_owned(),
+        }
+    }
+}
+
+/// The description of an input, laid out as the report of ffprobe.
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[derive(Clone, Debug)]
+pub struct MediaInfo {
+    pub format: FormatInfo,
+    pub streams: Vec<StreamInfo>,
+    pub chapters: Vec<ChapterInfo>,
+}
+
+impl<'a> From<&'a Input> for MediaInfo {
+    fn from(input: &'a Input) -> Self {
+        MediaInfo {
+            format: FormatInfo::from(input),
+            streams: input
+                .streams()
+                .map(|stream| StreamInfo::from(&stream))
+                .collect(),
+            chapters: input
+                .chapters()
+                .map(|chapter| ChapterInfo::from(&chapter))
+                .collect(),
+        }
+    }
+}
+
+/// Open the file at `path` to describe it, without decoding it.
+pub fn probe<P: AsRef<Path>>(path: &P) -> Result<MediaInfo, Error> {
+    super::input(path).map(|input| MediaInfo::from(&input))
+}
@@ -0,0 +1,237 @@
+//! Outputs writing to any `io::Write` instead of a URL, e.g. to upload the
+//! output while it is being muxed.
+//!
+//! The I/O context is not seekable, so muxers never rewrite data already
+//! written: fragmented MP4 needs `movflags=frag_keyframe+empty_moov` (or
+//! `cmaf`), and the byte ranges reported by `output_as_with_ranges` are final.
+
+use std::ffi::CString;
+use std::io::{self, Write};
+use std::ptr;
+use std::slice;
+
+use super::context::{self, destructor};
+use ffi::AVIODataMarkerType::*;
+use ffi::*;
+use libc::{c_int, c_void, EIO, ENOMEM};
+use Error;
+
+const BUFFER_SIZE: usize = 64 * 1024;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Marker {
+    /// The header of the container.
+    Header,
+    /// A point where playback can start, e.g. a fragment starting with a
+    /// keyframe.
+    SyncPoint,
+    /// A point where the output can be split, e.g. a fragment without
+    /// keyframes.
+    BoundaryPoint,
+    /// Data without any specific meaning.
+    Unknown,
+    /// The trailer of the container.
+    Trailer,
+    /// A point where the muxer asked to flush.
+    FlushPoint,
+}
+
+impl From<AVIODataMarkerType> for Marker {
+    fn from(value: AVIODataMarkerType) -> Self {
+        match value {
+            AVIO_DATA_MARKER_HEADER => Marker::Header,
+            AVIO_DATA_MARKER_SYNC_POINT => Marker::SyncPoint,
+            AVIO_DATA_MARKER_BOUNDARY_POINT => Marker::BoundaryPoint,
+            AVIO_DATA_MARKER_UNKNOWN => Marker::Unknown,
+            AVIO_DATA_MARKER_TRAILER => Marker::Trailer,
+            AVIO_DATA_MARKER_FLUSH_POINT => Marker::FlushPoint,
+        }
+    }
+}
+
+/// A range of bytes of the output, starting at a marker.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct Range {
+    pub start: u64,
+    pub end: u64,
+    pub marker: Marker,
+    /// The time of the marker in `AV_TIME_BASE` units, if known.
+    pub time: Option<i64>,
+}
+
+type Handler = Box<dyn FnMut(Range) + Send>;
+
+struct Opaque {
+    writer: Box<dyn Write + Send>,
+    handler: Option<Handler>,
+
+    position: u64,
+    current: Option<Range>,
+}
+
+impl Opaque {
+    fn write(&mut self, data: &[u8], marker: Marker, time: i64) -> io::Result<()> {
+        let start = match (self.current, marker) {
+            (None, _) => true,
+            (Some(_), Marker::SyncPoint) | (Some(_), Marker::BoundaryPoint) => true,
+            (Some(_), Marker::Unknown) | (Some(_), Marker::FlushPoint) => false,
+            (Some(current), marker) => current.marker != marker,
+        };
+
+        if start {
+            self.report();
+            self.current = Some(Range {
+                start: self.position,
+                end: self.position,
+                marker,
+                time: if time == AV_NOPTS_VALUE {
+                    None
+                } else {
+                    Some(time)
+                },
+            });
+        }
+
+        self.writer.write_all(data)?;
+        self.position += data.len() as u64;
+
+        if let Some(ref mut current) = self.current {
+            current.end = self.position;
+        }
+
+        Ok(())
+    }
+
+    fn report(&mut self) {
+        if let (Some(range), Some(handler)) = (self.current.take(), self.handler.as_mut()) {
+            if range.end > range.start {
+                handler(range);
+            }
+        }
+    }
+}
+
+unsafe fn free(opaque: *mut c_void) {
+    let mut opaque = Box::from_raw(opaque as *mut Opaque);
+
+    opaque.report();
+    let _ = opaque.writer.flush();
+}
+
+fn status(result: io::Result<()>, size: c_int) -> c_int {
+    match result {
+        Ok(()) => size,
+        Err(e) => AVERROR(e.raw_os_error().unwrap_or(EIO)),
+    }
+}
+
+// the buffers written are const since FFmpeg 7.0
+#[cfg(not(feature = "ffmpeg_7_0"))]
+type WriteBuffer = *mut u8;
+#[cfg(feature = "ffmpeg_7_0")]
+type WriteBuffer = *const u8;
+
+unsafe extern "C" fn write_packet(opaque: *mut c_void, buf: WriteBuffer, size: c_int) -> c_int {
+    let opaque = &mut *(opaque as *mut Opaque);
+    let data = slice::from_raw_parts(buf, size as usize);
+
+    status(opaque.writer.write_all(data), size)
+}
+
+unsafe extern "C" fn write_data_type(
+    opaque: *mut c_void,
+    buf: WriteBuffer,
+    size: c_int,
+    kind: AVIODataMarkerType,
+    time: i64,
+) -> c_int {
+    let opaque = &mut *(opaque as *mut Opaque);
+    let data = slice::from_raw_parts(buf, size as usize);
+
+    status(opaque.write(data, kind.into(), time), size)
+}
+
+/// Create an output of `format` (e.g. "mp4") writing to `writer`.
+pub fn output_as<W>(writer: W, format: &str) -> Result<context::Output, Error>
+where
+    W: Write + Send + 'static,
+{
+    unsafe { open(Box::new(writer), format, None) }
+}
+
+/// Create an output of `format` writing to `writer`, calling `handler` with
+/// each range of bytes once the next marker is written.
+///
+/// The last range, usually the trailer, is reported when the output is
+/// dropped.
+pub fn output_as_with_ranges<W, F>(
+    writer: W,
+    format: &str,
+    handler: F,
+) -> Result<context::Output, Error>
+where
+    W: Write + Send + 'static,
+    F: FnMut(Range) + Send + 'static,
+{
+    unsafe { open(Box::new(writer), format, Some(Box::new(handler))) }
+}
+
+unsafe fn open(
+    writer: Box<dyn Write + Send>,
+    format: &str,
+    handler: Option<Handler>,
+) -> Result<context::Output, Error> {
+    let mut ps = ptr::null_mut();
+    let format = CString::new(format).unwrap();
+
+    match avformat_alloc_output_context2(&mut ps, ptr::null_mut(), format.as_ptr(), ptr::null()) {
+        0 => (),
+        e => return Err(Error::from(e)),
+    }
+
+    let buffer = av_malloc(BUFFER_SIZE) as *mut u8;
+
+    if buffer.is_null() {
+        avformat_free_context(ps);
+        return Err(Error::Other { errno: ENOMEM });
+    }
+
+    let ranges = handler.is_some();
+    let opaque = Box::into_raw(Box::new(Opaque {
+        writer,
+        handler,
+
+        position: 0,
+        current: None,
+    }));
+
+    let pb = avio_alloc_context(
+        buffer,
+        BUFFER_SIZE as c_int,
+        1,
+        opaque as *mut c_void,
+        None,
+        Some(write_packet),
+        None,
+    );
+
+    if pb.is_null() {
+        av_free(buffer as *mut c_void);
+        drop(Box::from_raw(opaque));
+        avformat_free_context(ps);
+
+        return Err(Error::Other { errno: ENOMEM });
+    }
+
+    if ranges {
+        (*pb).write_data_type = Some(write_data_type);
+    }
+
+    (*ps).pb = pb;
+    (*ps).flags |= AVFMT_FLAG_CUSTOM_IO;
+
+    Ok(context::Output::wrap_with(
+        ps,
+        destructor::Mode::OutputCustom(free),
+    ))
+}
@@ -7,6 +7,8 @@ pub mod stream;
 
 pub mod chapter;
 
+pub mod program;
+
 pub mod context;
 pub use self::context::Context;
 
@@ -14,22 +16,48 @@ pub mod format;
 pub use self::format::{flag, Flags};
 pub use self::format::{list, Input, Output};
 
+mod options;
+
+pub mod demuxer;
+
+pub mod dir;
+
+pub mod editlist;
+
+pub mod hls;
+
+pub mod id3v2;
+
+pub mod image2;
+
+pub mod info;
+pub use self::info::{probe, ChapterInfo, FormatInfo, MediaInfo, StreamInfo};
+
+pub mod io;
+
+pub mod mp4;
+
 pub mod network;
 
+pub mod vorbis;
+
 use std::ffi::{CStr, CString};
 use std::path::Path;
 use std::ptr;
 use std::str::from_utf8_unchecked;
 
 use ffi::*;
+use libc::{c_char, c_int};
 use {Dictionary, Error, Format};
 
+#[cfg(not(feature = "ffmpeg_5_0"))]
 pub fn register_all() {
     unsafe {
         av_register_all();
     }
 }
 
+#[cfg(not(feature = "ffmpeg_5_0"))]
 pub fn register(format: &Format) {
     match *format {
         Format::Input(ref format) => unsafe {
@@ -54,6 +82,31 @@ pub fn license() -> &'static str {
     unsafe { from_utf8_unchecked(CStr::from_ptr(avformat_license()).to_bytes()) }
 }
 
+/// Get the names of the protocols of the linked FFmpeg supporting input,
+/// and of those supporting output, e.g. to check for `https` or `srt`.
+pub fn protocols() -> (Vec<&'static str>, Vec<&'static str>) {
+    (protocols_of(0), protocols_of(1))
+}
+
+fn protocols_of(output: c_int) -> Vec<&'static str> {
+    let mut names = Vec::new();
+    let mut opaque = ptr::null_mut();
+
+    unsafe {
+        loop {
+            let name = avio_enum_protocols(&mut opaque, output);
+
+            if name.is_null() {
+                break;
+            }
+
+            names.push(from_utf8_unchecked(CStr::from_ptr(name).to_bytes()));
+        }
+    }
+
+    names
+}
+
 // XXX: use to_cstring when stable
 fn from_path<P: AsRef<Path>>(path: &P) -> CString {
     CString::new(path.as_ref().as_os_str().to_str().unwrap()).unwrap()
@@ -190,6 +243,41 @@ pub fn input_with_dictionary<P: AsRef<Path>>(
     }
 }
 
+/// Open an input with options, e.g. from `format::demuxer`, failing with
+/// `Error::OptionNotFound` when neither the demuxer nor the generic format
+/// context know any of them, where `input_with_dictionary` ignores them.
+pub fn input_with_options<'a, P: AsRef<Path>, O: Into<Dictionary<'a>>>(
+    path: &P,
+    options: O,
+) -> Result<context::Input, Error> {
+    unsafe {
+        let mut ps = ptr::null_mut();
+        let path = from_path(path);
+        let mut opts = options.into().disown();
+        let res = avformat_open_input(&mut ps, path.as_ptr(), ptr::null_mut(), &mut opts);
+        let unknown = !opts.is_null() && av_dict_count(opts) > 0;
+
+        Dictionary::own(opts);
+
+        match res {
+            0 if unknown => {
+                avformat_close_input(&mut ps);
+                Err(Error::OptionNotFound)
+            }
+
+            0 => match avformat_find_stream_info(ps, ptr::null_mut()) {
+                r if r >= 0 => Ok(context::Input::wrap(ps)),
+                e => {
+                    avformat_close_input(&mut ps);
+                    Err(Error::from(e))
+                }
+            },
+
+            e => Err(Error::from(e)),
+        }
+    }
+}
+
 pub fn input_with_interrupt<P: AsRef<Path>, F>(
     path: &P,
     closure: F,
@@ -324,3 +412,28 @@ pub fn output_as_with<P: AsRef<Path>>(
         }
     }
 }
+
+/// Create the SDP session description of RTP `outputs`, e.g. for clients
+/// of an RTP streaming server, after their headers were written.
+///
+/// A single output holding all the streams gives a session with a single
+/// address, one output per stream a session with an address per stream.
+pub fn sdp(outputs: &[&context::Output]) -> Result<String, Error> {
+    unsafe {
+        let mut contexts = outputs
+            .iter()
+            .map(|output| output.as_ptr() as *mut AVFormatContext)
+            .collect::<Vec<_>>();
+        let mut buf = vec![0 as c_char; 64 * 1024];
+
+        match av_sdp_create(
+            contexts.as_mut_ptr(),
+            contexts.len() as c_int,
+            buf.as_mut_ptr(),
+            buf.len() as c_int,
+        ) {
+            e if e < 0 => Err(Error::from(e)),
+            _ => Ok(CStr::from_ptr(buf.as_ptr()).to_string_lossy().into_owned()),
+        }
+    }
+}
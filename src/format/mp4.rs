@@ -0,0 +1,179 @@
+//! Options of the MOV/MP4 muxer, e.g. to write fragmented MP4 for CMAF and
+//! DASH, or MP4 files playable while downloaded.
+//!
+//! The options are given to `Output::write_header_with` as a dictionary,
+//! converted from `Options`, or set on the output beforehand with
+//! `Options::apply`.
+
+use std::time::Duration;
+
+use super::options::{self, micros};
+use format::context::Output;
+use Error;
+
+bitflags! {
+    /// The `movflags` option of the muxer.
+    #[derive(Default)]
+    pub struct Flags: u32 {
+        /// Write an initial `moov` atom without samples, for fragmented
+        /// outputs.
+        const EMPTY_MOOV           = 1 << 0;
+        /// Start a fragment at each video keyframe.
+        const FRAG_KEYFRAME        = 1 << 1;
+        const FRAG_EVERY_FRAME     = 1 << 2;
+        /// Write a fragment for each track rather than one for all.
+        const SEPARATE_MOOF        = 1 << 3;
+        /// Start fragments only when flushing the output.
+        const FRAG_CUSTOM          = 1 << 4;
+        /// Move the `moov` atom to the start of the file once written, for
+        /// progressive download.
+        const FASTSTART            = 1 << 5;
+        const OMIT_TFHD_OFFSET     = 1 << 6;
+        const DEFAULT_BASE_MOOF    = 1 << 7;
+        /// Write segments suitable for DASH.
+        const DASH                 = 1 << 8;
+        /// Write segments suitable for CMAF, since FFmpeg 4.3.
+        const CMAF                 = 1 << 9;
+        const FRAG_DISCONT         = 1 << 10;
+        const DELAY_MOOV           = 1 << 11;
+        const GLOBAL_SIDX          = 1 << 12;
+        const SKIP_SIDX            = 1 << 13;
+        const SKIP_TRAILER         = 1 << 14;
+        const NEGATIVE_CTS_OFFSETS = 1 << 15;
+    }
+}
+
+const NAMES: [(Flags, &str); 16] = [
+    (Flags::EMPTY_MOOV, "empty_moov"),
+    (Flags::FRAG_KEYFRAME, "frag_keyframe"),
+    (Flags::FRAG_EVERY_FRAME, "frag_every_frame"),
+    (Flags::SEPARATE_MOOF, "separate_moof"),
+    (Flags::FRAG_CUSTOM, "frag_custom"),
+    (Flags::FASTSTART, "faststart"),
+    (Flags::OMIT_TFHD_OFFSET, "omit_tfhd_offset"),
+    (Flags::DEFAULT_BASE_MOOF, "default_base_moof"),
+    (Flags::DASH, "dash"),
+    (Flags::CMAF, "cmaf"),
+    (Flags::FRAG_DISCONT, "frag_discont"),
+    (Flags::DELAY_MOOV, "delay_moov"),
+    (Flags::GLOBAL_SIDX, "global_sidx"),
+    (Flags::SKIP_SIDX, "skip_sidx"),
+    (Flags::SKIP_TRAILER, "skip_trailer"),
+    (Flags::NEGATIVE_CTS_OFFSETS, "negative_cts_offsets"),
+];
+
+impl Flags {
+    /// Get the value of the `movflags` option, e.g. `+faststart`.
+    pub fn to_option(&self) -> String {
+        options::flags(
+            NAMES
+                .iter()
+                .map(|&(flag, name)| (self.contains(flag), name)),
+        )
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Default, Debug)]
+pub struct Options {
+    flags: Flags,
+    frag_duration: Option<Duration>,
+    min_frag_duration: Option<Duration>,
+    frag_size: Option<u32>,
+}
+
+impl Options {
+    pub fn new() -> Self {
+        Options::default()
+    }
+
+    /// Options for a regular file whose index is at the start, so that it
+    /// can be played while downloaded.
+    pub fn faststart() -> Self {
+        Options::new().flags(Flags::FASTSTART)
+    }
+
+    /// Options for a fragmented file, whose fragments start at keyframes.
+    pub fn fragmented() -> Self {
+        Options::new().flags(Flags::FRAG_KEYFRAME | Flags::EMPTY_MOOV | Flags::DEFAULT_BASE_MOOF)
+    }
+
+    /// Options for CMAF segments, as used by DASH and low-latency HLS.
+    pub fn cmaf() -> Self {
+        Options::fragmented().flags(Flags::CMAF | Flags::SEPARATE_MOOF)
+    }
+
+    /// Add `flags` to the flags of the muxer.
+    pub fn flags(mut self, flags: Flags) -> Self {
+        self.flags |= flags;
+        self
+    }
+
+    /// Set the maximum duration of the fragments.
+    pub fn frag_duration(mut self, value: Duration) -> Self {
+        self.frag_duration = Some(value);
+        self
+    }
+
+    /// Set the minimum duration of the fragments, which only start at
+    /// keyframes once reached.
+    pub fn min_frag_duration(mut self, value: Duration) -> Self {
+        self.min_frag_duration = Some(value);
+        self
+    }
+
+    /// Set the maximum size of the fragments, in bytes.
+    pub fn frag_size(mut self, value: u32) -> Self {
+        self.frag_size = Some(value);
+        self
+    }
+
+    /// Set the options on the muxer of `output`, before writing the header.
+    pub fn apply(&self, output: &mut Output) -> Result<(), Error> {
+        options::apply(self.values(), output)
+    }
+
+    fn values(&self) -> Vec<(String, String)> {
+        let mut values = Vec::new();
+
+        if !self.flags.is_empty() {
+            values.push(("movflags".into(), self.flags.to_option()));
+        }
+
+        if let Some(value) = self.frag_duration {
+            values.push(("frag_duration".into(), micros(value).to_string()));
+        }
+
+        if let Some(value) = self.min_frag_duration {
+            values.push(("min_frag_duration".into(), micros(value).to_string()));
+        }
+
+        if let Some(value) = self.frag_size {
+            values.push(("frag_size".into(), value.to_string()));
+        }
+
+        values
+    }
+}
+
+into_dictionary!(Options, |value: Options| value.values());
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values() {
+        let options = Options::fragmented().frag_duration(Duration::from_millis(2500));
+
+        assert_eq!(
+            options.values(),
+            vec![
+                (
+                    String::from("movflags"),
+                    String::from("+empty_moov+frag_keyframe+default_base_moof")
+                ),
+                (String::from("frag_duration"), String::from("2500000")),
+            ]
+        );
+    }
+}
@@ -1,13 +1,231 @@
+//! Network protocols, and typed options of the SRT, RTSP and RTMP ones and
+//! of TLS to pass when opening inputs or outputs, e.g. to
+//! `format::input_with_options` or `format::output_with`.
+
+use std::time::Duration;
+
+use super::options::{micros, millis};
 use ffi::*;
 
+/// Initialize the network libraries, e.g. of TLS, which is otherwise done
+/// when opening each network URL, not thread safely before FFmpeg 4.0.
 pub fn init() {
     unsafe {
         avformat_network_init();
     }
 }
 
+/// Release the network libraries, once for each call to `init()`.
 pub fn deinit() {
     unsafe {
         avformat_network_deinit();
     }
 }
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum SrtMode {
+    Caller,
+    Listener,
+    Rendezvous,
+}
+
+/// Options of the SRT protocol.
+#[derive(Eq, PartialEq, Clone, Default, Debug)]
+pub struct Srt {
+    options: Vec<(String, String)>,
+}
+
+impl Srt {
+    /// Set the receiver delay absorbing packet losses and jitter.
+    pub fn latency(self, value: Duration) -> Self {
+        self.set("latency", micros(value).to_string())
+    }
+
+    /// Set the passphrase of the encryption, of 10 to 79 characters.
+    pub fn passphrase<S: Into<String>>(self, value: S) -> Self {
+        self.set("passphrase", value)
+    }
+
+    /// Set the length of the encryption key in bytes, 16, 24 or 32.
+    pub fn key_length(self, value: u8) -> Self {
+        self.set("pbkeylen", value.to_string())
+    }
+
+    pub fn mode(self, value: SrtMode) -> Self {
+        self.set(
+            "mode",
+            match value {
+                SrtMode::Caller => "caller",
+                SrtMode::Listener => "listener",
+                SrtMode::Rendezvous => "rendezvous",
+            },
+        )
+    }
+
+    /// Set the stream id, e.g. the resource a server is asked for.
+    pub fn stream_id<S: Into<String>>(self, value: S) -> Self {
+        self.set("streamid", value)
+    }
+
+    pub fn connect_timeout(self, value: Duration) -> Self {
+        self.set("connect_timeout", millis(value).to_string())
+    }
+
+    /// Set the timeout of reads and writes.
+    pub fn timeout(self, value: Duration) -> Self {
+        self.set("timeout", micros(value).to_string())
+    }
+}
+
+options!(Srt);
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum RtspTransport {
+    Udp,
+    Tcp,
+    UdpMulticast,
+    Http,
+}
+
+/// Options of the RTSP protocol.
+#[derive(Eq, PartialEq, Clone, Default, Debug)]
+pub struct Rtsp {
+    options: Vec<(String, String)>,
+}
+
+impl Rtsp {
+    /// Set the lower transport of the streams, e.g. TCP to get through
+    /// firewalls dropping UDP.
+    pub fn transport(self, value: RtspTransport) -> Self {
+        self.set(
+            "rtsp_transport",
+            match value {
+                RtspTransport::Udp => "udp",
+                RtspTransport::Tcp => "tcp",
+                RtspTransport::UdpMulticast => "udp_multicast",
+                RtspTransport::Http => "http",
+            },
+        )
+    }
+
+    /// Try TCP first when the server supports several transports.
+    pub fn prefer_tcp(self) -> Self {
+        self.set("rtsp_flags", "prefer_tcp")
+    }
+
+    /// Set the timeout of the socket reads and writes.
+    #[cfg(not(feature = "ffmpeg_5_0"))]
+    pub fn timeout(self, value: Duration) -> Self {
+        self.set("stimeout", micros(value).to_string())
+    }
+
+    /// Set the timeout of the socket reads and writes.
+    #[cfg(feature = "ffmpeg_5_0")]
+    pub fn timeout(self, value: Duration) -> Self {
+        self.set("timeout", micros(value).to_string())
+    }
+
+    pub fn user_agent<S: Into<String>>(self, value: S) -> Self {
+        self.set("user_agent", value)
+    }
+
+    /// Set the number of packets buffered to reorder the RTP packets
+    /// received over UDP.
+    pub fn reorder_queue_size(self, value: u32) -> Self {
+        self.set("reorder_queue_size", value.to_string())
+    }
+}
+
+options!(Rtsp);
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum RtmpLive {
+    /// Try a live stream, then a recorded one.
+    Any,
+    Live,
+    Recorded,
+}
+
+/// Options of the RTMP protocol.
+#[derive(Eq, PartialEq, Clone, Default, Debug)]
+pub struct Rtmp {
+    options: Vec<(String, String)>,
+}
+
+impl Rtmp {
+    /// Set whether the stream to play is live or recorded.
+    pub fn live(self, value: RtmpLive) -> Self {
+        self.set(
+            "rtmp_live",
+            match value {
+                RtmpLive::Any => "any",
+                RtmpLive::Live => "live",
+                RtmpLive::Recorded => "recorded",
+            },
+        )
+    }
+
+    /// Set the duration the client asks the server to buffer.
+    pub fn buffer(self, value: Duration) -> Self {
+        self.set("rtmp_buffer", millis(value).to_string())
+    }
+
+    /// Set the application, when not in the path of the URL.
+    pub fn app<S: Into<String>>(self, value: S) -> Self {
+        self.set("rtmp_app", value)
+    }
+
+    /// Set the stream to play or publish, when not in the path of the URL.
+    pub fn playpath<S: Into<String>>(self, value: S) -> Self {
+        self.set("rtmp_playpath", value)
+    }
+
+    pub fn tc_url<S: Into<String>>(self, value: S) -> Self {
+        self.set("rtmp_tcurl", value)
+    }
+}
+
+options!(Rtmp);
+
+/// Options of TLS, e.g. for HTTPS inputs, or RTMPS inputs and outputs.
+///
+/// FFmpeg has no process wide TLS configuration, the options are given to
+/// each input or output, along with those of the protocol if any, e.g.
+/// merged with `DictionaryMut::merge`.
+#[derive(Eq, PartialEq, Clone, Default, Debug)]
+pub struct Tls {
+    options: Vec<(String, String)>,
+}
+
+impl Tls {
+    /// Set the file of the certificates of the authorities to trust, instead
+    /// of those of the system.
+    pub fn ca_file<S: Into<String>>(self, value: S) -> Self {
+        self.set("ca_file", value)
+    }
+
+    /// Set whether to verify the certificate of the peer against the
+    /// authorities, which FFmpeg does not by default.
+    pub fn verify(self, value: bool) -> Self {
+        self.set("tls_verify", if value { "1" } else { "0" })
+    }
+
+    /// Set the host name to verify the certificate of the peer against,
+    /// instead of the host of the URL.
+    pub fn verify_host<S: Into<String>>(self, value: S) -> Self {
+        self.set("verifyhost", value)
+    }
+
+    /// Set the file of the certificate to present, e.g. for client
+    /// authentication.
+    pub fn cert_file<S: Into<String>>(self, value: S) -> Self {
+        self.set("cert_file", value)
+    }
+
+    /// Set the file of the private key of the certificate.
+    pub fn key_file<S: Into<String>>(self, value: S) -> Self {
+        self.set("key_file", value)
+    }
+}
+
+options!(Tls);
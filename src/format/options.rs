@@ -0,0 +1,38 @@
+//! Helpers shared by the typed options of the demuxers, muxers and
+//! protocols.
+
+use std::time::Duration;
+
+use format::context::Output;
+use option::Settable;
+use Error;
+
+/// Get the value of a flags option, e.g. `+faststart+frag_keyframe`, from
+/// the names of the flags and whether they are set.
+pub fn flags<'a, I: IntoIterator<Item = (bool, &'a str)>>(flags: I) -> String {
+    flags
+        .into_iter()
+        .filter(|&(set, _)| set)
+        .map(|(_, name)| format!("+{}", name))
+        .collect()
+}
+
+/// Set `options` on the muxer of `output`, before writing the header.
+pub fn apply<I: IntoIterator<Item = (String, String)>>(
+    options: I,
+    output: &mut Output,
+) -> Result<(), Error> {
+    for (name, value) in options {
+        output.set_str(&name, &value)?;
+    }
+
+    Ok(())
+}
+
+pub fn micros(value: Duration) -> u64 {
+    value.as_secs() * 1_000_000 + u64::from(value.subsec_micros())
+}
+
+pub fn millis(value: Duration) -> u64 {
+    value.as_secs() * 1000 + u64::from(value.subsec_millis())
+}
@@ -0,0 +1,8 @@
+//! Programs of MPEG-TS inputs and outputs, i.e. the services of a broadcast
+//! multiplex, each grouping the streams of a channel.
+
+mod program;
+pub use self::program::{Program, StreamIter};
+
+mod program_mut;
+pub use self::program_mut::ProgramMut;
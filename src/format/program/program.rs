@@ -0,0 +1,107 @@
+use ffi::*;
+use libc::c_uint;
+use {DictionaryRef, Discard, Stream};
+
+use format::context::common::Context;
+
+// WARNING: index refers to the offset in the programs array (starting from 0)
+// it is not the id, which is the service id of MPEG-TS programs
+pub struct Program<'a> {
+    context: &'a Context,
+    index: usize,
+}
+
+impl<'a> Program<'a> {
+    pub unsafe fn wrap(context: &Context, index: usize) -> Program {
+        Program { context, index }
+    }
+
+    pub unsafe fn as_ptr(&self) -> *const AVProgram {
+        *(*self.context.as_ptr()).programs.add(self.index)
+    }
+}
+
+impl<'a> Program<'a> {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn id(&self) -> i32 {
+        unsafe { (*self.as_ptr()).id }
+    }
+
+    /// Get the program number, as in the PAT of MPEG-TS inputs.
+    pub fn number(&self) -> i32 {
+        unsafe { (*self.as_ptr()).program_num }
+    }
+
+    pub fn pmt_pid(&self) -> i32 {
+        unsafe { (*self.as_ptr()).pmt_pid }
+    }
+
+    pub fn pcr_pid(&self) -> i32 {
+        unsafe { (*self.as_ptr()).pcr_pid }
+    }
+
+    pub fn discard(&self) -> Discard {
+        unsafe { Discard::from((*self.as_ptr()).discard) }
+    }
+
+    /// Iterate over the streams of the program.
+    pub fn streams(&self) -> StreamIter<'a> {
+        StreamIter {
+            context: self.context,
+            program: unsafe { self.as_ptr() },
+            current: 0,
+        }
+    }
+
+    pub fn metadata(&self) -> DictionaryRef {
+        unsafe { DictionaryRef::wrap((*self.as_ptr()).metadata) }
+    }
+}
+
+impl<'a> PartialEq for Program<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        unsafe { self.as_ptr() == other.as_ptr() }
+    }
+}
+
+pub struct StreamIter<'a> {
+    context: &'a Context,
+    program: *const AVProgram,
+    current: c_uint,
+}
+
+impl<'a> Iterator for StreamIter<'a> {
+    type Item = Stream<'a>;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        unsafe {
+            if self.current >= (*self.program).nb_stream_indexes {
+                return None;
+            }
+
+            self.current += 1;
+
+            let index = *(*self.program)
+                .stream_index
+                .add((self.current - 1) as usize);
+
+            Some(Stream::wrap(self.context, index as usize))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        unsafe {
+            let length = (*self.program).nb_stream_indexes as usize;
+
+            (
+                length - self.current as usize,
+                Some(length - self.current as usize),
+            )
+        }
+    }
+}
+
+impl<'a> ExactSizeIterator for StreamIter<'a> {}
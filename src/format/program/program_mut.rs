@@ -0,0 +1,97 @@
+use std::mem;
+use std::ops::Deref;
+
+use super::Program;
+use ffi::*;
+use format::context::common::Context;
+use libc::c_uint;
+use {Dictionary, DictionaryMut, Discard, Error};
+
+// WARNING: index refers to the offset in the programs array (starting from 0)
+// it is not the id, which is the service id of MPEG-TS programs
+pub struct ProgramMut<'a> {
+    context: &'a mut Context,
+    index: usize,
+
+    immutable: Program<'a>,
+}
+
+impl<'a> ProgramMut<'a> {
+    pub unsafe fn wrap(context: &mut Context, index: usize) -> ProgramMut {
+        ProgramMut {
+            context: mem::transmute_copy(&context),
+            index,
+
+            immutable: Program::wrap(mem::transmute_copy(&context), index),
+        }
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVProgram {
+        *(*self.context.as_mut_ptr()).programs.add(self.index)
+    }
+}
+
+impl<'a> ProgramMut<'a> {
+    pub fn set_number(&mut self, value: i32) {
+        unsafe {
+            (*self.as_mut_ptr()).program_num = value;
+        }
+    }
+
+    pub fn set_pmt_pid(&mut self, value: i32) {
+        unsafe {
+            (*self.as_mut_ptr()).pmt_pid = value;
+        }
+    }
+
+    pub fn set_pcr_pid(&mut self, value: i32) {
+        unsafe {
+            (*self.as_mut_ptr()).pcr_pid = value;
+        }
+    }
+
+    /// Set which packets of the streams of the program demuxers drop, e.g.
+    /// `Discard::All` to only read the other programs of an input.
+    pub fn set_discard(&mut self, value: Discard) {
+        unsafe {
+            (*self.as_mut_ptr()).discard = value.into();
+        }
+    }
+
+    /// Add the stream at `index` to the program, a stream being possibly
+    /// shared by several programs.
+    pub fn add_stream(&mut self, index: usize) -> Result<(), Error> {
+        unsafe {
+            if index >= (*self.context.as_ptr()).nb_streams as usize {
+                return Err(Error::StreamNotFound);
+            }
+
+            let id = self.id();
+            av_program_add_stream_index(self.context.as_mut_ptr(), id, index as c_uint);
+
+            Ok(())
+        }
+    }
+
+    pub fn set_metadata<K: AsRef<str>, V: AsRef<str>>(&mut self, key: K, value: V) {
+        // dictionary.set() allocates the AVDictionary the first time a key/value is inserted
+        // so we want to update the metadata dictionary afterwards
+        unsafe {
+            let mut dictionary = Dictionary::own(self.metadata().as_mut_ptr());
+            dictionary.set(key.as_ref(), value.as_ref());
+            (*self.as_mut_ptr()).metadata = dictionary.disown();
+        }
+    }
+
+    pub fn metadata(&mut self) -> DictionaryMut {
+        unsafe { DictionaryMut::wrap((*self.as_mut_ptr()).metadata) }
+    }
+}
+
+impl<'a> Deref for ProgramMut<'a> {
+    type Target = Program<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.immutable
+    }
+}
@@ -0,0 +1,85 @@
+use std::ffi::{CStr, CString};
+use std::ops::Deref;
+use std::ptr;
+use std::slice;
+use std::str::from_utf8_unchecked;
+
+use super::Stream;
+use codec;
+use ffi::*;
+use media;
+
+/// An attachment stream, as found in Matroska files (e.g. fonts for ASS
+/// subtitles).
+pub struct Attachment<'a> {
+    stream: Stream<'a>,
+}
+
+impl<'a> Attachment<'a> {
+    /// Get the stream as an attachment, if it is one.
+    pub fn new(stream: Stream<'a>) -> Option<Self> {
+        if stream.parameters().medium() == media::Type::Attachment {
+            Some(Attachment { stream })
+        } else {
+            None
+        }
+    }
+
+    pub fn filename(&self) -> Option<&'a str> {
+        self.tag("filename")
+    }
+
+    pub fn mime_type(&self) -> Option<&'a str> {
+        self.tag("mimetype")
+    }
+
+    /// Get the content of the attached file.
+    pub fn data(&self) -> &'a [u8] {
+        unsafe {
+            let par = (*self.stream.as_ptr()).codecpar;
+
+            if (*par).extradata.is_null() {
+                &[]
+            } else {
+                slice::from_raw_parts((*par).extradata, (*par).extradata_size as usize)
+            }
+        }
+    }
+
+    fn tag(&self, key: &str) -> Option<&'a str> {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            let entry = av_dict_get(
+                (*self.stream.as_ptr()).metadata,
+                key.as_ptr(),
+                ptr::null_mut(),
+                0,
+            );
+
+            if entry.is_null() {
+                None
+            } else {
+                Some(from_utf8_unchecked(
+                    CStr::from_ptr((*entry).value).to_bytes(),
+                ))
+            }
+        }
+    }
+}
+
+impl<'a> Deref for Attachment<'a> {
+    type Target = Stream<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.stream
+    }
+}
+
+/// Guess the codec of an attachment from its MIME type.
+pub fn codec(mime_type: &str) -> codec::Id {
+    match mime_type {
+        "application/x-truetype-font" | "application/x-font" | "font/ttf" => codec::Id::TTF,
+        "application/vnd.ms-opentype" | "font/otf" => codec::Id::OTF,
+        _ => codec::Id::None,
+    }
+}
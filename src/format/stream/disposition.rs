@@ -2,6 +2,8 @@ use ffi::*;
 use libc::c_int;
 
 bitflags! {
+    /// The role of a stream, e.g. the default audio track or forced
+    /// subtitles, which players use to pick the streams to play.
     pub struct Disposition: c_int {
         const DEFAULT          = AV_DISPOSITION_DEFAULT;
         const DUB              = AV_DISPOSITION_DUB;
@@ -17,5 +19,12 @@ bitflags! {
         const CAPTIONS         = AV_DISPOSITION_CAPTIONS;
         const DESCRIPTIONS     = AV_DISPOSITION_DESCRIPTIONS;
         const METADATA         = AV_DISPOSITION_METADATA;
+        const TIMED_THUMBNAILS = AV_DISPOSITION_TIMED_THUMBNAILS;
+        #[cfg(feature = "ffmpeg_4_1")]
+        const DEPENDENT        = AV_DISPOSITION_DEPENDENT;
+        #[cfg(feature = "ffmpeg_4_4")]
+        const STILL_IMAGE      = AV_DISPOSITION_STILL_IMAGE;
+        #[cfg(feature = "ffmpeg_5_1")]
+        const NON_DIEGETIC     = AV_DISPOSITION_NON_DIEGETIC;
     }
 }
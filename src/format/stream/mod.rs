@@ -1,3 +1,6 @@
+pub mod attachment;
+pub use self::attachment::Attachment;
+
 pub mod disposition;
 pub use self::disposition::Disposition;
 
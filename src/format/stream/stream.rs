@@ -1,9 +1,10 @@
 use super::Disposition;
-use codec::{self, packet};
+use codec;
+use codec::packet::{self, Mut};
 use ffi::*;
 use format::context::common::Context;
 use libc::c_int;
-use {DictionaryRef, Discard, Rational};
+use {DictionaryRef, Discard, Error, Packet, Rational};
 
 #[derive(Debug)]
 pub struct Stream<'a> {
@@ -26,6 +27,12 @@ impl<'a> Stream<'a> {
         unsafe { (*self.as_ptr()).id }
     }
 
+    #[cfg(not(feature = "ffmpeg_5_0"))]
+    #[deprecated(
+        since = "4.4.0",
+        note = "Underlying field AVStream::codec has been deprecated since FFmpeg 3.1 and removed \
+        in FFmpeg 5.0; consider switching to codec::Context::from_parameters(stream.parameters())"
+    )]
     pub fn codec(&self) -> codec::Context {
         unsafe { codec::Context::wrap((*self.as_ptr()).codec, Some(self.context.destructor())) }
     }
@@ -60,6 +67,31 @@ impl<'a> Stream<'a> {
         unsafe { Disposition::from_bits_truncate((*self.as_ptr()).disposition) }
     }
 
+    /// Whether the stream is an attached picture, e.g. the cover art of an
+    /// MP3, FLAC or M4A file.
+    pub fn is_attached_picture(&self) -> bool {
+        self.disposition().contains(Disposition::ATTACHED_PIC)
+    }
+
+    /// Get the image of an attached picture stream, which demuxers read
+    /// with the header rather than as a packet.
+    pub fn attached_picture(&self) -> Result<Option<Packet>, Error> {
+        unsafe {
+            let picture = &(*self.as_ptr()).attached_pic;
+
+            if !self.is_attached_picture() || picture.size <= 0 {
+                return Ok(None);
+            }
+
+            let mut packet = Packet::empty();
+
+            match av_packet_ref(packet.as_mut_ptr(), picture) {
+                0 => Ok(Some(packet)),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
     pub fn discard(&self) -> Discard {
         unsafe { Discard::from((*self.as_ptr()).discard) }
     }
@@ -69,7 +101,7 @@ impl<'a> Stream<'a> {
     }
 
     pub fn rate(&self) -> Rational {
-        unsafe { Rational::from(av_stream_get_r_frame_rate(self.as_ptr())) }
+        unsafe { Rational::from((*self.as_ptr()).r_frame_rate) }
     }
 
     pub fn avg_frame_rate(&self) -> Rational {
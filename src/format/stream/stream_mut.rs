@@ -1,7 +1,8 @@
+use std::ffi::CString;
 use std::mem;
 use std::ops::Deref;
 
-use super::Stream;
+use super::{Disposition, Stream};
 use ffi::*;
 use format::context::common::Context;
 use {codec, Dictionary, Rational};
@@ -29,6 +30,12 @@ impl<'a> StreamMut<'a> {
 }
 
 impl<'a> StreamMut<'a> {
+    pub fn set_id(&mut self, value: i32) {
+        unsafe {
+            (*self.as_mut_ptr()).id = value;
+        }
+    }
+
     pub fn set_time_base<R: Into<Rational>>(&mut self, value: R) {
         unsafe {
             (*self.as_mut_ptr()).time_base = value.into().into();
@@ -37,7 +44,7 @@ impl<'a> StreamMut<'a> {
 
     pub fn set_rate<R: Into<Rational>>(&mut self, value: R) {
         unsafe {
-            av_stream_set_r_frame_rate(self.as_mut_ptr(), value.into().into());
+            (*self.as_mut_ptr()).r_frame_rate = value.into().into();
         }
     }
 
@@ -55,12 +62,47 @@ impl<'a> StreamMut<'a> {
         }
     }
 
+    /// Set the role of the stream, e.g. `Disposition::DEFAULT` for the
+    /// audio track to play or `Disposition::FORCED` for subtitles, which
+    /// replaces the previous one.
+    pub fn set_disposition(&mut self, value: Disposition) {
+        unsafe {
+            (*self.as_mut_ptr()).disposition = value.bits();
+        }
+    }
+
     pub fn set_metadata(&mut self, metadata: Dictionary) {
         unsafe {
             let metadata = metadata.disown();
+            av_dict_free(&mut (*self.as_mut_ptr()).metadata);
             (*self.as_mut_ptr()).metadata = metadata;
         }
     }
+
+    /// Set the language of the stream, an ISO 639-2 code, e.g. `"eng"`,
+    /// keeping the other metadata.
+    pub fn set_language(&mut self, code: &str) {
+        self.set_tag("language", code);
+    }
+
+    /// Set the name of the track, keeping the other metadata.
+    pub fn set_title(&mut self, title: &str) {
+        self.set_tag("title", title);
+    }
+
+    fn set_tag(&mut self, key: &str, value: &str) {
+        let key = CString::new(key).unwrap();
+        let value = CString::new(value).unwrap();
+
+        unsafe {
+            av_dict_set(
+                &mut (*self.as_mut_ptr()).metadata,
+                key.as_ptr(),
+                value.as_ptr(),
+                0,
+            );
+        }
+    }
 }
 
 impl<'a> Deref for StreamMut<'a> {
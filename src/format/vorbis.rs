@@ -0,0 +1,264 @@
+//! Vorbis comments, as written by the Ogg (Vorbis, Opus, FLAC) and FLAC
+//! muxers.
+//!
+//! The muxers take the comments from the stream metadata, use
+//! `Dictionary::try_from(comments)` to get a `Dictionary` to pass to
+//! `StreamMut::set_metadata`.
+
+use std::convert::TryFrom;
+use std::ffi::CString;
+use std::ptr;
+
+use ffi::*;
+use libc::{c_char, c_int};
+use {Dictionary, Error};
+
+/// The type of a picture, as defined by the FLAC `METADATA_BLOCK_PICTURE`.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum PictureType {
+    Other,
+    FileIcon,
+    OtherFileIcon,
+    FrontCover,
+    BackCover,
+    Leaflet,
+    Media,
+    LeadArtist,
+    Artist,
+    Conductor,
+    Band,
+    Composer,
+    Lyricist,
+    RecordingLocation,
+    DuringRecording,
+    DuringPerformance,
+    ScreenCapture,
+    BrightColoredFish,
+    Illustration,
+    BandLogo,
+    PublisherLogo,
+}
+
+impl From<PictureType> for u32 {
+    fn from(value: PictureType) -> u32 {
+        value as u32
+    }
+}
+
+/// A picture to embed in the comments, e.g. a cover.
+#[derive(Clone, Debug)]
+pub struct Picture {
+    pub kind: PictureType,
+    pub mime_type: String,
+    pub description: String,
+
+    pub width: u32,
+    pub height: u32,
+    /// Bits per pixel.
+    pub depth: u32,
+    /// Number of colors for indexed images, 0 otherwise.
+    pub colors: u32,
+
+    pub data: Vec<u8>,
+}
+
+impl Picture {
+    pub fn new<S: Into<String>>(kind: PictureType, mime_type: S, data: Vec<u8>) -> Self {
+        Picture {
+            kind,
+            mime_type: mime_type.into(),
+            description: String::new(),
+
+            width: 0,
+            height: 0,
+            depth: 0,
+            colors: 0,
+
+            data,
+        }
+    }
+
+    /// Serialize the picture as a FLAC `METADATA_BLOCK_PICTURE`, without the
+    /// metadata block header.
+    pub fn to_block(&self) -> Vec<u8> {
+        fn push(block: &mut Vec<u8>, value: u32) {
+            block.extend_from_slice(&value.to_be_bytes());
+        }
+
+        let mut block = Vec::with_capacity(
+            32 + self.mime_type.len() + self.description.len() + self.data.len(),
+        );
+
+        push(&mut block, self.kind.into());
+        push(&mut block, self.mime_type.len() as u32);
+        block.extend_from_slice(self.mime_type.as_bytes());
+        push(&mut block, self.description.len() as u32);
+        block.extend_from_slice(self.description.as_bytes());
+        push(&mut block, self.width);
+        push(&mut block, self.height);
+        push(&mut block, self.depth);
+        push(&mut block, self.colors);
+        push(&mut block, self.data.len() as u32);
+        block.extend_from_slice(&self.data);
+
+        block
+    }
+
+    /// Serialize the picture as the base64 value of a
+    /// `METADATA_BLOCK_PICTURE` comment.
+    pub fn to_base64(&self) -> String {
+        let block = self.to_block();
+        let mut out = vec![0 as c_char; (block.len() + 2) / 3 * 4 + 1];
+
+        unsafe {
+            av_base64_encode(
+                out.as_mut_ptr(),
+                out.len() as c_int,
+                block.as_ptr(),
+                block.len() as c_int,
+            );
+        }
+
+        out.pop();
+        out.into_iter().map(|c| c as u8 as char).collect()
+    }
+}
+
+/// A set of Vorbis comments, fields may be repeated.
+#[derive(Clone, Debug, Default)]
+pub struct Comments {
+    fields: Vec<(String, String)>,
+}
+
+impl Comments {
+    pub fn new() -> Self {
+        Comments { fields: Vec::new() }
+    }
+
+    /// Set `key` to `value`, replacing any existing field with the same name.
+    pub fn set<K: AsRef<str>, V: Into<String>>(&mut self, key: K, value: V) -> &mut Self {
+        self.remove(key.as_ref());
+        self.add(key, value)
+    }
+
+    /// Add a field, keeping existing fields with the same name (e.g. several
+    /// artists).
+    pub fn add<K: AsRef<str>, V: Into<String>>(&mut self, key: K, value: V) -> &mut Self {
+        self.fields
+            .push((key.as_ref().to_uppercase(), value.into()));
+        self
+    }
+
+    /// Remove all the fields named `key`.
+    pub fn remove(&mut self, key: &str) -> &mut Self {
+        self.fields
+            .retain(|&(ref k, _)| !k.eq_ignore_ascii_case(key));
+        self
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|&&(ref k, _)| k.eq_ignore_ascii_case(key))
+            .map(|&(_, ref v)| v.as_str())
+    }
+
+    pub fn fields(&self) -> &[(String, String)] {
+        &self.fields
+    }
+
+    pub fn set_title<V: Into<String>>(&mut self, value: V) -> &mut Self {
+        self.set("TITLE", value)
+    }
+
+    pub fn set_artist<V: Into<String>>(&mut self, value: V) -> &mut Self {
+        self.set("ARTIST", value)
+    }
+
+    pub fn set_album<V: Into<String>>(&mut self, value: V) -> &mut Self {
+        self.set("ALBUM", value)
+    }
+
+    pub fn set_album_artist<V: Into<String>>(&mut self, value: V) -> &mut Self {
+        self.set("ALBUMARTIST", value)
+    }
+
+    pub fn set_genre<V: Into<String>>(&mut self, value: V) -> &mut Self {
+        self.set("GENRE", value)
+    }
+
+    pub fn set_date<V: Into<String>>(&mut self, value: V) -> &mut Self {
+        self.set("DATE", value)
+    }
+
+    pub fn set_track(&mut self, number: u32, total: Option<u32>) -> &mut Self {
+        self.set("TRACKNUMBER", number.to_string());
+
+        match total {
+            Some(total) => self.set("TRACKTOTAL", total.to_string()),
+            None => self.remove("TRACKTOTAL"),
+        }
+    }
+
+    pub fn set_disc(&mut self, number: u32, total: Option<u32>) -> &mut Self {
+        self.set("DISCNUMBER", number.to_string());
+
+        match total {
+            Some(total) => self.set("DISCTOTAL", total.to_string()),
+            None => self.remove("DISCTOTAL"),
+        }
+    }
+
+    /// Embed a picture as a `METADATA_BLOCK_PICTURE` field.
+    pub fn add_picture(&mut self, picture: &Picture) -> &mut Self {
+        self.add("METADATA_BLOCK_PICTURE", picture.to_base64())
+    }
+}
+
+impl<'a> TryFrom<Comments> for Dictionary<'a> {
+    type Error = Error;
+
+    fn try_from(value: Comments) -> Result<Dictionary<'a>, Error> {
+        unsafe {
+            let mut dictionary = Dictionary::own(ptr::null_mut());
+
+            for (key, value) in value.fields {
+                let key = CString::new(key).map_err(|_| Error::InvalidData)?;
+                let value = CString::new(value).map_err(|_| Error::InvalidData)?;
+                let mut ptr = dictionary.disown();
+                let res = av_dict_set(&mut ptr, key.as_ptr(), value.as_ptr(), AV_DICT_MULTIKEY);
+
+                dictionary = Dictionary::own(ptr);
+
+                if res < 0 {
+                    return Err(Error::from(res));
+                }
+            }
+
+            Ok(dictionary)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_picture_block() {
+        let mut picture = Picture::new(PictureType::FrontCover, "image/png", vec![1, 2, 3]);
+        picture.width = 1;
+        picture.height = 2;
+
+        let block = picture.to_block();
+
+        assert_eq!(&block[0..4], &[0, 0, 0, 3]);
+        assert_eq!(&block[4..8], &[0, 0, 0, 9]);
+        assert_eq!(&block[8..17], b"image/png");
+        assert_eq!(&block[17..21], &[0, 0, 0, 0]);
+        assert_eq!(&block[21..25], &[0, 0, 0, 1]);
+        assert_eq!(&block[25..29], &[0, 0, 0, 2]);
+        assert_eq!(&block[37..41], &[0, 0, 0, 3]);
+        assert_eq!(&block[41..], &[1, 2, 3]);
+    }
+}
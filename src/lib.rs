@@ -32,11 +32,17 @@ pub extern crate ffmpeg_sys_next as sys;
 #[cfg(feature = "image")]
 extern crate image;
 extern crate libc;
+#[cfg(feature = "ndarray")]
+extern crate ndarray;
+#[cfg(feature = "serde")]
+extern crate serde;
 
 pub use sys as ffi;
 
 #[macro_use]
 pub mod util;
+pub use util::audio_fifo::{self, AudioFifo};
+pub use util::buffer::{self, Buffer, BufferPool};
 pub use util::channel_layout::{self, ChannelLayout};
 pub use util::chroma;
 pub use util::color;
@@ -46,6 +52,7 @@ pub use util::dictionary::Owned as Dictionary;
 pub use util::dictionary::Ref as DictionaryRef;
 pub use util::error::{self, Error};
 pub use util::frame::{self, Frame};
+pub use util::hwcontext;
 pub use util::log;
 pub use util::mathematics::{self, rescale, Rescale, Rounding};
 pub use util::media;
@@ -53,6 +60,7 @@ pub use util::option;
 pub use util::picture;
 pub use util::rational::{self, Rational};
 pub use util::time;
+pub use util::timecode::{self, Timecode};
 
 #[cfg(feature = "format")]
 pub mod format;
@@ -61,6 +69,8 @@ pub use format::chapter::{Chapter, ChapterMut};
 #[cfg(feature = "format")]
 pub use format::format::Format;
 #[cfg(feature = "format")]
+pub use format::program::{Program, ProgramMut};
+#[cfg(feature = "format")]
 pub use format::stream::{Stream, StreamMut};
 
 #[cfg(feature = "codec")]
@@ -75,7 +85,7 @@ pub use codec::discard::Discard;
 pub use codec::field_order::FieldOrder;
 #[cfg(feature = "codec")]
 pub use codec::packet::{self, Packet};
-#[cfg(feature = "codec")]
+#[cfg(all(feature = "codec", not(feature = "ffmpeg_5_0")))]
 pub use codec::picture::Picture;
 #[cfg(feature = "codec")]
 pub use codec::subtitle::{self, Subtitle};
@@ -92,18 +102,21 @@ pub mod filter;
 #[cfg(feature = "filter")]
 pub use filter::Filter;
 
+#[cfg(feature = "codec")]
+pub mod pipeline;
+
 pub mod software;
 
 fn init_error() {
     util::error::register_all();
 }
 
-#[cfg(feature = "format")]
+#[cfg(all(feature = "format", not(feature = "ffmpeg_5_0")))]
 fn init_format() {
     format::register_all();
 }
 
-#[cfg(not(feature = "format"))]
+#[cfg(any(not(feature = "format"), feature = "ffmpeg_5_0"))]
 fn init_format() {}
 
 #[cfg(feature = "device")]
@@ -114,12 +127,12 @@ fn init_device() {
 #[cfg(not(feature = "device"))]
 fn init_device() {}
 
-#[cfg(feature = "filter")]
+#[cfg(all(feature = "filter", not(feature = "ffmpeg_5_0")))]
 fn init_filter() {
     filter::register_all();
 }
 
-#[cfg(not(feature = "filter"))]
+#[cfg(any(not(feature = "filter"), feature = "ffmpeg_5_0"))]
 fn init_filter() {}
 
 #[cfg_attr(
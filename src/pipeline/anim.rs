@@ -0,0 +1,614 @@
+//! Animated images (GIF, APNG and animated WebP) decoded into RGBA frames.
+//!
+//! The GIF and APNG decoders of FFmpeg already apply the disposal and blend
+//! operations, so their frames come out composited. The WebP decoder does
+//! not, and its frames are returned as decoded, to be composited by the
+//! caller with the operations of `Frame::control`. The operations and the
+//! loop count are dropped by the decoders, so they are read from the
+//! container instead and reported next to the frames.
+//!
+//! `encode` writes such frames back as animated WebP, APNG or AVIF, e.g. for
+//! stickers and emotes.
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use codec::{self, Context};
+use software::scaling::{self, Flags};
+use util::error::EAGAIN;
+use util::format::Pixel;
+use {decoder, encoder, format, frame, media, Codec, Dictionary, Error, Packet, Rational, Rescale};
+
+/// What happens to the area of a frame before rendering the next one.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Disposal {
+    /// The frame is left in place.
+    None,
+    /// The area is cleared to the background.
+    Background,
+    /// The area is restored to what it was before the frame.
+    Previous,
+}
+
+/// How a frame is drawn over the previous ones.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Blend {
+    /// The pixels of the frame replace the previous ones, transparent or not.
+    Source,
+    /// The frame is alpha blended over the previous ones.
+    Over,
+}
+
+/// The per-frame parameters read from the container.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct Control {
+    pub delay: Duration,
+    pub disposal: Disposal,
+    pub blend: Blend,
+}
+
+/// The animation parameters read from the container.
+#[derive(Eq, PartialEq, Clone, Debug, Default)]
+pub struct Info {
+    /// The number of times the animation is played, 0 for forever.
+    pub loops: u32,
+    pub frames: Vec<Control>,
+}
+
+pub struct Frame {
+    /// The frame as decoded, in `Pixel::RGBA`, composited for GIF and APNG.
+    pub image: frame::Video,
+    /// How long the frame is displayed, from the timestamps set by the
+    /// demuxer, which already replaces too short delays as browsers do.
+    pub delay: Duration,
+    /// The operations of the frame, if the container was parsed.
+    pub control: Option<Control>,
+}
+
+pub struct AnimatedImage {
+    pub width: u32,
+    pub height: u32,
+    /// The number of times the animation is played, 0 for forever.
+    pub loops: u32,
+    pub frames: Vec<Frame>,
+}
+
+/// Decode all the frames of the animated image at `path`.
+pub fn decode<P: AsRef<Path>>(path: P) -> Result<AnimatedImage, Error> {
+    let path = path.as_ref();
+    let info = fs::read(path).ok().and_then(|data| parse(&data));
+
+    let mut input = format::input(&path)?;
+    let (index, time_base, end) = {
+        let stream = input
+            .streams()
+            .best(media::Type::Video)
+            .ok_or(Error::StreamNotFound)?;
+
+        let end = match (stream.start_time(), stream.duration()) {
+            (start, duration) if duration > 0 => Some(start.max(0) + duration),
+            _ => None,
+        };
+
+        (stream.index(), stream.time_base(), end)
+    };
+
+    let mut context = Context::new();
+    context.set_parameters(input.stream(index).unwrap().parameters())?;
+    let mut decoder = context.decoder().video()?;
+
+    let mut decoded = Vec::new();
+    let mut frame = frame::Video::empty();
+
+    for (stream, packet) in input.packets() {
+        if stream.index() == index {
+            decoder.send_packet(&packet)?;
+            receive(&mut decoder, &mut frame, &mut decoded)?;
+        }
+    }
+
+    decoder.send_eof()?;
+    receive(&mut decoder, &mut frame, &mut decoded)?;
+
+    let controls = info
+        .as_ref()
+        .map(|info| &info.frames[..])
+        .filter(|frames| frames.len() == decoded.len());
+
+    let mut frames: Vec<Frame> = Vec::with_capacity(decoded.len());
+    let mut scaler: Option<scaling::Context> = None;
+
+    for (i, source) in decoded.iter().enumerate() {
+        let reusable = scaler.as_ref().map_or(false, |s| {
+            s.input().format == source.format()
+                && s.input().width == source.width()
+                && s.input().height == source.height()
+        });
+
+        if !reusable {
+            scaler = Some(scaling::Context::get(
+                source.format(),
+                source.width(),
+                source.height(),
+                Pixel::RGBA,
+                source.width(),
+                source.height(),
+                Flags::BILINEAR,
+            )?);
+        }
+
+        let mut image = frame::Video::empty();
+        scaler.as_mut().unwrap().run(source, &mut image)?;
+
+        let next = decoded.get(i + 1).and_then(|f| f.timestamp()).or(end);
+        let delay = match (source.timestamp(), next) {
+            (Some(current), Some(next)) if next > current => {
+                micros((next - current).rescale(time_base, Rational(1, 1_000_000)))
+            }
+            _ => frames
+                .last()
+                .map_or(Duration::from_millis(100), |f| f.delay),
+        };
+
+        frames.push(Frame {
+            image,
+            delay,
+            control: controls.map(|c| c[i]),
+        });
+    }
+
+    Ok(AnimatedImage {
+        width: decoder.width(),
+        height: decoder.height(),
+        loops: info.map_or(0, |info| info.loops),
+        frames,
+    })
+}
+
+/// The containers `encode` writes.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Container {
+    /// Animated WebP with alpha, requires libwebp.
+    WebP,
+    /// Animated PNG with alpha.
+    Apng,
+    /// AVIF image sequence, without alpha, requires libaom, SVT-AV1 or
+    /// rav1e.
+    Avif,
+}
+
+impl Container {
+    pub fn muxer(&self) -> &'static str {
+        match *self {
+            Container::WebP => "webp",
+            Container::Apng => "apng",
+            Container::Avif => "avif",
+        }
+    }
+
+    /// Get the encoder used, the first one available of the candidates.
+    pub fn encoder(&self) -> Option<Codec> {
+        let candidates: &[&str] = match *self {
+            Container::WebP => &["libwebp_anim", "libwebp"],
+            Container::Apng => &["apng"],
+            Container::Avif => &["libaom-av1", "libsvtav1", "librav1e"],
+        };
+
+        candidates
+            .iter()
+            .filter_map(|name| encoder::find_by_name(name))
+            .next()
+    }
+
+    fn format(&self) -> Pixel {
+        match *self {
+            Container::WebP => Pixel::YUVA420P,
+            Container::Apng => Pixel::RGBA,
+            Container::Avif => Pixel::YUV420P,
+        }
+    }
+
+    // the muxer option with the number of times the animation is played
+    fn loop_option(&self) -> &'static str {
+        match *self {
+            Container::Apng => "plays",
+            Container::WebP | Container::Avif => "loop",
+        }
+    }
+}
+
+/// Encode `frames`, each with how long it is displayed, to the file at
+/// `path`, played `loops` times (0 for forever).
+///
+/// The frames are scaled to the size of the first one if needed.
+pub fn encode<'a, I, P>(frames: I, container: Container, loops: u32, path: &P) -> Result<(), Error>
+where
+    I: IntoIterator<Item = (&'a frame::Video, Duration)>,
+    P: AsRef<Path>,
+{
+    let frames = frames
+        .into_iter()
+        .map(|(frame, delay)| (frame, millis(delay)))
+        .collect::<Vec<_>>();
+
+    let (width, height, final_delay) = match (frames.first(), frames.last()) {
+        (Some(&(first, _)), Some(&(_, delay))) => (first.width(), first.height(), delay),
+        _ => return Err(Error::InvalidData),
+    };
+
+    let codec = container.encoder().ok_or(Error::EncoderNotFound)?;
+    let time_base = Rational(1, 1000);
+
+    let mut output = format::output_as(path, container.muxer())?;
+    let global_header = output
+        .format()
+        .flags()
+        .contains(format::Flags::GLOBAL_HEADER);
+
+    let mut encoder = Context::new().encoder().video()?;
+    encoder.set_width(width);
+    encoder.set_height(height);
+    encoder.set_format(container.format());
+    encoder.set_time_base(time_base);
+
+    if global_header {
+        encoder.set_flags(codec::Flags::GLOBAL_HEADER);
+    }
+
+    let mut encoder = encoder.open_as(codec)?;
+
+    {
+        let mut stream = output.add_stream(codec)?;
+        stream.set_parameters(&encoder);
+        stream.set_time_base(time_base);
+    }
+
+    let mut options = Dictionary::new();
+    options.set(container.loop_option(), &loops.to_string());
+
+    // the muxer repeats the previous delay for the last frame otherwise
+    if container == Container::Apng {
+        options.set("final_delay", &format!("{}/1000", final_delay));
+    }
+
+    let mut last = None;
+    let mut timing = Vec::new();
+    let mut pts = 0;
+    let mut scaler: Option<scaling::Context> = None;
+
+    output.write_header_with(options)?;
+
+    for (source, delay) in frames {
+        let reusable = scaler.as_ref().map_or(false, |s| {
+            s.input().format == source.format()
+                && s.input().width == source.width()
+                && s.input().height == source.height()
+        });
+
+        if !reusable {
+            scaler = Some(scaling::Context::get(
+                source.format(),
+                source.width(),
+                source.height(),
+                container.format(),
+                width,
+                height,
+                Flags::BILINEAR,
+            )?);
+        }
+
+        let mut image = frame::Video::empty();
+        scaler.as_mut().unwrap().run(source, &mut image)?;
+        image.set_pts(Some(pts));
+
+        timing.push((pts, delay));
+        pts += delay;
+
+        encoder.send_frame(&image)?;
+        write(&mut encoder, &mut output, &timing)?;
+
+        last = Some(image);
+    }
+
+    // the animation encoder of libwebp only knows the end of the last frame
+    // from a following one, identical frames being merged into the previous
+    if let (Some(mut image), "libwebp_anim") = (last, codec.name()) {
+        image.set_pts(Some(pts));
+        encoder.send_frame(&image)?;
+        write(&mut encoder, &mut output, &timing)?;
+    }
+
+    encoder.send_eof()?;
+    write(&mut encoder, &mut output, &timing)?;
+
+    output.write_trailer()
+}
+
+fn write(
+    encoder: &mut encoder::video::Encoder,
+    output: &mut format::context::Output,
+    timing: &[(i64, i64)],
+) -> Result<(), Error> {
+    let time_base = output.stream(0).ok_or(Error::Bug)?.time_base();
+    let mut packet = Packet::empty();
+
+    loop {
+        match encoder.receive_packet(&mut packet) {
+            Ok(()) => (),
+            Err(Error::Eof) => return Ok(()),
+            Err(Error::Other { errno }) if errno == EAGAIN => return Ok(()),
+            Err(e) => return Err(e),
+        }
+
+        if let Some(&(_, delay)) = timing.iter().find(|&&(pts, _)| Some(pts) == packet.pts()) {
+            packet.set_duration(delay);
+        }
+
+        packet.set_stream(0);
+        packet.rescale_ts(Rational(1, 1000), time_base);
+        packet.write_interleaved(output)?;
+    }
+}
+
+fn receive(
+    decoder: &mut decoder::Video,
+    frame: &mut frame::Video,
+    decoded: &mut Vec<frame::Video>,
+) -> Result<(), Error> {
+    loop {
+        match decoder.receive_frame(frame) {
+            Ok(()) => decoded.push(frame.clone()),
+            Err(Error::Eof) => return Ok(()),
+            Err(Error::Other { errno }) if errno == EAGAIN => return Ok(()),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn micros(value: i64) -> Duration {
+    Duration::from_micros(value.max(0) as u64)
+}
+
+fn millis(value: Duration) -> i64 {
+    (value.as_secs() * 1000 + u64::from(value.subsec_millis())).max(1) as i64
+}
+
+/// Read the loop count and the per-frame parameters of a GIF, APNG or
+/// animated WebP file.
+pub fn parse(data: &[u8]) -> Option<Info> {
+    if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        parse_gif(data)
+    } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        parse_apng(data)
+    } else if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        parse_webp(data)
+    } else {
+        None
+    }
+}
+
+fn le16(data: &[u8]) -> u32 {
+    u32::from(data[0]) | u32::from(data[1]) << 8
+}
+
+fn le24(data: &[u8]) -> u32 {
+    le16(data) | u32::from(data[2]) << 16
+}
+
+fn be16(data: &[u8]) -> u32 {
+    u32::from(data[0]) << 8 | u32::from(data[1])
+}
+
+fn be32(data: &[u8]) -> u32 {
+    be16(data) << 16 | be16(&data[2..])
+}
+
+fn parse_gif(data: &[u8]) -> Option<Info> {
+    // skip the data sub-blocks at `pos`, returning the position after them
+    fn skip(data: &[u8], mut pos: usize) -> Option<usize> {
+        loop {
+            let size = *data.get(pos)? as usize;
+            pos += 1 + size;
+
+            if size == 0 {
+                return Some(pos);
+            }
+        }
+    }
+
+    let packed = *data.get(10)?;
+    let mut pos = 13;
+
+    if packed & 0x80 != 0 {
+        pos += 3 << ((packed & 7) + 1);
+    }
+
+    let mut info = Info {
+        loops: 1,
+        frames: Vec::new(),
+    };
+    let mut control = None;
+
+    loop {
+        match *data.get(pos)? {
+            0x21 => {
+                let label = *data.get(pos + 1)?;
+                let block = data.get(pos + 2..pos + 3 + *data.get(pos + 2)? as usize)?;
+
+                if label == 0xf9 && block.len() >= 5 {
+                    control = Some(Control {
+                        delay: Duration::from_millis(u64::from(le16(&block[2..])) * 10),
+                        disposal: match (block[1] >> 2) & 7 {
+                            2 => Disposal::Background,
+                            3 => Disposal::Previous,
+                            _ => Disposal::None,
+                        },
+                        blend: Blend::Over,
+                    });
+                } else if label == 0xff && &block[1..] == b"NETSCAPE2.0" {
+                    let sub = data.get(pos + 2 + block.len()..pos + 6 + block.len())?;
+
+                    if sub[0] >= 3 && sub[1] == 1 {
+                        let count = le16(&sub[2..]);
+                        info.loops = if count == 0 { 0 } else { count + 1 };
+                    }
+                }
+
+                pos = skip(data, pos + 2)?;
+            }
+
+            0x2c => {
+                let packed = *data.get(pos + 9)?;
+                pos += 10;
+
+                if packed & 0x80 != 0 {
+                    pos += 3 << ((packed & 7) + 1);
+                }
+
+                pos = skip(data, pos + 1)?;
+
+                info.frames.push(control.take().unwrap_or(Control {
+                    delay: Duration::from_millis(0),
+                    disposal: Disposal::None,
+                    blend: Blend::Over,
+                }));
+            }
+
+            0x3b => return Some(info),
+            _ => return None,
+        }
+    }
+}
+
+fn parse_apng(data: &[u8]) -> Option<Info> {
+    let mut info = None;
+    let mut pos = 8;
+
+    while let Some(header) = data.get(pos..pos + 8) {
+        let size = be32(header) as usize;
+        let chunk = data.get(pos + 8..pos + 8 + size)?;
+
+        match &header[4..] {
+            b"acTL" if size >= 8 => {
+                info = Some(Info {
+                    loops: be32(&chunk[4..]),
+                    frames: Vec::new(),
+                });
+            }
+
+            b"fcTL" if size >= 26 => {
+                let info = info.as_mut()?;
+                let (num, den) = (be16(&chunk[20..]), be16(&chunk[22..]));
+                let den = if den == 0 { 100 } else { den };
+
+                info.frames.push(Control {
+                    delay: Duration::from_millis(u64::from(num) * 1000 / u64::from(den)),
+                    disposal: match chunk[24] {
+                        1 => Disposal::Background,
+                        2 => Disposal::Previous,
+                        _ => Disposal::None,
+                    },
+                    blend: match chunk[25] {
+                        1 => Blend::Over,
+                        _ => Blend::Source,
+                    },
+                });
+            }
+
+            b"IEND" => break,
+            _ => (),
+        }
+
+        pos += 12 + size;
+    }
+
+    info
+}
+
+fn parse_webp(data: &[u8]) -> Option<Info> {
+    let mut info = None;
+    let mut pos = 12;
+
+    while let Some(header) = data.get(pos..pos + 8) {
+        let size = (le16(&header[4..]) | le16(&header[6..]) << 16) as usize;
+        let chunk = data.get(pos + 8..pos + 8 + size)?;
+
+        match &header[0..4] {
+            b"ANIM" if size >= 6 => {
+                info = Some(Info {
+                    loops: le16(&chunk[4..]),
+                    frames: Vec::new(),
+                });
+            }
+
+            b"ANMF" if size >= 16 => {
+                info.as_mut()?.frames.push(Control {
+                    delay: Duration::from_millis(u64::from(le24(&chunk[12..]))),
+                    disposal: if chunk[15] & 1 != 0 {
+                        Disposal::Background
+                    } else {
+                        Disposal::None
+                    },
+                    blend: if chunk[15] & 2 != 0 {
+                        Blend::Source
+                    } else {
+                        Blend::Over
+                    },
+                });
+            }
+
+            _ => (),
+        }
+
+        pos += 8 + size + (size & 1);
+    }
+
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gif() {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"GIF89a\x01\x00\x01\x00\x00\x00\x00");
+        data.extend_from_slice(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x02\x00\x00");
+
+        for &disposal in &[0x08u8, 0x0c] {
+            data.extend_from_slice(&[0x21, 0xf9, 0x04, disposal, 0x0a, 0x00, 0x00, 0x00]);
+            data.extend_from_slice(b"\x2c\x00\x00\x00\x00\x01\x00\x01\x00\x00\x02\x02\x44\x01\x00");
+        }
+
+        data.push(0x3b);
+
+        let info = parse(&data).unwrap();
+        assert_eq!(info.loops, 3);
+        assert_eq!(info.frames.len(), 2);
+        assert_eq!(info.frames[0].delay, Duration::from_millis(100));
+        assert_eq!(info.frames[0].disposal, Disposal::Background);
+        assert_eq!(info.frames[1].disposal, Disposal::Previous);
+    }
+
+    #[test]
+    fn test_parse_webp() {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"RIFF\x00\x00\x00\x00WEBP");
+        data.extend_from_slice(b"ANIM\x06\x00\x00\x00\x00\x00\x00\x00\x00\x00");
+        data.extend_from_slice(b"ANMF\x10\x00\x00\x00");
+        data.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x28, 0, 0, 0x03]);
+
+        let info = parse(&data).unwrap();
+        assert_eq!(info.loops, 0);
+        assert_eq!(
+            info.frames,
+            vec![Control {
+                delay: Duration::from_millis(40),
+                disposal: Disposal::Background,
+                blend: Blend::Source,
+            }]
+        );
+    }
+}
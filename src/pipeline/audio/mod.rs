@@ -0,0 +1,13 @@
+//! Audio processing helpers.
+
+#[cfg(feature = "filter")]
+pub mod retime;
+#[cfg(feature = "filter")]
+pub use self::retime::{retime, Method, Pitch};
+
+#[cfg(feature = "software-resampling")]
+pub mod windows;
+#[cfg(all(feature = "software-resampling", feature = "format"))]
+pub use self::windows::pcm_windows;
+#[cfg(feature = "software-resampling")]
+pub use self::windows::Windows;
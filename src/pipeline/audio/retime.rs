@@ -0,0 +1,98 @@
+//! Audio speed changes, picking between the `rubberband`, `atempo` and
+//! `asetrate` filters depending on the pitch handling and on the filters
+//! available in the linked FFmpeg build.
+//!
+//! The returned descriptions are meant for `filter::Builder::filters`, possibly
+//! chained with other filters.
+
+use filter::find;
+use Error;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Pitch {
+    /// Keep the pitch, changing the tempo only.
+    Preserve,
+    /// Shift the pitch along with the speed, as playing a tape faster.
+    Shift,
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Method {
+    /// The `rubberband` filter, higher quality but only available in builds
+    /// linking librubberband.
+    Rubberband,
+    /// The `atempo` filter, chained when the factor is out of its range.
+    Atempo,
+    /// The `asetrate` filter followed by `aresample`.
+    Resample,
+}
+
+impl Method {
+    /// Check whether the filters needed by the method are available.
+    pub fn is_available(self) -> bool {
+        match self {
+            Method::Rubberband => find("rubberband").is_some(),
+            Method::Atempo => find("atempo").is_some(),
+            Method::Resample => find("asetrate").is_some() && find("aresample").is_some(),
+        }
+    }
+}
+
+/// Get the method used for `pitch`, if any is available.
+pub fn method(pitch: Pitch) -> Option<Method> {
+    let candidates: &[Method] = match pitch {
+        Pitch::Preserve => &[Method::Rubberband, Method::Atempo],
+        Pitch::Shift => &[Method::Resample],
+    };
+
+    candidates.iter().cloned().find(|m| m.is_available())
+}
+
+/// Get the filter description changing the speed by `factor` (e.g. 2.0 to
+/// play twice as fast) of audio at `rate` Hz.
+pub fn retime(factor: f64, pitch: Pitch, rate: u32) -> Result<String, Error> {
+    if !factor.is_finite() || factor <= 0.0 || rate == 0 {
+        return Err(Error::InvalidData);
+    }
+
+    match method(pitch).ok_or(Error::FilterNotFound)? {
+        Method::Rubberband => Ok(format!("rubberband=tempo={}", factor)),
+        Method::Atempo => Ok(atempo(factor)),
+        Method::Resample => Ok(format!(
+            "asetrate={},aresample={}",
+            (f64::from(rate) * factor).round() as u64,
+            rate
+        )),
+    }
+}
+
+/// Get a chain of `atempo` filters changing the tempo by `factor`, each
+/// within the [0.5, 2.0] range supported by all versions.
+pub fn atempo(mut factor: f64) -> String {
+    let mut filters = Vec::new();
+
+    while factor > 2.0 {
+        filters.push(String::from("atempo=2"));
+        factor /= 2.0;
+    }
+
+    while factor < 0.5 {
+        filters.push(String::from("atempo=0.5"));
+        factor /= 0.5;
+    }
+
+    filters.push(format!("atempo={}", factor));
+    filters.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_atempo_chain() {
+        assert_eq!(atempo(1.5), "atempo=1.5");
+        assert_eq!(atempo(6.0), "atempo=2,atempo=2,atempo=1.5");
+        assert_eq!(atempo(0.125), "atempo=0.5,atempo=0.5,atempo=0.5");
+    }
+}
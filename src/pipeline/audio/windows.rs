@@ -0,0 +1,287 @@
+//! Fixed-size windows of resampled `f32` PCM, as expected by analysis and
+//! inference code (speech recognition, source separation, ...).
+//!
+//! Timestamps are in samples at the output rate, i.e. in a `1/rate` time
+//! base, counted from the timestamp of the first frame and advancing by
+//! exactly `hop` samples per window.
+
+use std::slice;
+
+#[cfg(feature = "format")]
+use codec::{self, decoder};
+#[cfg(feature = "format")]
+use format::context::Input;
+use software::resampling::Context;
+#[cfg(feature = "format")]
+use util::error::EAGAIN;
+use util::format::sample::{Sample, Type};
+use {frame, ChannelLayout, Error, Rational, Rescale};
+#[cfg(feature = "format")]
+use {media, Packet};
+
+/// Split resampled audio into windows of `window` samples every `hop`
+/// samples, channels being interleaved.
+pub struct Windows {
+    resampler: Option<Context>,
+
+    rate: u32,
+    channel_layout: ChannelLayout,
+    window: usize,
+    hop: usize,
+
+    buffer: Vec<f32>,
+    skip: usize,
+    timestamp: Option<i64>,
+    finished: bool,
+}
+
+impl Windows {
+    pub fn new(
+        rate: u32,
+        channel_layout: ChannelLayout,
+        window: usize,
+        hop: usize,
+    ) -> Result<Self, Error> {
+        if rate == 0 || channel_layout.channels() <= 0 || window == 0 || hop == 0 {
+            return Err(Error::InvalidData);
+        }
+
+        Ok(Windows {
+            resampler: None,
+
+            rate,
+            channel_layout,
+            window,
+            hop,
+
+            buffer: Vec::new(),
+            skip: 0,
+            timestamp: None,
+            finished: false,
+        })
+    }
+
+    /// Add a decoded frame, whose timestamps are in `time_base`.
+    pub fn push(&mut self, frame: &frame::Audio, time_base: Rational) -> Result<(), Error> {
+        let channel_layout = if frame.channel_layout().is_empty() {
+            ChannelLayout::default(i32::from(frame.channels()))
+        } else {
+            frame.channel_layout()
+        };
+
+        let stale = match self.resampler {
+            Some(ref resampler) => {
+                let input = resampler.input();
+
+                input.format != frame.format()
+                    || input.channel_layout != channel_layout
+                    || input.rate != frame.rate()
+            }
+
+            None => true,
+        };
+
+        if stale {
+            self.drain()?;
+            self.resampler = Some(Context::get(
+                frame.format(),
+                channel_layout,
+                frame.rate(),
+                Sample::F32(Type::Packed),
+                self.channel_layout,
+                self.rate,
+            )?);
+        }
+
+        if self.timestamp.is_none() {
+            self.timestamp = Some(
+                frame
+                    .pts()
+                    .map_or(0, |pts| pts.rescale(time_base, (1, self.rate as i32))),
+            );
+        }
+
+        let samples = self
+            .resampler
+            .as_ref()
+            .unwrap()
+            .out_samples(frame.samples())?;
+        let mut output = frame::Audio::new(Sample::F32(Type::Packed), samples, self.channel_layout);
+
+        self.resampler.as_mut().unwrap().run(frame, &mut output)?;
+        self.append(&output);
+
+        Ok(())
+    }
+
+    /// Flush the resampler, the last window is padded with silence.
+    pub fn finish(&mut self) -> Result<(), Error> {
+        self.drain()?;
+        self.finished = true;
+
+        Ok(())
+    }
+
+    /// Take the next complete window, with its timestamp.
+    pub fn pop(&mut self) -> Option<(i64, Vec<f32>)> {
+        let channels = self.channel_layout.channels() as usize;
+        let length = self.window * channels;
+
+        if self.buffer.is_empty() || (self.buffer.len() < length && !self.finished) {
+            return None;
+        }
+
+        let mut window = self.buffer[..length.min(self.buffer.len())].to_vec();
+        window.resize(length, 0.0);
+
+        let hop = self.hop * channels;
+        let drained = hop.min(self.buffer.len());
+
+        self.buffer.drain(..drained);
+        self.skip = hop - drained;
+
+        let timestamp = self.timestamp.unwrap_or(0);
+        self.timestamp = Some(timestamp + self.hop as i64);
+
+        Some((timestamp, window))
+    }
+
+    fn drain(&mut self) -> Result<(), Error> {
+        if let Some(mut resampler) = self.resampler.take() {
+            let mut output = frame::Audio::empty();
+
+            resampler.flush(&mut output)?;
+            self.append(&output);
+        }
+
+        Ok(())
+    }
+
+    fn append(&mut self, frame: &frame::Audio) {
+        let length = frame.samples() * self.channel_layout.channels() as usize;
+
+        if length == 0 {
+            return;
+        }
+
+        let data =
+            unsafe { slice::from_raw_parts(frame.plane_bytes(0).as_ptr() as *const f32, length) };
+        let skipped = self.skip.min(data.len());
+
+        self.skip -= skipped;
+        self.buffer.extend_from_slice(&data[skipped..]);
+    }
+}
+
+/// Iterator over the PCM windows of the best audio stream of an input.
+#[cfg(feature = "format")]
+pub struct PcmWindows<'a> {
+    input: &'a mut Input,
+    stream: usize,
+    time_base: Rational,
+    decoder: decoder::Audio,
+    windows: Windows,
+
+    flushing: bool,
+    done: bool,
+}
+
+#[cfg(feature = "format")]
+impl<'a> PcmWindows<'a> {
+    fn fill(&mut self) -> Result<(), Error> {
+        let mut frame = frame::Audio::empty();
+
+        match self.decoder.receive_frame(&mut frame) {
+            Ok(()) => return self.windows.push(&frame, self.time_base),
+
+            Err(Error::Eof) => {
+                self.done = true;
+                return self.windows.finish();
+            }
+
+            Err(Error::Other { errno }) if errno == EAGAIN => (),
+            Err(e) => return Err(e),
+        }
+
+        if self.flushing {
+            return self.decoder.send_eof();
+        }
+
+        let mut packet = Packet::empty();
+
+        loop {
+            match packet.read(self.input) {
+                Ok(()) if packet.stream() == self.stream => {
+                    return self.decoder.send_packet(&packet)
+                }
+                Ok(()) => (),
+
+                Err(Error::Eof) => {
+                    self.flushing = true;
+                    return self.decoder.send_eof();
+                }
+
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "format")]
+impl<'a> Iterator for PcmWindows<'a> {
+    type Item = Result<(i64, Vec<f32>), Error>;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        loop {
+            if let Some(window) = self.windows.pop() {
+                return Some(Ok(window));
+            }
+
+            if self.done {
+                return None;
+            }
+
+            if let Err(e) = self.fill() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+/// Decode the best audio stream of `input` into windows of `window` samples
+/// every `hop` samples, resampled to `rate` and `channel_layout` (e.g. mono
+/// 16kHz for speech recognition).
+#[cfg(feature = "format")]
+pub fn pcm_windows(
+    input: &mut Input,
+    rate: u32,
+    channel_layout: ChannelLayout,
+    window: usize,
+    hop: usize,
+) -> Result<PcmWindows, Error> {
+    let windows = Windows::new(rate, channel_layout, window, hop)?;
+
+    let (stream, time_base, parameters) = {
+        let stream = input
+            .streams()
+            .best(media::Type::Audio)
+            .ok_or(Error::StreamNotFound)?;
+
+        (stream.index(), stream.time_base(), stream.parameters())
+    };
+
+    let mut context = codec::Context::new();
+    context.set_parameters(parameters)?;
+
+    Ok(PcmWindows {
+        input,
+        stream,
+        time_base,
+        decoder: context.decoder().audio()?,
+        windows,
+
+        flushing: false,
+        done: false,
+    })
+}
@@ -0,0 +1,246 @@
+//! Removal of consecutive duplicate frames, as the `mpdecimate` filter does,
+//! e.g. to clean up screen recordings.
+//!
+//! Kept frames are returned once the next distinct frame is known, along
+//! with their duration up to it, so the output is a correct variable frame
+//! rate stream.
+
+use std::mem;
+
+#[cfg(feature = "format")]
+use codec::{self, decoder};
+#[cfg(feature = "format")]
+use format::context::Input;
+use frame::{diff, Video};
+#[cfg(feature = "format")]
+use util::error::EAGAIN;
+use Error;
+#[cfg(feature = "format")]
+use {media, Packet, Rational};
+
+pub struct Dedup {
+    threshold: u8,
+    fraction: f64,
+    max_drops: Option<usize>,
+
+    last: Option<Video>,
+    drops: usize,
+    dropped: usize,
+}
+
+impl Dedup {
+    /// Frames are duplicates when at most `fraction` (0.0 to 1.0) of their
+    /// pixels differ by more than `threshold` on an 8-bit scale.
+    pub fn new(threshold: u8, fraction: f64) -> Self {
+        Dedup {
+            threshold,
+            fraction,
+            max_drops: None,
+
+            last: None,
+            drops: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Keep at least one frame out of `value + 1` consecutive duplicates.
+    pub fn set_max_drops(&mut self, value: Option<usize>) {
+        self.max_drops = value;
+    }
+
+    /// Get the number of frames dropped so far.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Add a frame, returning the previous kept frame and its duration in the
+    /// time base of the timestamps when `frame` is distinct.
+    ///
+    /// The duration is `None` when the timestamps are missing.
+    pub fn push(&mut self, frame: &Video) -> Result<Option<(Video, Option<i64>)>, Error> {
+        let duplicate = match self.last {
+            Some(ref last) => {
+                self.max_drops.map_or(true, |max| self.drops < max)
+                    && self.is_duplicate(last, frame)?
+            }
+
+            None => {
+                self.last = Some(frame.clone());
+                return Ok(None);
+            }
+        };
+
+        if duplicate {
+            self.drops += 1;
+            self.dropped += 1;
+
+            return Ok(None);
+        }
+
+        self.drops = 0;
+
+        let last = mem::replace(&mut self.last, Some(frame.clone())).unwrap();
+        let duration = match (last.pts(), frame.pts()) {
+            (Some(start), Some(end)) => Some(end - start),
+            _ => None,
+        };
+
+        Ok(Some((last, duration)))
+    }
+
+    /// Take the last kept frame, `end` being the timestamp of the end of the
+    /// stream if known.
+    pub fn flush(&mut self, end: Option<i64>) -> Option<(Video, Option<i64>)> {
+        self.drops = 0;
+        self.last.take().map(|last| {
+            let duration = match (last.pts(), end) {
+                (Some(start), Some(end)) => Some(end - start),
+                _ => None,
+            };
+
+            (last, duration)
+        })
+    }
+
+    fn is_duplicate(&self, a: &Video, b: &Video) -> Result<bool, Error> {
+        if a.format() != b.format() || a.width() != b.width() || a.height() != b.height() {
+            return Ok(false);
+        }
+
+        let stats = diff::stats(a, b, self.threshold)?;
+        let pixels = a.width() as f64 * a.height() as f64;
+
+        Ok(stats.changed as f64 <= self.fraction * pixels)
+    }
+}
+
+/// Iterator over the distinct frames of the best video stream of an input,
+/// with their durations.
+#[cfg(feature = "format")]
+pub struct Run<'a> {
+    input: &'a mut Input,
+    stream: usize,
+    time_base: Rational,
+    decoder: decoder::Video,
+    dedup: Dedup,
+
+    end: Option<i64>,
+    done: bool,
+}
+
+#[cfg(feature = "format")]
+impl<'a> Run<'a> {
+    /// Get the time base of the timestamps and durations, the one of the
+    /// stream.
+    pub fn time_base(&self) -> Rational {
+        self.time_base
+    }
+
+    /// Get the number of frames dropped so far.
+    pub fn dropped(&self) -> usize {
+        self.dedup.dropped()
+    }
+
+    fn decode(&mut self) -> Result<Option<Video>, Error> {
+        let mut frame = Video::empty();
+        let mut packet = Packet::empty();
+
+        loop {
+            match self.decoder.receive_frame(&mut frame) {
+                Ok(()) => {
+                    let timestamp = frame.timestamp();
+                    frame.set_pts(timestamp);
+
+                    self.end = timestamp.map(|pts| pts + frame.packet().duration);
+                    return Ok(Some(frame));
+                }
+
+                Err(Error::Eof) => return Ok(None),
+                Err(Error::Other { errno }) if errno == EAGAIN => (),
+                Err(e) => return Err(e),
+            }
+
+            loop {
+                match packet.read(self.input) {
+                    Ok(()) if packet.stream() == self.stream => {
+                        self.decoder.send_packet(&packet)?;
+                        break;
+                    }
+                    Ok(()) => (),
+
+                    Err(Error::Eof) => {
+                        self.decoder.send_eof()?;
+                        break;
+                    }
+
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "format")]
+impl<'a> Iterator for Run<'a> {
+    type Item = Result<(Video, Option<i64>), Error>;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        while !self.done {
+            let pushed = match self.decode() {
+                Ok(Some(frame)) => self.dedup.push(&frame),
+
+                Ok(None) => {
+                    self.done = true;
+                    return self.dedup.flush(self.end).map(Ok);
+                }
+
+                Err(e) => Err(e),
+            };
+
+            match pushed {
+                Ok(Some(kept)) => return Some(Ok(kept)),
+                Ok(None) => (),
+
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Decode the best video stream of `input`, dropping the frames whose
+/// pixels all differ by at most `threshold` from the previous kept frame.
+#[cfg(feature = "format")]
+pub fn run(input: &mut Input, threshold: u8) -> Result<Run, Error> {
+    run_with(input, Dedup::new(threshold, 0.0))
+}
+
+/// Like `run`, with the settings of `dedup`.
+#[cfg(feature = "format")]
+pub fn run_with(input: &mut Input, dedup: Dedup) -> Result<Run, Error> {
+    let (stream, time_base, parameters) = {
+        let stream = input
+            .streams()
+            .best(media::Type::Video)
+            .ok_or(Error::StreamNotFound)?;
+
+        (stream.index(), stream.time_base(), stream.parameters())
+    };
+
+    Ok(Run {
+        input,
+        stream,
+        time_base,
+        decoder: codec::Context::from_parameters(parameters)?
+            .decoder()
+            .video()?,
+        dedup,
+
+        end: None,
+        done: false,
+    })
+}
@@ -0,0 +1,857 @@
+//! Cut lists: concatenation of segments of an input into one output.
+//!
+//! `copy` remuxes the segments without decoding, starting each one at the
+//! keyframe preceding its start, which is exact when cuts are on keyframes.
+//! Otherwise, or to change the speed, `video_filters` and `audio_filters`
+//! give the filter descriptions to re-encode the segments through a graph
+//! built with `filter::Builder`, and `video_transitions` and
+//! `audio_transitions` the ones joining them with crossfades instead of hard
+//! cuts. `render` picks between remuxing and re-encoding.
+
+#[cfg(feature = "filter")]
+use super::audio::retime;
+use codec;
+#[cfg(feature = "filter")]
+use codec::capabilities::Capabilities;
+#[cfg(feature = "filter")]
+use codec::decoder;
+use ffi::AV_TIME_BASE;
+#[cfg(feature = "filter")]
+use filter::{AudioInput, Graph, VideoInput};
+use format::context::{Input, Output};
+#[cfg(feature = "filter")]
+use format::Flags;
+#[cfg(feature = "filter")]
+use util::error::EAGAIN;
+#[cfg(feature = "filter")]
+use Frame;
+use {encoder, media, Error, Packet, Rational, Rescale};
+
+/// A segment of the input, with times in seconds.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct Segment {
+    pub start: f64,
+    pub end: f64,
+    /// The playback speed, e.g. 2.0 to play twice as fast.
+    pub speed: f64,
+}
+
+impl Segment {
+    pub fn new(start: f64, end: f64) -> Self {
+        Segment {
+            start,
+            end,
+            speed: 1.0,
+        }
+    }
+
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Get the duration in the output, in seconds.
+    pub fn duration(&self) -> f64 {
+        (self.end - self.start) / self.speed
+    }
+
+    fn is_valid(&self) -> bool {
+        self.start >= 0.0 && self.end > self.start && self.speed.is_finite() && self.speed > 0.0
+    }
+}
+
+fn check(segments: &[Segment]) -> Result<(), Error> {
+    if segments.is_empty() || !segments.iter().all(Segment::is_valid) {
+        Err(Error::InvalidData)
+    } else {
+        Ok(())
+    }
+}
+
+/// A transition at the joins between segments, overlapping the end of each
+/// segment with the start of the next one.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct Transition {
+    pub kind: Kind,
+    /// The curve of the audio crossfade.
+    pub curve: Curve,
+    /// The duration in seconds, shortening the output by as much per join.
+    pub duration: f64,
+}
+
+impl Transition {
+    pub fn new(kind: Kind, duration: f64) -> Self {
+        Transition {
+            kind,
+            curve: Curve::Triangular,
+            duration,
+        }
+    }
+
+    pub fn with_curve(mut self, curve: Curve) -> Self {
+        self.curve = curve;
+        self
+    }
+}
+
+/// The video transitions of the `xfade` filter, available from FFmpeg 4.3.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Kind {
+    Fade,
+    FadeBlack,
+    FadeWhite,
+    Dissolve,
+    WipeLeft,
+    WipeRight,
+    WipeUp,
+    WipeDown,
+    SlideLeft,
+    SlideRight,
+    SlideUp,
+    SlideDown,
+    CircleCrop,
+    RectCrop,
+    Radial,
+    Pixelize,
+}
+
+impl Kind {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Kind::Fade => "fade",
+            Kind::FadeBlack => "fadeblack",
+            Kind::FadeWhite => "fadewhite",
+            Kind::Dissolve => "dissolve",
+            Kind::WipeLeft => "wipeleft",
+            Kind::WipeRight => "wiperight",
+            Kind::WipeUp => "wipeup",
+            Kind::WipeDown => "wipedown",
+            Kind::SlideLeft => "slideleft",
+            Kind::SlideRight => "slideright",
+            Kind::SlideUp => "slideup",
+            Kind::SlideDown => "slidedown",
+            Kind::CircleCrop => "circlecrop",
+            Kind::RectCrop => "rectcrop",
+            Kind::Radial => "radial",
+            Kind::Pixelize => "pixelize",
+        }
+    }
+}
+
+/// The fade curves of the `acrossfade` filter.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Curve {
+    Triangular,
+    QuarterSine,
+    HalfSine,
+    ExponentialSine,
+    Logarithmic,
+    Parabola,
+    Cubic,
+    SquareRoot,
+    Exponential,
+}
+
+impl Curve {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Curve::Triangular => "tri",
+            Curve::QuarterSine => "qsin",
+            Curve::HalfSine => "hsin",
+            Curve::ExponentialSine => "esin",
+            Curve::Logarithmic => "log",
+            Curve::Parabola => "par",
+            Curve::Cubic => "cub",
+            Curve::SquareRoot => "squ",
+            Curve::Exponential => "exp",
+        }
+    }
+}
+
+/// Get the filter description cutting and concatenating the segments of a
+/// single video input.
+#[cfg(feature = "filter")]
+pub fn video_filters(segments: &[Segment]) -> Result<String, Error> {
+    check(segments)?;
+
+    let mut spec = video_segments(segments);
+    spec.push_str(&concat(segments.len(), "v=1:a=0"));
+
+    Ok(spec)
+}
+
+/// Get the filter description cutting the segments of a single video input
+/// and joining them with `transition`.
+#[cfg(feature = "filter")]
+pub fn video_transitions(segments: &[Segment], transition: &Transition) -> Result<String, Error> {
+    check_transition(segments, transition)?;
+
+    let mut spec = video_segments(segments);
+    let mut offset = 0.0;
+
+    for index in 1..segments.len() {
+        offset += segments[index - 1].duration() - transition.duration;
+
+        spec.push_str(&format!(
+            ";{}[s{}]xfade=transition={}:duration={}:offset={}{}",
+            input_label(index),
+            index,
+            transition.kind.name(),
+            transition.duration,
+            offset,
+            output_label(index, segments.len()),
+        ));
+    }
+
+    Ok(spec)
+}
+
+/// Get the filter description cutting and concatenating the segments of a
+/// single audio input, keeping the pitch when the speed changes.
+#[cfg(feature = "filter")]
+pub fn audio_filters(segments: &[Segment]) -> Result<String, Error> {
+    check(segments)?;
+
+    let mut spec = audio_segments(segments);
+    spec.push_str(&concat(segments.len(), "v=0:a=1"));
+
+    Ok(spec)
+}
+
+/// Get the filter description cutting the segments of a single audio input
+/// and crossfading them over the duration of `transition`.
+#[cfg(feature = "filter")]
+pub fn audio_transitions(segments: &[Segment], transition: &Transition) -> Result<String, Error> {
+    check_transition(segments, transition)?;
+
+    let mut spec = audio_segments(segments);
+
+    for index in 1..segments.len() {
+        spec.push_str(&format!(
+            ";{0}[s{1}]acrossfade=d={2}:c1={3}:c2={3}{4}",
+            input_label(index),
+            index,
+            transition.duration,
+            transition.curve.name(),
+            output_label(index, segments.len()),
+        ));
+    }
+
+    Ok(spec)
+}
+
+#[cfg(feature = "filter")]
+fn video_segments(segments: &[Segment]) -> String {
+    let mut spec = split("split", segments.len());
+
+    for (index, segment) in segments.iter().enumerate() {
+        spec.push_str(&format!(
+            ";[p{0}]trim=start={1}:end={2},setpts=(PTS-STARTPTS)/{3}[s{0}]",
+            index, segment.start, segment.end, segment.speed
+        ));
+    }
+
+    spec
+}
+
+#[cfg(feature = "filter")]
+fn audio_segments(segments: &[Segment]) -> String {
+    let mut spec = split("asplit", segments.len());
+
+    for (index, segment) in segments.iter().enumerate() {
+        spec.push_str(&format!(
+            ";[p{0}]atrim=start={1}:end={2},asetpts=PTS-STARTPTS",
+            index, segment.start, segment.end
+        ));
+
+        if segment.speed != 1.0 {
+            spec.push(',');
+            spec.push_str(&retime::atempo(segment.speed));
+        }
+
+        spec.push_str(&format!("[s{}]", index));
+    }
+
+    spec
+}
+
+#[cfg(feature = "filter")]
+fn split(filter: &str, count: usize) -> String {
+    let mut spec = format!("{}={}", filter, count);
+
+    for index in 0..count {
+        spec.push_str(&format!("[p{}]", index));
+    }
+
+    spec
+}
+
+#[cfg(feature = "filter")]
+fn concat(count: usize, kinds: &str) -> String {
+    let mut spec = String::from(";");
+
+    for index in 0..count {
+        spec.push_str(&format!("[s{}]", index));
+    }
+
+    spec.push_str(&format!("concat=n={}:{}", count, kinds));
+    spec
+}
+
+#[cfg(feature = "filter")]
+fn check_transition(segments: &[Segment], transition: &Transition) -> Result<(), Error> {
+    check(segments)?;
+
+    // Each segment must outlast the transitions at both of its ends.
+    let count = |index: usize| (index > 0) as u8 + (index + 1 < segments.len()) as u8;
+
+    if segments.len() < 2
+        || !transition.duration.is_finite()
+        || transition.duration <= 0.0
+        || segments
+            .iter()
+            .enumerate()
+            .any(|(i, s)| s.duration() < f64::from(count(i)) * transition.duration)
+    {
+        Err(Error::InvalidData)
+    } else {
+        Ok(())
+    }
+}
+
+/// Get the label of the result of the joins before the segment `index`.
+#[cfg(feature = "filter")]
+fn input_label(index: usize) -> String {
+    if index == 1 {
+        String::from("[s0]")
+    } else {
+        format!("[j{}]", index - 1)
+    }
+}
+
+/// Get the label of the join before the segment `index`, the last one being
+/// left unlabeled for the output of the graph.
+#[cfg(feature = "filter")]
+fn output_label(index: usize, count: usize) -> String {
+    if index + 1 == count {
+        String::new()
+    } else {
+        format!("[j{}]", index)
+    }
+}
+
+/// Remux the segments of the audio, video and subtitle streams of `input`
+/// into `output`, writing its header and trailer.
+///
+/// Segments must keep their speed, and streams with reordered frames may
+/// produce non-monotonic timestamps at the joins.
+pub fn copy(input: &mut Input, segments: &[Segment], output: &mut Output) -> Result<(), Error> {
+    check(segments)?;
+
+    if segments.iter().any(|s| s.speed != 1.0) {
+        return Err(Error::InvalidData);
+    }
+
+    let mut mapping = Vec::new();
+    let mut reference = None;
+
+    for stream in input.streams() {
+        let medium = stream.parameters().medium();
+
+        if medium != media::Type::Audio
+            && medium != media::Type::Video
+            && medium != media::Type::Subtitle
+        {
+            mapping.push(None);
+            continue;
+        }
+
+        if medium == media::Type::Video && reference.is_none() {
+            reference = Some(stream.index());
+        }
+
+        let mut ost = output.add_stream(encoder::find(codec::Id::None))?;
+        ost.set_parameters(stream.parameters());
+
+        unsafe {
+            (*ost.parameters().as_mut_ptr()).codec_tag = 0;
+        }
+
+        mapping.push(Some((ost.index(), stream.time_base())));
+    }
+
+    output.write_header()?;
+
+    let time_base = Rational(1, AV_TIME_BASE as i32);
+    let mut offset = 0i64;
+
+    for segment in segments {
+        let start = (segment.start * f64::from(AV_TIME_BASE)) as i64;
+        let end = (segment.end * f64::from(AV_TIME_BASE)) as i64;
+
+        input.seek(start, ..start)?;
+
+        let mut origin = None;
+        let mut pending = Vec::new();
+        let mut last = offset;
+        let mut packet = Packet::empty();
+
+        loop {
+            match packet.read(input) {
+                Ok(()) => (),
+                Err(Error::Eof) => break,
+                Err(e) => return Err(e),
+            }
+
+            // streams may be added after the header, e.g. in MPEG-TS
+            let (index, source) = match mapping.get(packet.stream()).cloned() {
+                Some(Some(stream)) => stream,
+                _ => continue,
+            };
+
+            let time = match packet.pts().or_else(|| packet.dts()) {
+                Some(time) => time.rescale(source, time_base),
+                None => continue,
+            };
+
+            if time >= end {
+                if reference.map_or(true, |r| r == packet.stream()) {
+                    break;
+                }
+
+                continue;
+            }
+
+            if origin.is_none() {
+                if reference.map_or(true, |r| r == packet.stream() && packet.is_key()) {
+                    origin = Some(time);
+                } else {
+                    pending.push((packet.clone(), index, source, time));
+                    continue;
+                }
+            }
+
+            let origin = origin.unwrap();
+
+            for (packet, index, source, time) in pending.drain(..) {
+                if time >= origin {
+                    last = last.max(write(output, packet, index, source, offset - origin)?);
+                }
+            }
+
+            if time >= origin {
+                let packet = packet.clone();
+                last = last.max(write(output, packet, index, source, offset - origin)?);
+            }
+        }
+
+        offset = last;
+    }
+
+    output.write_trailer()
+}
+
+/// Write a packet shifted by `shift` microseconds, returning its end time in
+/// the output.
+fn write(
+    output: &mut Output,
+    mut packet: Packet,
+    index: usize,
+    source: Rational,
+    shift: i64,
+) -> Result<i64, Error> {
+    let time_base = Rational(1, AV_TIME_BASE as i32);
+    let shift = shift.rescale(time_base, source);
+    let end = (packet.pts().or_else(|| packet.dts()).unwrap_or(0) + shift + packet.duration())
+        .rescale(source, time_base);
+
+    packet.set_pts(packet.pts().map(|pts| pts + shift));
+    packet.set_dts(packet.dts().map(|dts| dts + shift));
+    packet.set_position(-1);
+    packet.set_stream(index);
+    packet.rescale_ts(source, output.stream(index).ok_or(Error::Bug)?.time_base());
+    packet.write_interleaved(output)?;
+
+    Ok(end)
+}
+
+/// Concatenate the segments of `input` into `output`, writing its header
+/// and trailer.
+///
+/// The streams are remuxed with `copy` when the segments keep their speed
+/// and start on keyframes of the video. Otherwise the best video and audio
+/// streams are re-encoded with the encoders of their codecs, through the
+/// filters of `video_filters` and `audio_filters`, and the other streams
+/// are left out.
+#[cfg(feature = "filter")]
+pub fn render(input: &mut Input, segments: &[Segment], output: &mut Output) -> Result<(), Error> {
+    check(segments)?;
+
+    if segments.iter().all(|s| s.speed == 1.0) && on_keyframes(input, segments)? {
+        return copy(input, segments, output);
+    }
+
+    let mut tracks = Vec::new();
+
+    for &medium in &[media::Type::Video, media::Type::Audio] {
+        let (stream, time_base, parameters) = match input.streams().best(medium) {
+            Some(stream) => (stream.index(), stream.time_base(), stream.parameters()),
+            None => continue,
+        };
+
+        let decoder = codec::Context::from_parameters(parameters)?.decoder();
+
+        tracks.push(match medium {
+            media::Type::Video => {
+                Track::video(stream, time_base, decoder.video()?, segments, output)?
+            }
+            _ => Track::audio(stream, time_base, decoder.audio()?, segments, output)?,
+        });
+    }
+
+    if tracks.is_empty() {
+        return Err(Error::StreamNotFound);
+    }
+
+    output.write_header()?;
+    input.seek(0, ..)?;
+
+    let mut packet = Packet::empty();
+
+    loop {
+        match packet.read(input) {
+            Ok(()) => (),
+            Err(Error::Eof) => break,
+            Err(e) => return Err(e),
+        }
+
+        if let Some(track) = tracks.iter_mut().find(|t| t.stream == packet.stream()) {
+            track.decoder.send_packet(&packet)?;
+            track.decode(output)?;
+        }
+    }
+
+    for track in &mut tracks {
+        track.decoder.send_eof()?;
+        track.decode(output)?;
+
+        track.graph.get("in").ok_or(Error::Bug)?.source().flush()?;
+        track.filter(output)?;
+
+        track.encoder.send_eof()?;
+        track.write(output)?;
+    }
+
+    output.write_trailer()
+}
+
+/// Check whether the segments start on keyframes of the best video stream.
+#[cfg(feature = "filter")]
+fn on_keyframes(input: &mut Input, segments: &[Segment]) -> Result<bool, Error> {
+    let (reference, source) = match input.streams().best(media::Type::Video) {
+        Some(stream) => (stream.index(), stream.time_base()),
+        None => return Ok(true),
+    };
+
+    let time_base = Rational(1, AV_TIME_BASE as i32);
+    let mut packet = Packet::empty();
+
+    for segment in segments {
+        let start = (segment.start * f64::from(AV_TIME_BASE)) as i64;
+
+        input.seek(start, ..start)?;
+
+        loop {
+            match packet.read(input) {
+                Ok(()) if packet.stream() == reference => break,
+                Ok(()) => (),
+                Err(Error::Eof) => return Ok(false),
+                Err(e) => return Err(e),
+            }
+        }
+
+        if !packet.is_key() || packet.pts() != Some(start.rescale(time_base, source)) {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// A stream re-encoded through the filters cutting its segments.
+#[cfg(feature = "filter")]
+struct Track {
+    stream: usize,
+    decoder: decoder::Opened,
+    graph: Graph,
+
+    index: usize,
+    encoder: encoder::Encoder,
+    time_base: Rational,
+
+    decoded: Frame,
+    filtered: Frame,
+}
+
+#[cfg(feature = "filter")]
+impl Track {
+    fn video(
+        stream: usize,
+        source: Rational,
+        decoder: decoder::Video,
+        segments: &[Segment],
+        output: &mut Output,
+    ) -> Result<Self, Error> {
+        let codec = encoder::find(decoder.id()).ok_or(Error::EncoderNotFound)?;
+        let format = codec
+            .video()?
+            .supported_formats()
+            .best_match(decoder.format());
+
+        let mut graph = Graph::builder()
+            .video_input(VideoInput::from_decoder(&decoder, source))
+            .filters(video_filters(segments)?)
+            .video_output_as(format)
+            .build()?;
+
+        let (width, height, aspect_ratio, time_base) = {
+            let sink = graph.get("out").ok_or(Error::Bug)?;
+            let link = sink.inputs().next().ok_or(Error::Bug)?;
+
+            (
+                link.width(),
+                link.height(),
+                link.aspect_ratio(),
+                link.time_base(),
+            )
+        };
+
+        let mut encoder = codec::Context::new().encoder().video()?;
+        encoder.set_width(width);
+        encoder.set_height(height);
+        encoder.set_format(format);
+        encoder.set_aspect_ratio(aspect_ratio);
+        encoder.set_frame_rate(decoder.frame_rate());
+        encoder.set_time_base(time_base);
+
+        if output.format().flags().contains(Flags::GLOBAL_HEADER) {
+            encoder.set_flags(codec::Flags::GLOBAL_HEADER);
+        }
+
+        let encoder = encoder.open_as(codec)?;
+        let index = {
+            let mut stream = output.add_stream(codec)?;
+            stream.set_parameters(&encoder);
+            stream.set_time_base(time_base);
+            stream.index()
+        };
+
+        Ok(Track {
+            stream,
+            decoder: decoder.0,
+            graph,
+
+            index,
+            encoder: (encoder.0).0,
+            time_base,
+
+            decoded: unsafe { Frame::empty() },
+            filtered: unsafe { Frame::empty() },
+        })
+    }
+
+    fn audio(
+        stream: usize,
+        source: Rational,
+        decoder: decoder::Audio,
+        segments: &[Segment],
+        output: &mut Output,
+    ) -> Result<Self, Error> {
+        let source = AudioInput::from_decoder(&decoder, source);
+
+        let codec = encoder::find(decoder.id()).ok_or(Error::EncoderNotFound)?;
+        let audio = codec.audio()?;
+        let format = audio.supported_formats().best_match(source.format);
+        let rate = audio.supported_rates().best_match(source.rate as i32);
+        let channel_layout = audio
+            .supported_channel_layouts()
+            .best_match(source.channel_layout)
+            .or_else(|| audio.supported_channel_layouts().next())
+            .ok_or(Error::InvalidData)?;
+
+        let mut graph = Graph::builder()
+            .audio_input(source)
+            .filters(audio_filters(segments)?)
+            .audio_output_as(format, channel_layout, rate as u32)
+            .build()?;
+
+        let time_base = {
+            let sink = graph.get("out").ok_or(Error::Bug)?;
+            let link = sink.inputs().next().ok_or(Error::Bug)?;
+
+            link.time_base()
+        };
+
+        let mut encoder = codec::Context::new().encoder().audio()?;
+        encoder.set_rate(rate);
+        encoder.set_format(format);
+        encoder.set_channel_layout(channel_layout);
+        encoder.set_channels(channel_layout.channels());
+        encoder.set_time_base(time_base);
+
+        if output.format().flags().contains(Flags::GLOBAL_HEADER) {
+            encoder.set_flags(codec::Flags::GLOBAL_HEADER);
+        }
+
+        let encoder = encoder.open_as(codec)?;
+        let index = {
+            let mut stream = output.add_stream(codec)?;
+            stream.set_parameters(&encoder);
+            stream.set_time_base(time_base);
+            stream.index()
+        };
+
+        if !codec
+            .capabilities()
+            .contains(Capabilities::VARIABLE_FRAME_SIZE)
+        {
+            graph
+                .get("out")
+                .ok_or(Error::Bug)?
+                .sink()
+                .set_frame_size(encoder.frame_size());
+        }
+
+        Ok(Track {
+            stream,
+            decoder: decoder.0,
+            graph,
+
+            index,
+            encoder: (encoder.0).0,
+            time_base,
+
+            decoded: unsafe { Frame::empty() },
+            filtered: unsafe { Frame::empty() },
+        })
+    }
+
+    fn decode(&mut self, output: &mut Output) -> Result<(), Error> {
+        loop {
+            match self.decoder.receive_frame(&mut self.decoded) {
+                Ok(()) => (),
+                Err(Error::Eof) => return Ok(()),
+                Err(Error::Other { errno }) if errno == EAGAIN => return Ok(()),
+                Err(e) => return Err(e),
+            }
+
+            let timestamp = self.decoded.timestamp();
+            self.decoded.set_pts(timestamp);
+
+            self.graph
+                .get("in")
+                .ok_or(Error::Bug)?
+                .source()
+                .add(&self.decoded)?;
+            self.filter(output)?;
+        }
+    }
+
+    fn filter(&mut self, output: &mut Output) -> Result<(), Error> {
+        loop {
+            match self
+                .graph
+                .get("out")
+                .ok_or(Error::Bug)?
+                .sink()
+                .frame(&mut self.filtered)
+            {
+                Ok(()) => (),
+                Err(Error::Eof) => return Ok(()),
+                Err(Error::Other { errno }) if errno == EAGAIN => return Ok(()),
+                Err(e) => return Err(e),
+            }
+
+            self.encoder.send_frame(&self.filtered)?;
+            self.write(output)?;
+        }
+    }
+
+    fn write(&mut self, output: &mut Output) -> Result<(), Error> {
+        let time_base = output.stream(self.index).ok_or(Error::Bug)?.time_base();
+        let mut packet = Packet::empty();
+
+        loop {
+            match self.encoder.receive_packet(&mut packet) {
+                Ok(()) => (),
+                Err(Error::Eof) => return Ok(()),
+                Err(Error::Other { errno }) if errno == EAGAIN => return Ok(()),
+                Err(e) => return Err(e),
+            }
+
+            packet.set_stream(self.index);
+            packet.rescale_ts(self.time_base, time_base);
+            packet.write_interleaved(output)?;
+        }
+    }
+}
+
+#[cfg(all(test, feature = "filter"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filters() {
+        let segments = [
+            Segment::new(1.0, 2.5),
+            Segment::new(4.0, 5.0).with_speed(4.0),
+        ];
+
+        assert_eq!(
+            video_filters(&segments).unwrap(),
+            "split=2[p0][p1];\
+             [p0]trim=start=1:end=2.5,setpts=(PTS-STARTPTS)/1[s0];\
+             [p1]trim=start=4:end=5,setpts=(PTS-STARTPTS)/4[s1];\
+             [s0][s1]concat=n=2:v=1:a=0"
+        );
+        assert_eq!(
+            audio_filters(&segments).unwrap(),
+            "asplit=2[p0][p1];\
+             [p0]atrim=start=1:end=2.5,asetpts=PTS-STARTPTS[s0];\
+             [p1]atrim=start=4:end=5,asetpts=PTS-STARTPTS,atempo=2,atempo=2[s1];\
+             [s0][s1]concat=n=2:v=0:a=1"
+        );
+        assert!(video_filters(&[Segment::new(2.0, 1.0)]).is_err());
+    }
+
+    #[test]
+    fn test_transitions() {
+        let segments = [
+            Segment::new(0.0, 2.0),
+            Segment::new(5.0, 7.0),
+            Segment::new(10.0, 12.0).with_speed(2.0),
+        ];
+        let transition = Transition::new(Kind::WipeLeft, 0.5).with_curve(Curve::QuarterSine);
+
+        assert_eq!(
+            video_transitions(&segments, &transition).unwrap(),
+            "split=3[p0][p1][p2];\
+             [p0]trim=start=0:end=2,setpts=(PTS-STARTPTS)/1[s0];\
+             [p1]trim=start=5:end=7,setpts=(PTS-STARTPTS)/1[s1];\
+             [p2]trim=start=10:end=12,setpts=(PTS-STARTPTS)/2[s2];\
+             [s0][s1]xfade=transition=wipeleft:duration=0.5:offset=1.5[j1];\
+             [j1][s2]xfade=transition=wipeleft:duration=0.5:offset=3"
+        );
+        assert_eq!(
+            audio_transitions(&segments[..2], &transition).unwrap(),
+            "asplit=2[p0][p1];\
+             [p0]atrim=start=0:end=2,asetpts=PTS-STARTPTS[s0];\
+             [p1]atrim=start=5:end=7,asetpts=PTS-STARTPTS[s1];\
+             [s0][s1]acrossfade=d=0.5:c1=qsin:c2=qsin"
+        );
+
+        let transition = Transition::new(Kind::Fade, 1.5);
+        assert!(video_transitions(&segments, &transition).is_err());
+        assert!(video_transitions(&segments[..1], &transition).is_err());
+    }
+}
@@ -0,0 +1,290 @@
+//! GIF encoding, quantizing the frames with the `palettegen` and
+//! `paletteuse` filters.
+//!
+//! The GIF encoder only takes `Pixel::PAL8` frames, and converting them
+//! with the scaler uses a fixed palette, banding gradients and skin tones.
+//! Computing the palette from the frames themselves and dithering them with
+//! it gives much better looking results.
+
+use std::path::Path;
+use std::time::Duration;
+
+use codec::{self, Context};
+use filter::{Graph, VideoInput};
+use util::error::EAGAIN;
+use util::format::Pixel;
+use {encoder, format, frame, Dictionary, Error, Packet, Rational};
+
+/// How the palettes are computed.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Palette {
+    /// A single palette for all the frames, which are buffered until the
+    /// last one is given.
+    Global,
+    /// A palette for each frame, better when the colors change between
+    /// scenes, at the cost of larger files.
+    PerFrame,
+}
+
+/// How the colors missing from the palette are approximated.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Dither {
+    None,
+    /// An ordered dither, whose pattern is crosshatched at scale 0 up to
+    /// barely visible at scale 5.
+    Bayer(u8),
+    Heckbert,
+    FloydSteinberg,
+    Sierra2,
+    /// The error diffusion dither used by default.
+    Sierra2_4A,
+}
+
+impl Dither {
+    fn to_option(&self) -> String {
+        match *self {
+            Dither::None => "none".into(),
+            Dither::Bayer(scale) => format!("bayer:bayer_scale={}", scale.min(5)),
+            Dither::Heckbert => "heckbert".into(),
+            Dither::FloydSteinberg => "floyd_steinberg".into(),
+            Dither::Sierra2 => "sierra2".into(),
+            Dither::Sierra2_4A => "sierra2_4a".into(),
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct Options {
+    palette: Palette,
+    dither: Dither,
+    max_colors: u32,
+    loops: u32,
+}
+
+impl Options {
+    pub fn new() -> Self {
+        Options::default()
+    }
+
+    pub fn palette(mut self, value: Palette) -> Self {
+        self.palette = value;
+        self
+    }
+
+    pub fn dither(mut self, value: Dither) -> Self {
+        self.dither = value;
+        self
+    }
+
+    /// Set the maximum number of colors of the palettes, from 2 to 256.
+    pub fn max_colors(mut self, value: u32) -> Self {
+        self.max_colors = value.max(2).min(256);
+        self
+    }
+
+    /// Set the number of times the animation is played, 0 for forever.
+    pub fn loops(mut self, value: u32) -> Self {
+        self.loops = value;
+        self
+    }
+
+    fn filters(&self) -> String {
+        let (stats_mode, new) = match self.palette {
+            Palette::Global => ("full", 0),
+            Palette::PerFrame => ("single", 1),
+        };
+
+        format!(
+            "split[a][b];[a]palettegen=max_colors={}:stats_mode={}[p];[b][p]paletteuse=dither={}:new={}",
+            self.max_colors,
+            stats_mode,
+            self.dither.to_option(),
+            new
+        )
+    }
+
+    // the muxer option counts the repetitions, -1 playing the animation once
+    fn loop_option(&self) -> String {
+        match self.loops {
+            0 => "0".into(),
+            1 => "-1".into(),
+            n => (n - 1).to_string(),
+        }
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            palette: Palette::Global,
+            dither: Dither::Sierra2_4A,
+            max_colors: 256,
+            loops: 0,
+        }
+    }
+}
+
+/// Encode `frames`, each with how long it is displayed, to the GIF file at
+/// `path`.
+///
+/// The frames must have the size and format of the first one. GIF delays
+/// are in hundredths of a second, the delays are rounded down to them, and
+/// raised to one hundredth if shorter.
+pub fn encode<'a, I, P>(frames: I, options: &Options, path: &P) -> Result<(), Error>
+where
+    I: IntoIterator<Item = (&'a frame::Video, Duration)>,
+    P: AsRef<Path>,
+{
+    let frames = frames
+        .into_iter()
+        .map(|(frame, delay)| (frame, centis(delay)))
+        .collect::<Vec<_>>();
+
+    let (first, final_delay) = match (frames.first(), frames.last()) {
+        (Some(&(first, _)), Some(&(_, delay))) => (first, delay),
+        _ => return Err(Error::InvalidData),
+    };
+
+    let (width, height, source_format) = (first.width(), first.height(), first.format());
+    let time_base = Rational(1, 100);
+
+    let mut graph = Graph::builder()
+        .video_input(VideoInput::new(width, height, source_format, time_base))
+        .filters(options.filters())
+        .video_output_as(Pixel::PAL8)
+        .build()?;
+
+    let codec = encoder::find(codec::Id::GIF).ok_or(Error::EncoderNotFound)?;
+    let mut output = format::output_as(path, "gif")?;
+
+    let mut encoder = Context::new().encoder().video()?;
+    encoder.set_width(width);
+    encoder.set_height(height);
+    encoder.set_format(Pixel::PAL8);
+    encoder.set_time_base(time_base);
+
+    let mut encoder = encoder.open_as(codec)?;
+
+    {
+        let mut stream = output.add_stream(codec)?;
+        stream.set_parameters(&encoder);
+        stream.set_time_base(time_base);
+    }
+
+    let mut muxer_options = Dictionary::new();
+    muxer_options.set("loop", &options.loop_option());
+    muxer_options.set("final_delay", &final_delay.to_string());
+
+    output.write_header_with(muxer_options)?;
+
+    let mut timing = Vec::new();
+    let mut pts = 0;
+    let mut filtered = frame::Video::empty();
+
+    for (source, delay) in frames {
+        if source.width() != width || source.height() != height || source.format() != source_format
+        {
+            return Err(Error::InvalidData);
+        }
+
+        let mut image = source.ref_clone()?;
+        image.set_pts(Some(pts));
+
+        timing.push((pts, delay));
+        pts += delay;
+
+        graph.get("in").ok_or(Error::Bug)?.source().add(&image)?;
+        drain(
+            &mut graph,
+            &mut filtered,
+            &mut encoder,
+            &mut output,
+            &timing,
+        )?;
+    }
+
+    graph.get("in").ok_or(Error::Bug)?.source().flush()?;
+    drain(
+        &mut graph,
+        &mut filtered,
+        &mut encoder,
+        &mut output,
+        &timing,
+    )?;
+
+    encoder.send_eof()?;
+    write(&mut encoder, &mut output, &timing)?;
+
+    output.write_trailer()
+}
+
+fn drain(
+    graph: &mut Graph,
+    filtered: &mut frame::Video,
+    encoder: &mut encoder::video::Encoder,
+    output: &mut format::context::Output,
+    timing: &[(i64, i64)],
+) -> Result<(), Error> {
+    loop {
+        match graph.get("out").ok_or(Error::Bug)?.sink().frame(filtered) {
+            Ok(()) => (),
+            Err(Error::Eof) => return Ok(()),
+            Err(Error::Other { errno }) if errno == EAGAIN => return Ok(()),
+            Err(e) => return Err(e),
+        }
+
+        encoder.send_frame(filtered)?;
+        write(encoder, output, timing)?;
+    }
+}
+
+fn write(
+    encoder: &mut encoder::video::Encoder,
+    output: &mut format::context::Output,
+    timing: &[(i64, i64)],
+) -> Result<(), Error> {
+    let time_base = output.stream(0).ok_or(Error::Bug)?.time_base();
+    let mut packet = Packet::empty();
+
+    loop {
+        match encoder.receive_packet(&mut packet) {
+            Ok(()) => (),
+            Err(Error::Eof) => return Ok(()),
+            Err(Error::Other { errno }) if errno == EAGAIN => return Ok(()),
+            Err(e) => return Err(e),
+        }
+
+        if let Some(&(_, delay)) = timing.iter().find(|&&(pts, _)| Some(pts) == packet.pts()) {
+            packet.set_duration(delay);
+        }
+
+        packet.set_stream(0);
+        packet.rescale_ts(Rational(1, 100), time_base);
+        packet.write_interleaved(output)?;
+    }
+}
+
+fn centis(value: Duration) -> i64 {
+    (value.as_secs() * 100 + u64::from(value.subsec_millis() / 10)).max(1) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filters() {
+        let options = Options::new()
+            .palette(Palette::PerFrame)
+            .dither(Dither::Bayer(3))
+            .max_colors(64);
+
+        assert_eq!(
+            options.filters(),
+            "split[a][b];[a]palettegen=max_colors=64:stats_mode=single[p];\
+             [b][p]paletteuse=dither=bayer:bayer_scale=3:new=1"
+        );
+        assert_eq!(Options::new().loops(1).loop_option(), "-1");
+        assert_eq!(Options::new().loops(3).loop_option(), "2");
+    }
+}
@@ -0,0 +1,24 @@
+//! Helpers built on top of the codec and format wrappers for common
+//! processing loops.
+
+#[cfg(all(feature = "format", feature = "software-scaling"))]
+pub mod anim;
+#[cfg(all(feature = "format", feature = "software-scaling"))]
+pub use self::anim::AnimatedImage;
+
+pub mod audio;
+
+pub mod dedup;
+pub use self::dedup::Dedup;
+
+#[cfg(feature = "format")]
+pub mod edl;
+
+#[cfg(all(feature = "format", feature = "filter"))]
+pub mod gif;
+
+pub mod pace;
+pub use self::pace::RealtimeLimiter;
+
+#[cfg(feature = "format")]
+pub mod seek;
@@ -0,0 +1,135 @@
+//! Pacing of packet writes to the wallclock, as the `-re` option of the
+//! `ffmpeg` tool does, e.g. to re-stream a file to a live RTMP or SRT
+//! endpoint.
+//!
+//! The limiter only ever waits: packets late compared to the wallclock are
+//! let through immediately, so the output catches up after a stall instead of
+//! drifting.
+
+use std::convert::TryFrom;
+use std::thread;
+use std::time::Duration;
+
+use util::time;
+use {rescale, Error, Packet, Rational, Rescale};
+
+#[derive(Copy, Clone, Debug)]
+pub struct RealtimeLimiter {
+    speed: f64,
+    burst: i64,
+    origin: Option<(i64, i64)>,
+}
+
+impl RealtimeLimiter {
+    /// Create a limiter writing at real time, without an initial burst.
+    pub fn new() -> Self {
+        RealtimeLimiter {
+            speed: 1.0,
+            burst: 0,
+            origin: None,
+        }
+    }
+
+    /// Set the rate of the output relative to real time, e.g. 1.05 to write
+    /// slightly faster to keep the buffers of the endpoint filled.
+    pub fn speed(mut self, value: f64) -> Self {
+        if value.is_finite() && value > 0.0 {
+            self.speed = value;
+        }
+
+        self
+    }
+
+    /// Set how far ahead of the wallclock the output may run, e.g. to fill
+    /// the buffer of the players quickly when starting.
+    pub fn burst(mut self, value: Duration) -> Self {
+        self.burst = i64::try_from(value.as_micros()).unwrap_or(i64::max_value());
+        self
+    }
+
+    /// Restart the pacing from the next packet, e.g. when looping the input
+    /// or after seeking.
+    pub fn reset(&mut self) {
+        self.origin = None;
+    }
+
+    /// Get how long to wait before writing `packet`, whose timestamps are in
+    /// `time_base`.
+    ///
+    /// The DTS is used, falling back to the PTS, packets without any are not
+    /// delayed.
+    pub fn delay(&mut self, packet: &Packet, time_base: Rational) -> Duration {
+        match packet.dts().or_else(|| packet.pts()) {
+            Some(ts) => Duration::from_micros(
+                self.delay_at(ts.rescale(time_base, rescale::TIME_BASE), time::relative()),
+            ),
+
+            None => Duration::from_micros(0),
+        }
+    }
+
+    /// Wait until `packet`, whose timestamps are in `time_base`, is due.
+    pub fn wait(&mut self, packet: &Packet, time_base: Rational) -> Result<(), Error> {
+        let delay = self.delay(packet, time_base);
+
+        if delay > Duration::from_micros(0) {
+            thread::sleep(delay);
+        }
+
+        Ok(())
+    }
+
+    // timestamps too far apart to be paced are let through
+    fn delay_at(&mut self, ts: i64, now: i64) -> u64 {
+        let (start, first) = *self.origin.get_or_insert((now, ts));
+
+        let delay = ts
+            .checked_sub(first)
+            .and_then(|elapsed| start.checked_add((elapsed as f64 / self.speed) as i64))
+            .and_then(|due| due.checked_sub(self.burst))
+            .and_then(|due| due.checked_sub(now));
+
+        match delay {
+            Some(delay) if delay > 0 => delay as u64,
+            _ => 0,
+        }
+    }
+}
+
+impl Default for RealtimeLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay() {
+        let mut limiter = RealtimeLimiter::new();
+
+        assert_eq!(limiter.delay_at(5_000_000, 100), 0);
+        assert_eq!(limiter.delay_at(5_040_000, 100), 40_000);
+        assert_eq!(limiter.delay_at(5_040_000, 30_100), 10_000);
+        assert_eq!(limiter.delay_at(5_080_000, 200_000), 0);
+
+        let mut limiter = RealtimeLimiter::new()
+            .speed(2.0)
+            .burst(Duration::from_millis(10));
+
+        assert_eq!(limiter.delay_at(0, 0), 0);
+        assert_eq!(limiter.delay_at(100_000, 0), 40_000);
+
+        let mut limiter = RealtimeLimiter::new();
+
+        assert_eq!(limiter.delay_at(0, 0), 0);
+        assert_eq!(limiter.delay_at(5_000_000_000, 0), 5_000_000_000);
+
+        let mut limiter = RealtimeLimiter::new();
+
+        assert_eq!(limiter.delay_at(i64::min_value(), 0), 0);
+        assert_eq!(limiter.delay_at(i64::max_value(), 0), 0);
+    }
+}
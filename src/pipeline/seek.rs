@@ -0,0 +1,108 @@
+//! Frame accurate seeking in video streams.
+//!
+//! Demuxers can only seek to keyframes, the frames between the keyframe and
+//! the requested position are decoded and dropped to get the frame displayed
+//! at that position, as video editors and scrubbers need.
+
+use std::mem;
+use std::time::Duration;
+
+use ffi::AV_NOPTS_VALUE;
+use format::context::{Input, SeekFlags};
+use util::error::EAGAIN;
+use {decoder, frame, Error, Packet, Rational, Rescale};
+
+/// Seek to the frame displayed at `ts`, in the time base of the video stream
+/// `stream`, which `decoder` decodes.
+///
+/// The decoder is flushed and the input left right after the packet of the
+/// returned frame, or of a later one for decoders with delay.
+pub fn seek_to_timestamp(
+    input: &mut Input,
+    decoder: &mut decoder::Video,
+    stream: usize,
+    ts: i64,
+) -> Result<frame::Video, Error> {
+    input.seek_stream(Some(stream), ts, SeekFlags::BACKWARD)?;
+    decoder.flush();
+
+    let mut packet = Packet::empty();
+    let mut frame = frame::Video::empty();
+    let mut previous: Option<frame::Video> = None;
+
+    loop {
+        match input.read_packet_into(&mut packet) {
+            Ok(()) if packet.stream() != stream => continue,
+            Ok(()) => decoder.send_packet(&packet)?,
+            Err(Error::Eof) => decoder.send_eof()?,
+            Err(e) => return Err(e),
+        }
+
+        loop {
+            match decoder.receive_frame(&mut frame) {
+                Ok(()) => match frame.timestamp() {
+                    Some(pts) if pts == ts => return Ok(frame),
+                    // the frame displayed at `ts` is the one before
+                    Some(pts) if pts > ts => return Ok(previous.unwrap_or(frame)),
+                    _ => previous = Some(mem::replace(&mut frame, frame::Video::empty())),
+                },
+
+                Err(Error::Other { errno }) if errno == EAGAIN => break,
+                Err(Error::Eof) => return previous.ok_or(Error::Eof),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Seek to the frame displayed at `time` from the start of the video stream
+/// `stream`.
+pub fn seek_to_time(
+    input: &mut Input,
+    decoder: &mut decoder::Video,
+    stream: usize,
+    time: Duration,
+) -> Result<frame::Video, Error> {
+    let (time_base, start) = timing(input, stream)?;
+    let micros = time.as_secs() as i64 * 1_000_000 + i64::from(time.subsec_micros());
+
+    seek_to_timestamp(
+        input,
+        decoder,
+        stream,
+        start + micros.rescale(Rational(1, 1_000_000), time_base),
+    )
+}
+
+/// Seek to the frame `number` of the video stream `stream`, counted at its
+/// average frame rate, which is only exact for constant frame rates.
+pub fn seek_to_frame(
+    input: &mut Input,
+    decoder: &mut decoder::Video,
+    stream: usize,
+    number: i64,
+) -> Result<frame::Video, Error> {
+    let (time_base, start) = timing(input, stream)?;
+    let rate = input.stream(stream).unwrap().avg_frame_rate();
+
+    if rate.numerator() <= 0 || rate.denominator() <= 0 {
+        return Err(Error::InvalidData);
+    }
+
+    seek_to_timestamp(
+        input,
+        decoder,
+        stream,
+        start + number.rescale(rate.invert(), time_base),
+    )
+}
+
+fn timing(input: &Input, stream: usize) -> Result<(Rational, i64), Error> {
+    let stream = input.stream(stream).ok_or(Error::StreamNotFound)?;
+    let start = match stream.start_time() {
+        AV_NOPTS_VALUE => 0,
+        ts => ts,
+    };
+
+    Ok((stream.time_base(), start))
+}
@@ -1,9 +1,11 @@
 use std::ptr;
 
-use super::Delay;
+use super::{Delay, Dither, Engine};
 use ffi::*;
 use libc::c_int;
 use std::ffi::c_void;
+#[cfg(feature = "ffmpeg_7_0")]
+use util::channel_layout::Layout;
 use util::format;
 use Dictionary;
 use {frame, ChannelLayout, Error};
@@ -67,18 +69,20 @@ impl Context {
         dst_rate: u32,
         options: Dictionary,
     ) -> Result<Self, Error> {
+        let input = Definition {
+            format: src_format,
+            channel_layout: src_channel_layout,
+            rate: src_rate,
+        };
+
+        let output = Definition {
+            format: dst_format,
+            channel_layout: dst_channel_layout,
+            rate: dst_rate,
+        };
+
         unsafe {
-            let ptr = swr_alloc_set_opts(
-                ptr::null_mut(),
-                dst_channel_layout.bits() as i64,
-                dst_format.into(),
-                dst_rate as c_int,
-                src_channel_layout.bits() as i64,
-                src_format.into(),
-                src_rate as c_int,
-                0,
-                ptr::null_mut(),
-            );
+            let ptr = alloc(&input, &output);
 
             let mut opts = options.disown();
             let res = av_opt_set_dict(ptr as *mut c_void, &mut opts);
@@ -92,21 +96,7 @@ impl Context {
                 match swr_init(ptr) {
                     e if e < 0 => Err(Error::from(e)),
 
-                    _ => Ok(Context {
-                        ptr,
-
-                        input: Definition {
-                            format: src_format,
-                            channel_layout: src_channel_layout,
-                            rate: src_rate,
-                        },
-
-                        output: Definition {
-                            format: dst_format,
-                            channel_layout: dst_channel_layout,
-                            rate: dst_rate,
-                        },
-                    }),
+                    _ => Ok(Context { ptr, input, output }),
                 }
             } else {
                 Err(Error::InvalidData)
@@ -134,6 +124,111 @@ impl Context {
         }
     }
 
+    /// Get an upper bound on the number of samples the next call to `run` with
+    /// `samples` input samples will output.
+    pub fn out_samples(&self, samples: usize) -> Result<usize, Error> {
+        unsafe {
+            match swr_get_out_samples(self.as_ptr() as *mut _, samples as c_int) {
+                e if e < 0 => Err(Error::from(e)),
+                n => Ok(n as usize),
+            }
+        }
+    }
+
+    /// Activate resampling compensation, stretching or squeezing the output by
+    /// `delta` samples over `distance` output samples.
+    pub fn set_compensation(&mut self, delta: i32, distance: i32) -> Result<(), Error> {
+        unsafe {
+            match swr_set_compensation(self.as_mut_ptr(), delta as c_int, distance as c_int) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Drop the given number of output samples.
+    pub fn drop_output(&mut self, samples: usize) -> Result<(), Error> {
+        unsafe {
+            match swr_drop_output(self.as_mut_ptr(), samples as c_int) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Inject the given number of silent samples.
+    pub fn inject_silence(&mut self, samples: usize) -> Result<(), Error> {
+        unsafe {
+            match swr_inject_silence(self.as_mut_ptr(), samples as c_int) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Set a custom rematrixing matrix, where `matrix[i + stride * o]` is the
+    /// weight of input channel `i` in output channel `o`.
+    ///
+    /// The resampler is reinitialized, discarding any buffered samples.
+    pub fn set_matrix(&mut self, matrix: &[f64], stride: usize) -> Result<(), Error> {
+        let inputs = self.input.channel_layout.channels() as usize;
+        let outputs = self.output.channel_layout.channels() as usize;
+
+        if stride < inputs || matrix.len() < outputs.saturating_sub(1) * stride + inputs {
+            return Err(Error::InvalidData);
+        }
+
+        unsafe { self.reinit(|ptr| swr_set_matrix(ptr, matrix.as_ptr(), stride as c_int)) }
+    }
+
+    /// Get the matrix FFmpeg builds by default for the input and output
+    /// channel layouts, with one row of input channel weights per output
+    /// channel.
+    pub fn default_matrix(&self) -> Result<Vec<f64>, Error> {
+        let stride = self.input.channel_layout.channels() as usize;
+        let mut matrix = vec![0.0; stride * self.output.channel_layout.channels() as usize];
+
+        unsafe {
+            match build_matrix(&self.input, &self.output, &mut matrix, stride) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(matrix),
+            }
+        }
+    }
+
+    /// Set the dithering method.
+    ///
+    /// The resampler is reinitialized, discarding any buffered samples.
+    pub fn set_dither(&mut self, dither: Dither) -> Result<(), Error> {
+        let value: SwrDitherType = dither.into();
+        unsafe { self.reinit(|ptr| set_int(ptr, b"dither_method\0", value as i64)) }
+    }
+
+    /// Set the resampling engine, `Engine::SoundExchange` requires FFmpeg
+    /// built with libsoxr.
+    ///
+    /// The resampler is reinitialized, discarding any buffered samples.
+    pub fn set_engine(&mut self, engine: Engine) -> Result<(), Error> {
+        let value: SwrEngine = engine.into();
+        unsafe { self.reinit(|ptr| set_int(ptr, b"resampler\0", value as i64)) }
+    }
+
+    unsafe fn reinit<F>(&mut self, f: F) -> Result<(), Error>
+    where
+        F: FnOnce(*mut SwrContext) -> c_int,
+    {
+        swr_close(self.as_mut_ptr());
+
+        let res = f(self.as_mut_ptr());
+        let init = swr_init(self.as_mut_ptr());
+
+        match (res, init) {
+            (e, _) if e < 0 => Err(Error::from(e)),
+            (_, e) if e < 0 => Err(Error::from(e)),
+            _ => Ok(()),
+        }
+    }
+
     /// Run the resampler from the given input to the given output.
     ///
     /// When there are internal frames to process it will return `Ok(Some(Delay { .. }))`.
@@ -164,10 +259,21 @@ impl Context {
     /// Convert one of the remaining internal frames.
     ///
     /// When there are no more internal frames `Ok(None)` will be returned.
+    ///
+    /// An empty `output` is allocated to hold all the buffered samples.
     pub fn flush(&mut self, output: &mut frame::Audio) -> Result<Option<Delay>, Error> {
         output.set_rate(self.output.rate);
 
         unsafe {
+            if output.is_empty() {
+                match self.out_samples(0)? {
+                    0 => return Ok(None),
+                    samples => {
+                        output.alloc(self.output.format, samples, self.output.channel_layout)
+                    }
+                }
+            }
+
             match swr_convert_frame(self.as_mut_ptr(), output.as_mut_ptr(), ptr::null()) {
                 0 => Ok(self.delay()),
 
@@ -177,6 +283,11 @@ impl Context {
     }
 }
 
+// `name` is nul terminated
+unsafe fn set_int(ptr: *mut SwrContext, name: &[u8], value: i64) -> c_int {
+    av_opt_set_int(ptr as *mut c_void, name.as_ptr() as *const _, value, 0)
+}
+
 impl Drop for Context {
     fn drop(&mut self) {
         unsafe {
@@ -184,3 +295,81 @@ impl Drop for Context {
         }
     }
 }
+
+#[cfg(not(feature = "ffmpeg_7_0"))]
+unsafe fn alloc(input: &Definition, output: &Definition) -> *mut SwrContext {
+    swr_alloc_set_opts(
+        ptr::null_mut(),
+        output.channel_layout.bits() as i64,
+        output.format.into(),
+        output.rate as c_int,
+        input.channel_layout.bits() as i64,
+        input.format.into(),
+        input.rate as c_int,
+        0,
+        ptr::null_mut(),
+    )
+}
+
+#[cfg(feature = "ffmpeg_7_0")]
+unsafe fn alloc(input: &Definition, output: &Definition) -> *mut SwrContext {
+    let mut ptr = ptr::null_mut();
+
+    swr_alloc_set_opts2(
+        &mut ptr,
+        Layout::native(output.channel_layout).as_ptr(),
+        output.format.into(),
+        output.rate as c_int,
+        Layout::native(input.channel_layout).as_ptr(),
+        input.format.into(),
+        input.rate as c_int,
+        0,
+        ptr::null_mut(),
+    );
+
+    ptr
+}
+
+#[cfg(not(feature = "ffmpeg_7_0"))]
+unsafe fn build_matrix(
+    input: &Definition,
+    output: &Definition,
+    matrix: &mut [f64],
+    stride: usize,
+) -> c_int {
+    swr_build_matrix(
+        input.channel_layout.bits(),
+        output.channel_layout.bits(),
+        ::std::f64::consts::FRAC_1_SQRT_2,
+        ::std::f64::consts::FRAC_1_SQRT_2,
+        0.0,
+        1.0,
+        1.0,
+        matrix.as_mut_ptr(),
+        stride as c_int,
+        AVMatrixEncoding::AV_MATRIX_ENCODING_NONE,
+        ptr::null_mut(),
+    )
+}
+
+#[cfg(feature = "ffmpeg_7_0")]
+unsafe fn build_matrix(
+    input: &Definition,
+    output: &Definition,
+    matrix: &mut [f64],
+    stride: usize,
+) -> c_int {
+    swr_build_matrix2(
+        Layout::native(input.channel_layout).as_ptr(),
+        Layout::native(output.channel_layout).as_ptr(),
+        ::std::f64::consts::FRAC_1_SQRT_2,
+        ::std::f64::consts::FRAC_1_SQRT_2,
+        0.0,
+        1.0,
+        1.0,
+        matrix.as_mut_ptr(),
+        stride as isize,
+        AVMatrixEncoding::AV_MATRIX_ENCODING_NONE,
+        ptr::null_mut(),
+    )
+}
@@ -0,0 +1,89 @@
+use super::{Context, Flags};
+use util::format;
+use {frame, Error};
+
+/// A scaler that transparently recreates its context when the format or the
+/// dimensions of the input frames change, as with adaptive streams.
+pub struct Cached {
+    context: Option<Context>,
+
+    format: format::Pixel,
+    width: u32,
+    height: u32,
+    flags: Flags,
+}
+
+impl Cached {
+    /// Create a scaler to the given output format and dimensions.
+    ///
+    /// A zero width or height keeps the one of the input.
+    pub fn new(format: format::Pixel, width: u32, height: u32, flags: Flags) -> Self {
+        Cached {
+            context: None,
+
+            format,
+            width,
+            height,
+            flags,
+        }
+    }
+
+    /// Get the current context, if any frame has been scaled.
+    pub fn context(&self) -> Option<&Context> {
+        self.context.as_ref()
+    }
+
+    /// Scale `input` into `output`, recreating the context if needed.
+    ///
+    /// An `output` frame that does not match the current output definition is
+    /// reallocated.
+    pub fn run(&mut self, input: &frame::Video, output: &mut frame::Video) -> Result<(), Error> {
+        let stale = match self.context {
+            Some(ref context) => {
+                let definition = context.input();
+
+                definition.format != input.format()
+                    || definition.width != input.width()
+                    || definition.height != input.height()
+            }
+
+            None => true,
+        };
+
+        if stale {
+            self.context = None;
+            self.context = Some(Context::get(
+                input.format(),
+                input.width(),
+                input.height(),
+                self.format,
+                if self.width == 0 {
+                    input.width()
+                } else {
+                    self.width
+                },
+                if self.height == 0 {
+                    input.height()
+                } else {
+                    self.height
+                },
+                self.flags,
+            )?);
+        }
+
+        let context = self.context.as_mut().unwrap();
+        let definition = *context.output();
+
+        unsafe {
+            if !output.is_empty()
+                && (output.format() != definition.format
+                    || output.width() != definition.width
+                    || output.height() != definition.height)
+            {
+                *output = frame::Video::empty();
+            }
+        }
+
+        context.run(input, output)
+    }
+}
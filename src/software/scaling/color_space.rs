@@ -1,5 +1,6 @@
 use ffi::*;
 use libc::c_int;
+use util::color;
 
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
 pub enum ColorSpace {
@@ -11,6 +12,31 @@ pub enum ColorSpace {
     ITU624,
     SMPTE170M,
     SMPTE240M,
+    BT2020,
+}
+
+impl ColorSpace {
+    /// Get the YUV to RGB coefficient table used by the scaler.
+    pub fn coefficients(self) -> *const c_int {
+        unsafe { sws_getCoefficients(self.into()) }
+    }
+
+    /// Get the colorspace whose coefficient table is `table`.
+    pub fn from_coefficients(table: *const c_int) -> Self {
+        for &space in &[
+            ColorSpace::ITU709,
+            ColorSpace::FCC,
+            ColorSpace::ITU601,
+            ColorSpace::SMPTE240M,
+            ColorSpace::BT2020,
+        ] {
+            if space.coefficients() == table {
+                return space;
+            }
+        }
+
+        ColorSpace::Default
+    }
 }
 
 impl From<c_int> for ColorSpace {
@@ -20,6 +46,7 @@ impl From<c_int> for ColorSpace {
             SWS_CS_FCC => ColorSpace::FCC,
             SWS_CS_DEFAULT => ColorSpace::Default,
             SWS_CS_SMPTE240M => ColorSpace::SMPTE240M,
+            SWS_CS_BT2020 => ColorSpace::BT2020,
 
             _ => ColorSpace::Default,
         }
@@ -36,6 +63,57 @@ impl Into<c_int> for ColorSpace {
             ColorSpace::ITU624 => SWS_CS_ITU624,
             ColorSpace::SMPTE170M => SWS_CS_SMPTE170M,
             ColorSpace::SMPTE240M => SWS_CS_SMPTE240M,
+            ColorSpace::BT2020 => SWS_CS_BT2020,
+        }
+    }
+}
+
+impl From<color::Space> for ColorSpace {
+    fn from(value: color::Space) -> ColorSpace {
+        match value {
+            color::Space::BT709 => ColorSpace::ITU709,
+            color::Space::FCC => ColorSpace::FCC,
+            color::Space::BT470BG => ColorSpace::ITU601,
+            color::Space::SMPTE170M => ColorSpace::SMPTE170M,
+            color::Space::SMPTE240M => ColorSpace::SMPTE240M,
+            color::Space::BT2020NCL | color::Space::BT2020CL => ColorSpace::BT2020,
+
+            _ => ColorSpace::Default,
+        }
+    }
+}
+
+/// The colorspace conversion performed by a scaler.
+///
+/// `brightness`, `contrast` and `saturation` are 16.16 fixed-point values.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Details {
+    pub source: ColorSpace,
+    pub source_range: color::Range,
+    pub destination: ColorSpace,
+    pub destination_range: color::Range,
+
+    pub brightness: i32,
+    pub contrast: i32,
+    pub saturation: i32,
+}
+
+impl Details {
+    pub fn new(
+        source: ColorSpace,
+        source_range: color::Range,
+        destination: ColorSpace,
+        destination_range: color::Range,
+    ) -> Self {
+        Details {
+            source,
+            source_range,
+            destination,
+            destination_range,
+
+            brightness: 0,
+            contrast: 1 << 16,
+            saturation: 1 << 16,
         }
     }
 }
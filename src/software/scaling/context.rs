@@ -1,9 +1,10 @@
 use std::ptr;
 
-use super::Flags;
+use super::color_space::Details;
+use super::{ColorSpace, Flags};
 use ffi::*;
 use libc::c_int;
-use util::format;
+use util::{color, format};
 use {frame, Error};
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
@@ -127,6 +128,63 @@ impl Context {
         &self.output
     }
 
+    /// Get the colorspaces and ranges the scaler converts between, `None` if
+    /// the formats do not support it.
+    pub fn colorspace_details(&self) -> Option<Details> {
+        unsafe {
+            let mut source = ptr::null_mut();
+            let mut source_range = 0;
+            let mut destination = ptr::null_mut();
+            let mut destination_range = 0;
+            let mut brightness = 0;
+            let mut contrast = 0;
+            let mut saturation = 0;
+
+            match sws_getColorspaceDetails(
+                self.as_ptr() as *mut _,
+                &mut source,
+                &mut source_range,
+                &mut destination,
+                &mut destination_range,
+                &mut brightness,
+                &mut contrast,
+                &mut saturation,
+            ) {
+                e if e < 0 => None,
+                _ => Some(Details {
+                    source: ColorSpace::from_coefficients(source),
+                    source_range: range(source_range),
+                    destination: ColorSpace::from_coefficients(destination),
+                    destination_range: range(destination_range),
+
+                    brightness,
+                    contrast,
+                    saturation,
+                }),
+            }
+        }
+    }
+
+    /// Set the colorspaces and ranges to convert between, e.g. to expand
+    /// limited-range BT.709 input to full-range RGB.
+    pub fn set_colorspace_details(&mut self, details: Details) -> Result<(), Error> {
+        unsafe {
+            match sws_setColorspaceDetails(
+                self.as_mut_ptr(),
+                details.source.coefficients(),
+                (details.source_range == color::Range::JPEG) as c_int,
+                details.destination.coefficients(),
+                (details.destination_range == color::Range::JPEG) as c_int,
+                details.brightness as c_int,
+                details.contrast as c_int,
+                details.saturation as c_int,
+            ) {
+                e if e < 0 => Err(Error::InvalidData),
+                _ => Ok(()),
+            }
+        }
+    }
+
     pub fn run(&mut self, input: &frame::Video, output: &mut frame::Video) -> Result<(), Error> {
         if input.format() != self.input.format
             || input.width() != self.input.width
@@ -162,6 +220,101 @@ impl Context {
 
         Ok(())
     }
+
+    /// Scale the band of `height` rows starting at row `y` of `input`.
+    ///
+    /// Bands must be processed in order, from top to bottom or from bottom to
+    /// top, and be aligned on the chroma subsampling. Returns the number of
+    /// rows written to `output`.
+    pub fn run_slice(
+        &mut self,
+        input: &frame::Video,
+        y: u32,
+        height: u32,
+        output: &mut frame::Video,
+    ) -> Result<u32, Error> {
+        if input.format() != self.input.format
+            || input.width() != self.input.width
+            || input.height() != self.input.height
+        {
+            return Err(Error::InputChanged);
+        }
+
+        if y.checked_add(height)
+            .map_or(true, |end| end > self.input.height)
+        {
+            return Err(Error::InvalidData);
+        }
+
+        unsafe {
+            if output.is_empty() {
+                output.alloc(self.output.format, self.output.width, self.output.height);
+            }
+        }
+
+        if output.format() != self.output.format
+            || output.width() != self.output.width
+            || output.height() != self.output.height
+        {
+            return Err(Error::OutputChanged);
+        }
+
+        unsafe {
+            let format: AVPixelFormat = self.input.format.into();
+            let descriptor = av_pix_fmt_desc_get(format);
+            let planes = av_pix_fmt_count_planes(format);
+
+            if descriptor.is_null() || planes < 0 {
+                return Err(Error::InvalidData);
+            }
+
+            let shift = (*descriptor).log2_chroma_h as u32;
+
+            let mask = (1 << shift) - 1;
+
+            if y & mask != 0 || (height & mask != 0 && y + height != self.input.height) {
+                return Err(Error::InvalidData);
+            }
+
+            let mut data = [ptr::null::<u8>(); 4];
+
+            for (plane, pointer) in data.iter_mut().enumerate().take(planes as usize) {
+                let rows = if plane == 1 || plane == 2 {
+                    y >> shift
+                } else {
+                    y
+                };
+
+                *pointer = (*input.as_ptr()).data[plane]
+                    .offset(rows as isize * (*input.as_ptr()).linesize[plane] as isize);
+            }
+
+            if (*descriptor).flags & AV_PIX_FMT_FLAG_PAL as u64 != 0 {
+                data[1] = (*input.as_ptr()).data[1];
+            }
+
+            match sws_scale(
+                self.as_mut_ptr(),
+                data.as_ptr(),
+                (*input.as_ptr()).linesize.as_ptr() as *const _,
+                y as c_int,
+                height as c_int,
+                (*output.as_mut_ptr()).data.as_ptr() as *const *mut _,
+                (*output.as_mut_ptr()).linesize.as_ptr() as *mut _,
+            ) {
+                e if e < 0 => Err(Error::from(e)),
+                rows => Ok(rows as u32),
+            }
+        }
+    }
+}
+
+fn range(value: c_int) -> color::Range {
+    if value != 0 {
+        color::Range::JPEG
+    } else {
+        color::Range::MPEG
+    }
 }
 
 impl Drop for Context {
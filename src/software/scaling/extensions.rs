@@ -1,7 +1,10 @@
 use super::{Context, Flags};
 use util::format;
-use {decoder, frame, Error, Picture};
+#[cfg(not(feature = "ffmpeg_5_0"))]
+use Picture;
+use {decoder, frame, Error};
 
+#[cfg(not(feature = "ffmpeg_5_0"))]
 impl<'a> Picture<'a> {
     #[inline]
     pub fn scaler(&self, width: u32, height: u32, flags: Flags) -> Result<Context, Error> {
@@ -84,4 +87,13 @@ impl decoder::Video {
             Flags::FAST_BILINEAR,
         )
     }
+
+    /// Get a scaler bringing the decoded frames within `limits`, keeping the
+    /// pixel format, see `Decoder::limit`.
+    #[inline]
+    pub fn fit(&self, limits: &decoder::Limits, flags: Flags) -> Result<Context, Error> {
+        let (width, height) = limits.fit(self.width(), self.height());
+
+        self.scaler(width, height, flags)
+    }
 }
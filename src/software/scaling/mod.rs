@@ -16,6 +16,9 @@ pub use self::filter::Filter;
 pub mod context;
 pub use self::context::Context;
 
+pub mod cached;
+pub use self::cached::Cached;
+
 mod extensions;
 
 use std::ffi::CStr;
@@ -0,0 +1,180 @@
+use ffi::*;
+use libc::{c_int, c_void, ENOMEM};
+use util::format;
+use {frame, Error};
+
+/// A FIFO buffer of audio samples.
+///
+/// Useful to feed encoders with a fixed frame size from a source producing
+/// frames of arbitrary size, such as a resampler.
+pub struct AudioFifo {
+    ptr: *mut AVAudioFifo,
+    format: format::Sample,
+    channels: u16,
+}
+
+unsafe impl Send for AudioFifo {}
+
+impl AudioFifo {
+    #[inline(always)]
+    pub unsafe fn as_ptr(&self) -> *const AVAudioFifo {
+        self.ptr as *const _
+    }
+
+    #[inline(always)]
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVAudioFifo {
+        self.ptr
+    }
+}
+
+impl AudioFifo {
+    /// Create a FIFO for samples of the given format and channel count, with
+    /// room for `capacity` samples. The FIFO grows as needed on write.
+    pub fn new(format: format::Sample, channels: u16, capacity: usize) -> Result<Self, Error> {
+        if channels == 0 || format == format::Sample::None {
+            return Err(Error::InvalidData);
+        }
+
+        unsafe {
+            let ptr = av_audio_fifo_alloc(format.into(), c_int::from(channels), capacity as c_int);
+
+            if ptr.is_null() {
+                Err(Error::Other { errno: ENOMEM })
+            } else {
+                Ok(AudioFifo {
+                    ptr,
+                    format,
+                    channels,
+                })
+            }
+        }
+    }
+
+    #[inline]
+    pub fn format(&self) -> format::Sample {
+        self.format
+    }
+
+    #[inline]
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// Get the number of samples currently in the FIFO.
+    #[inline]
+    pub fn samples(&self) -> usize {
+        unsafe { av_audio_fifo_size(self.ptr) as usize }
+    }
+
+    /// Get the number of samples that can be written without growing the FIFO.
+    #[inline]
+    pub fn space(&self) -> usize {
+        unsafe { av_audio_fifo_space(self.ptr) as usize }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.samples() == 0
+    }
+
+    /// Grow the FIFO to hold at least `capacity` samples.
+    pub fn reserve(&mut self, capacity: usize) -> Result<(), Error> {
+        unsafe {
+            match av_audio_fifo_realloc(self.as_mut_ptr(), capacity as c_int) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Append all the samples of `frame`, returning how many were written.
+    pub fn write(&mut self, frame: &frame::Audio) -> Result<usize, Error> {
+        self.check(frame)?;
+
+        unsafe {
+            match av_audio_fifo_write(
+                self.as_mut_ptr(),
+                (*frame.as_ptr()).extended_data as *mut *mut c_void,
+                frame.samples() as c_int,
+            ) {
+                e if e < 0 => Err(Error::from(e)),
+                n => Ok(n as usize),
+            }
+        }
+    }
+
+    /// Fill `frame` with up to `frame.samples()` samples from the FIFO.
+    ///
+    /// The number of samples of `frame` is updated to the amount actually
+    /// read, which is also returned.
+    pub fn read(&mut self, frame: &mut frame::Audio) -> Result<usize, Error> {
+        self.check(frame)?;
+
+        unsafe {
+            match av_audio_fifo_read(
+                self.as_mut_ptr(),
+                (*frame.as_mut_ptr()).extended_data as *mut *mut c_void,
+                frame.samples() as c_int,
+            ) {
+                e if e < 0 => Err(Error::from(e)),
+                n => {
+                    frame.set_samples(n as usize);
+                    Ok(n as usize)
+                }
+            }
+        }
+    }
+
+    /// Like `read`, but leaves the samples in the FIFO.
+    pub fn peek(&self, frame: &mut frame::Audio) -> Result<usize, Error> {
+        self.check(frame)?;
+
+        unsafe {
+            match av_audio_fifo_peek(
+                self.ptr,
+                (*frame.as_mut_ptr()).extended_data as *mut *mut c_void,
+                frame.samples() as c_int,
+            ) {
+                e if e < 0 => Err(Error::from(e)),
+                n => {
+                    frame.set_samples(n as usize);
+                    Ok(n as usize)
+                }
+            }
+        }
+    }
+
+    /// Discard up to `samples` samples from the start of the FIFO.
+    pub fn drain(&mut self, samples: usize) -> Result<(), Error> {
+        unsafe {
+            match av_audio_fifo_drain(self.as_mut_ptr(), samples as c_int) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Discard all the samples in the FIFO.
+    #[inline]
+    pub fn reset(&mut self) {
+        unsafe {
+            av_audio_fifo_reset(self.as_mut_ptr());
+        }
+    }
+
+    fn check(&self, frame: &frame::Audio) -> Result<(), Error> {
+        if frame.format() != self.format || frame.channels() != self.channels {
+            Err(Error::InvalidData)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Drop for AudioFifo {
+    fn drop(&mut self) {
+        unsafe {
+            av_audio_fifo_free(self.as_mut_ptr());
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Reference counted buffers, the memory backing frames and packets, and
+//! pools to reuse them instead of allocating for every frame.
+
+use std::mem;
+use std::slice;
+
+use ffi::*;
+use libc::ENOMEM;
+use Error;
+
+pub struct Buffer {
+    ptr: *mut AVBufferRef,
+}
+
+unsafe impl Send for Buffer {}
+unsafe impl Sync for Buffer {}
+
+impl Buffer {
+    pub unsafe fn wrap(ptr: *mut AVBufferRef) -> Self {
+        Buffer { ptr }
+    }
+
+    pub unsafe fn as_ptr(&self) -> *const AVBufferRef {
+        self.ptr as *const _
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVBufferRef {
+        self.ptr
+    }
+
+    /// Give up the reference, to be released with `av_buffer_unref`.
+    pub fn into_raw(self) -> *mut AVBufferRef {
+        let ptr = self.ptr;
+        mem::forget(self);
+
+        ptr
+    }
+}
+
+impl Buffer {
+    pub fn new(size: usize) -> Result<Self, Error> {
+        unsafe { Buffer::from_raw(av_buffer_alloc(size as _)) }
+    }
+
+    pub fn zeroed(size: usize) -> Result<Self, Error> {
+        unsafe { Buffer::from_raw(av_buffer_allocz(size as _)) }
+    }
+
+    pub fn copy(data: &[u8]) -> Result<Self, Error> {
+        let mut buffer = Buffer::new(data.len())?;
+        buffer.data_mut().unwrap().copy_from_slice(data);
+
+        Ok(buffer)
+    }
+
+    unsafe fn from_raw(ptr: *mut AVBufferRef) -> Result<Self, Error> {
+        if ptr.is_null() {
+            Err(Error::Other { errno: ENOMEM })
+        } else {
+            Ok(Buffer { ptr })
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        unsafe { (*self.as_ptr()).size as usize }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn data(&self) -> &[u8] {
+        unsafe {
+            if (*self.as_ptr()).data.is_null() {
+                &[]
+            } else {
+                slice::from_raw_parts((*self.as_ptr()).data, self.len())
+            }
+        }
+    }
+
+    /// Get the data of the buffer, `None` if it is shared with other
+    /// references, see `make_writable`.
+    pub fn data_mut(&mut self) -> Option<&mut [u8]> {
+        if !self.is_writable() {
+            return None;
+        }
+
+        unsafe {
+            if (*self.as_ptr()).data.is_null() {
+                Some(&mut [])
+            } else {
+                Some(slice::from_raw_parts_mut(
+                    (*self.as_mut_ptr()).data,
+                    self.len(),
+                ))
+            }
+        }
+    }
+
+    /// Whether this is the only reference to the buffer.
+    pub fn is_writable(&self) -> bool {
+        unsafe { av_buffer_is_writable(self.as_ptr()) != 0 }
+    }
+
+    pub fn references(&self) -> usize {
+        unsafe { av_buffer_get_ref_count(self.as_ptr()) as usize }
+    }
+
+    /// Copy the data if the buffer is shared, so it can be modified.
+    pub fn make_writable(&mut self) -> Result<(), Error> {
+        unsafe {
+            match av_buffer_make_writable(&mut self.ptr) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+}
+
+impl Clone for Buffer {
+    fn clone(&self) -> Self {
+        unsafe {
+            let ptr = av_buffer_ref(self.as_ptr() as *mut _);
+
+            if ptr.is_null() {
+                panic!("out of memory");
+            }
+
+            Buffer { ptr }
+        }
+    }
+}
+
+impl Drop for Buffer {
+    fn drop(&mut self) {
+        unsafe {
+            av_buffer_unref(&mut self.ptr);
+        }
+    }
+}
+
+/// A pool of buffers of the same size, returned to it when their last
+/// reference is dropped.
+pub struct BufferPool {
+    ptr: *mut AVBufferPool,
+}
+
+unsafe impl Send for BufferPool {}
+unsafe impl Sync for BufferPool {}
+
+impl BufferPool {
+    pub unsafe fn as_ptr(&self) -> *const AVBufferPool {
+        self.ptr as *const _
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVBufferPool {
+        self.ptr
+    }
+}
+
+impl BufferPool {
+    pub fn new(size: usize) -> Result<Self, Error> {
+        unsafe {
+            let ptr = av_buffer_pool_init(size as _, None);
+
+            if ptr.is_null() {
+                Err(Error::Other { errno: ENOMEM })
+            } else {
+                Ok(BufferPool { ptr })
+            }
+        }
+    }
+
+    /// Get a buffer from the pool, allocating it if none is available. Its
+    /// content is not initialized, buffers being reused as they are.
+    pub fn get(&self) -> Result<Buffer, Error> {
+        unsafe { Buffer::from_raw(av_buffer_pool_get(self.ptr)) }
+    }
+}
+
+impl Drop for BufferPool {
+    fn drop(&mut self) {
+        // the pool is freed once all its buffers are returned
+        unsafe {
+            av_buffer_pool_uninit(&mut self.ptr);
+        }
+    }
+}
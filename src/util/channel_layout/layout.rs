@@ -0,0 +1,532 @@
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::mem;
+
+use super::ChannelLayout;
+use ffi::AVChannel::*;
+use ffi::AVChannelOrder::*;
+use ffi::*;
+use libc::{c_char, c_int, c_uint, ENOMEM};
+use Error;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Order {
+    /// Only the number of channels is known.
+    Unspecified,
+    /// The channels are those of a mask, in the order of its bits.
+    Native,
+    /// The channels are listed explicitly, in any order.
+    Custom,
+    /// Ambisonic channels in ACN order, optionally followed by the channels
+    /// of a mask.
+    Ambisonic,
+}
+
+impl From<AVChannelOrder> for Order {
+    fn from(value: AVChannelOrder) -> Self {
+        match value {
+            AV_CHANNEL_ORDER_UNSPEC => Order::Unspecified,
+            AV_CHANNEL_ORDER_NATIVE => Order::Native,
+            AV_CHANNEL_ORDER_CUSTOM => Order::Custom,
+            AV_CHANNEL_ORDER_AMBISONIC => Order::Ambisonic,
+
+            #[allow(unreachable_patterns)]
+            _ => Order::Unspecified,
+        }
+    }
+}
+
+impl From<Order> for AVChannelOrder {
+    fn from(value: Order) -> AVChannelOrder {
+        match value {
+            Order::Unspecified => AV_CHANNEL_ORDER_UNSPEC,
+            Order::Native => AV_CHANNEL_ORDER_NATIVE,
+            Order::Custom => AV_CHANNEL_ORDER_CUSTOM,
+            Order::Ambisonic => AV_CHANNEL_ORDER_AMBISONIC,
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Channel {
+    None,
+    FrontLeft,
+    FrontRight,
+    FrontCenter,
+    LowFrequency,
+    BackLeft,
+    BackRight,
+    FrontLeftOfCenter,
+    FrontRightOfCenter,
+    BackCenter,
+    SideLeft,
+    SideRight,
+    TopCenter,
+    TopFrontLeft,
+    TopFrontCenter,
+    TopFrontRight,
+    TopBackLeft,
+    TopBackCenter,
+    TopBackRight,
+    StereoLeft,
+    StereoRight,
+    WideLeft,
+    WideRight,
+    SurroundDirectLeft,
+    SurroundDirectRight,
+    LowFrequency2,
+    TopSideLeft,
+    TopSideRight,
+    BottomFrontCenter,
+    BottomFrontLeft,
+    BottomFrontRight,
+
+    /// A channel that is present but carries no signal.
+    Unused,
+    /// A channel with unknown semantics.
+    Unknown,
+    /// The ambisonic component with the given ACN index.
+    Ambisonic(u32),
+}
+
+impl Channel {
+    /// Get the channel of a name such as "FL" or "AMBI3".
+    pub fn from_name(name: &str) -> Option<Channel> {
+        let name = CString::new(name).ok()?;
+
+        match Channel::from_raw(unsafe { raw::av_channel_from_string(name.as_ptr()) }) {
+            Channel::None => None,
+            channel => Some(channel),
+        }
+    }
+
+    /// Get the short name, e.g. "FL".
+    pub fn name(&self) -> String {
+        describe(|buf, size| unsafe { raw::av_channel_name(buf, size, self.into_raw()) })
+    }
+
+    /// Get the human readable description, e.g. "front left".
+    pub fn description(&self) -> String {
+        describe(|buf, size| unsafe { raw::av_channel_description(buf, size, self.into_raw()) })
+    }
+
+    fn from_raw(value: c_int) -> Self {
+        if value >= AV_CHAN_AMBISONIC_BASE as c_int && value <= AV_CHAN_AMBISONIC_END as c_int {
+            return Channel::Ambisonic((value - AV_CHAN_AMBISONIC_BASE as c_int) as u32);
+        }
+
+        match value {
+            v if v == AV_CHAN_FRONT_LEFT as c_int => Channel::FrontLeft,
+            v if v == AV_CHAN_FRONT_RIGHT as c_int => Channel::FrontRight,
+            v if v == AV_CHAN_FRONT_CENTER as c_int => Channel::FrontCenter,
+            v if v == AV_CHAN_LOW_FREQUENCY as c_int => Channel::LowFrequency,
+            v if v == AV_CHAN_BACK_LEFT as c_int => Channel::BackLeft,
+            v if v == AV_CHAN_BACK_RIGHT as c_int => Channel::BackRight,
+            v if v == AV_CHAN_FRONT_LEFT_OF_CENTER as c_int => Channel::FrontLeftOfCenter,
+            v if v == AV_CHAN_FRONT_RIGHT_OF_CENTER as c_int => Channel::FrontRightOfCenter,
+            v if v == AV_CHAN_BACK_CENTER as c_int => Channel::BackCenter,
+            v if v == AV_CHAN_SIDE_LEFT as c_int => Channel::SideLeft,
+            v if v == AV_CHAN_SIDE_RIGHT as c_int => Channel::SideRight,
+            v if v == AV_CHAN_TOP_CENTER as c_int => Channel::TopCenter,
+            v if v == AV_CHAN_TOP_FRONT_LEFT as c_int => Channel::TopFrontLeft,
+            v if v == AV_CHAN_TOP_FRONT_CENTER as c_int => Channel::TopFrontCenter,
+            v if v == AV_CHAN_TOP_FRONT_RIGHT as c_int => Channel::TopFrontRight,
+            v if v == AV_CHAN_TOP_BACK_LEFT as c_int => Channel::TopBackLeft,
+            v if v == AV_CHAN_TOP_BACK_CENTER as c_int => Channel::TopBackCenter,
+            v if v == AV_CHAN_TOP_BACK_RIGHT as c_int => Channel::TopBackRight,
+            v if v == AV_CHAN_STEREO_LEFT as c_int => Channel::StereoLeft,
+            v if v == AV_CHAN_STEREO_RIGHT as c_int => Channel::StereoRight,
+            v if v == AV_CHAN_WIDE_LEFT as c_int => Channel::WideLeft,
+            v if v == AV_CHAN_WIDE_RIGHT as c_int => Channel::WideRight,
+            v if v == AV_CHAN_SURROUND_DIRECT_LEFT as c_int => Channel::SurroundDirectLeft,
+            v if v == AV_CHAN_SURROUND_DIRECT_RIGHT as c_int => Channel::SurroundDirectRight,
+            v if v == AV_CHAN_LOW_FREQUENCY_2 as c_int => Channel::LowFrequency2,
+            v if v == AV_CHAN_TOP_SIDE_LEFT as c_int => Channel::TopSideLeft,
+            v if v == AV_CHAN_TOP_SIDE_RIGHT as c_int => Channel::TopSideRight,
+            v if v == AV_CHAN_BOTTOM_FRONT_CENTER as c_int => Channel::BottomFrontCenter,
+            v if v == AV_CHAN_BOTTOM_FRONT_LEFT as c_int => Channel::BottomFrontLeft,
+            v if v == AV_CHAN_BOTTOM_FRONT_RIGHT as c_int => Channel::BottomFrontRight,
+            v if v == AV_CHAN_UNUSED as c_int => Channel::Unused,
+            v if v == AV_CHAN_UNKNOWN as c_int => Channel::Unknown,
+
+            _ => Channel::None,
+        }
+    }
+
+    fn into_raw(self) -> c_int {
+        let channel = match self {
+            Channel::None => AV_CHAN_NONE,
+            Channel::FrontLeft => AV_CHAN_FRONT_LEFT,
+            Channel::FrontRight => AV_CHAN_FRONT_RIGHT,
+            Channel::FrontCenter => AV_CHAN_FRONT_CENTER,
+            Channel::LowFrequency => AV_CHAN_LOW_FREQUENCY,
+            Channel::BackLeft => AV_CHAN_BACK_LEFT,
+            Channel::BackRight => AV_CHAN_BACK_RIGHT,
+            Channel::FrontLeftOfCenter => AV_CHAN_FRONT_LEFT_OF_CENTER,
+            Channel::FrontRightOfCenter => AV_CHAN_FRONT_RIGHT_OF_CENTER,
+            Channel::BackCenter => AV_CHAN_BACK_CENTER,
+            Channel::SideLeft => AV_CHAN_SIDE_LEFT,
+            Channel::SideRight => AV_CHAN_SIDE_RIGHT,
+            Channel::TopCenter => AV_CHAN_TOP_CENTER,
+            Channel::TopFrontLeft => AV_CHAN_TOP_FRONT_LEFT,
+            Channel::TopFrontCenter => AV_CHAN_TOP_FRONT_CENTER,
+            Channel::TopFrontRight => AV_CHAN_TOP_FRONT_RIGHT,
+            Channel::TopBackLeft => AV_CHAN_TOP_BACK_LEFT,
+            Channel::TopBackCenter => AV_CHAN_TOP_BACK_CENTER,
+            Channel::TopBackRight => AV_CHAN_TOP_BACK_RIGHT,
+            Channel::StereoLeft => AV_CHAN_STEREO_LEFT,
+            Channel::StereoRight => AV_CHAN_STEREO_RIGHT,
+            Channel::WideLeft => AV_CHAN_WIDE_LEFT,
+            Channel::WideRight => AV_CHAN_WIDE_RIGHT,
+            Channel::SurroundDirectLeft => AV_CHAN_SURROUND_DIRECT_LEFT,
+            Channel::SurroundDirectRight => AV_CHAN_SURROUND_DIRECT_RIGHT,
+            Channel::LowFrequency2 => AV_CHAN_LOW_FREQUENCY_2,
+            Channel::TopSideLeft => AV_CHAN_TOP_SIDE_LEFT,
+            Channel::TopSideRight => AV_CHAN_TOP_SIDE_RIGHT,
+            Channel::BottomFrontCenter => AV_CHAN_BOTTOM_FRONT_CENTER,
+            Channel::BottomFrontLeft => AV_CHAN_BOTTOM_FRONT_LEFT,
+            Channel::BottomFrontRight => AV_CHAN_BOTTOM_FRONT_RIGHT,
+            Channel::Unused => AV_CHAN_UNUSED,
+            Channel::Unknown => AV_CHAN_UNKNOWN,
+
+            // FFmpeg represents the ambisonic components as offsets from
+            // the base value, which have no variant of their own, so they
+            // are only ever handled as integers.
+            Channel::Ambisonic(index) => {
+                let last =
+                    (AV_CHAN_AMBISONIC_END as c_int - AV_CHAN_AMBISONIC_BASE as c_int) as u32;
+
+                return AV_CHAN_AMBISONIC_BASE as c_int + index.min(last) as c_int;
+            }
+        };
+
+        channel as c_int
+    }
+}
+
+impl From<AVChannel> for Channel {
+    fn from(value: AVChannel) -> Self {
+        Channel::from_raw(value as c_int)
+    }
+}
+
+/// A channel layout of any order, backed by `AVChannelLayout`.
+pub struct Layout(AVChannelLayout);
+
+unsafe impl Send for Layout {}
+
+impl Layout {
+    /// Take ownership of a layout, which must have been initialized by
+    /// FFmpeg.
+    pub unsafe fn own(layout: AVChannelLayout) -> Self {
+        Layout(layout)
+    }
+
+    pub unsafe fn as_ptr(&self) -> *const AVChannelLayout {
+        &self.0
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVChannelLayout {
+        &mut self.0
+    }
+}
+
+impl Layout {
+    /// A layout of `channels` channels with unknown positions.
+    pub fn unspecified(channels: u32) -> Self {
+        unsafe {
+            let mut layout: AVChannelLayout = mem::zeroed();
+            layout.order = AV_CHANNEL_ORDER_UNSPEC;
+            layout.nb_channels = channels as c_int;
+
+            Layout(layout)
+        }
+    }
+
+    /// The layout of a legacy channel mask.
+    pub fn native(mask: ChannelLayout) -> Self {
+        unsafe {
+            let mut layout: AVChannelLayout = mem::zeroed();
+            layout.order = AV_CHANNEL_ORDER_NATIVE;
+            layout.nb_channels = mask.bits().count_ones() as c_int;
+            layout.u.mask = mask.bits();
+
+            Layout(layout)
+        }
+    }
+
+    /// The default layout for `channels` channels.
+    pub fn default(channels: u32) -> Self {
+        unsafe {
+            let mut layout: AVChannelLayout = mem::zeroed();
+            av_channel_layout_default(&mut layout, channels as c_int);
+
+            Layout(layout)
+        }
+    }
+
+    /// A layout of the given channels, in that order.
+    pub fn custom(channels: &[Channel]) -> Result<Self, Error> {
+        unsafe {
+            let map = av_mallocz(channels.len() * mem::size_of::<AVChannelCustom>())
+                as *mut AVChannelCustom;
+
+            if map.is_null() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            for (index, &channel) in channels.iter().enumerate() {
+                // written as an integer, ambisonic channels have no variant
+                *(&mut (*map.add(index)).id as *mut AVChannel as *mut c_int) = channel.into_raw();
+            }
+
+            let mut layout: AVChannelLayout = mem::zeroed();
+            layout.order = AV_CHANNEL_ORDER_CUSTOM;
+            layout.nb_channels = channels.len() as c_int;
+            layout.u.map = map;
+
+            Ok(Layout(layout))
+        }
+    }
+
+    /// An ambisonic layout of the given order, followed by the channels of
+    /// `extra` (e.g. a stereo head-locked pair).
+    pub fn ambisonic(order: u32, extra: ChannelLayout) -> Self {
+        unsafe {
+            let mut layout: AVChannelLayout = mem::zeroed();
+            layout.order = AV_CHANNEL_ORDER_AMBISONIC;
+            layout.nb_channels = ((order + 1) * (order + 1) + extra.bits().count_ones()) as c_int;
+            layout.u.mask = extra.bits();
+
+            Layout(layout)
+        }
+    }
+
+    /// Parse a layout such as "5.1", "FL+FR+LFE", "ambisonic 1" or "4c".
+    pub fn parse(description: &str) -> Result<Self, Error> {
+        let description = CString::new(description).map_err(|_| Error::InvalidData)?;
+
+        unsafe {
+            let mut layout: AVChannelLayout = mem::zeroed();
+
+            match av_channel_layout_from_string(&mut layout, description.as_ptr()) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(Layout(layout)),
+            }
+        }
+    }
+
+    pub fn order(&self) -> Order {
+        Order::from(self.0.order)
+    }
+
+    pub fn channels(&self) -> u32 {
+        self.0.nb_channels as u32
+    }
+
+    /// Get the legacy mask of a native layout.
+    pub fn mask(&self) -> Option<ChannelLayout> {
+        if self.0.order == AV_CHANNEL_ORDER_NATIVE {
+            unsafe { Some(ChannelLayout::from_bits_truncate(self.0.u.mask)) }
+        } else {
+            None
+        }
+    }
+
+    /// Get the channel at `index`.
+    pub fn channel(&self, index: u32) -> Option<Channel> {
+        unsafe {
+            match Channel::from_raw(raw::av_channel_layout_channel_from_index(
+                &self.0,
+                index as c_uint,
+            )) {
+                Channel::None => None,
+                channel => Some(channel),
+            }
+        }
+    }
+
+    /// Get the index of `channel` in the layout.
+    pub fn index(&self, channel: Channel) -> Option<u32> {
+        unsafe {
+            match raw::av_channel_layout_index_from_channel(&self.0, channel.into_raw()) {
+                e if e < 0 => None,
+                index => Some(index as u32),
+            }
+        }
+    }
+
+    pub fn iter(&self) -> ChannelIter {
+        ChannelIter {
+            layout: self,
+            cur: 0,
+        }
+    }
+
+    /// Get the description, as accepted by `parse`.
+    pub fn description(&self) -> String {
+        describe(|buf, size| unsafe { av_channel_layout_describe(&self.0, buf, size) })
+    }
+
+    pub fn is_valid(&self) -> bool {
+        unsafe { av_channel_layout_check(&self.0) == 1 }
+    }
+}
+
+impl Clone for Layout {
+    fn clone(&self) -> Self {
+        unsafe {
+            let mut layout: AVChannelLayout = mem::zeroed();
+
+            if av_channel_layout_copy(&mut layout, &self.0) < 0 {
+                panic!("out of memory");
+            }
+
+            Layout(layout)
+        }
+    }
+}
+
+impl Drop for Layout {
+    fn drop(&mut self) {
+        unsafe {
+            av_channel_layout_uninit(&mut self.0);
+        }
+    }
+}
+
+impl PartialEq for Layout {
+    fn eq(&self, other: &Self) -> bool {
+        unsafe { av_channel_layout_compare(&self.0, &other.0) == 0 }
+    }
+}
+
+impl fmt::Debug for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.description())
+    }
+}
+
+impl From<ChannelLayout> for Layout {
+    fn from(value: ChannelLayout) -> Self {
+        Layout::native(value)
+    }
+}
+
+pub struct ChannelIter<'a> {
+    layout: &'a Layout,
+    cur: u32,
+}
+
+impl<'a> Iterator for ChannelIter<'a> {
+    type Item = Channel;
+
+    fn next(&mut self) -> Option<Channel> {
+        if self.cur >= self.layout.channels() {
+            return None;
+        }
+
+        let channel = self.layout.channel(self.cur);
+        self.cur += 1;
+
+        Some(channel.unwrap_or(Channel::Unknown))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let length = (self.layout.channels() - self.cur) as usize;
+
+        (length, Some(length))
+    }
+}
+
+impl<'a> ExactSizeIterator for ChannelIter<'a> {}
+
+/// Call a function writing a string into a buffer, returning the size
+/// needed including the terminating nul.
+fn describe<F>(mut function: F) -> String
+where
+    F: FnMut(*mut c_char, usize) -> c_int,
+{
+    let mut buffer = vec![0 as c_char; 64];
+
+    loop {
+        let size = function(buffer.as_mut_ptr(), buffer.len());
+
+        if size < 0 {
+            return String::new();
+        }
+
+        if size as usize <= buffer.len() {
+            break;
+        }
+
+        buffer.resize(size as usize, 0);
+    }
+
+    unsafe {
+        CStr::from_ptr(buffer.as_ptr())
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+// The functions taking or returning an `AVChannel`, declared with integers
+// as FFmpeg uses values outside of the variants of the enum for the
+// ambisonic channels.
+mod raw {
+    use ffi::AVChannelLayout;
+    use libc::{c_char, c_int, c_uint};
+
+    extern "C" {
+        pub fn av_channel_name(buf: *mut c_char, buf_size: usize, channel: c_int) -> c_int;
+        pub fn av_channel_description(buf: *mut c_char, buf_size: usize, channel: c_int) -> c_int;
+        pub fn av_channel_from_string(name: *const c_char) -> c_int;
+        pub fn av_channel_layout_channel_from_index(
+            channel_layout: *const AVChannelLayout,
+            idx: c_uint,
+        ) -> c_int;
+        pub fn av_channel_layout_index_from_channel(
+            channel_layout: *const AVChannelLayout,
+            channel: c_int,
+        ) -> c_int;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layouts() {
+        let layout = Layout::native(ChannelLayout::_5POINT1);
+        assert_eq!(layout.channels(), 6);
+        assert_eq!(layout.mask(), Some(ChannelLayout::_5POINT1));
+        assert_eq!(layout.channel(3), Some(Channel::LowFrequency));
+        assert_eq!(Layout::parse("5.1").unwrap(), layout);
+
+        let layout = Layout::ambisonic(1, ChannelLayout::STEREO);
+        assert_eq!(layout.order(), Order::Ambisonic);
+        assert_eq!(layout.channels(), 6);
+        assert_eq!(layout.channel(2), Some(Channel::Ambisonic(2)));
+        assert_eq!(layout.channel(5), Some(Channel::FrontRight));
+        assert!(layout.is_valid());
+
+        let layout = Layout::custom(&[Channel::FrontRight, Channel::FrontLeft]).unwrap();
+        assert_eq!(layout.index(Channel::FrontLeft), Some(1));
+        assert_eq!(layout.mask(), None);
+
+        assert_eq!(Channel::from_name("FL"), Some(Channel::FrontLeft));
+        assert_eq!(Channel::Ambisonic(3).name(), "AMBI3");
+    }
+
+    #[test]
+    fn test_masks() {
+        let layout = ChannelLayout::from_name("5.1(side)").unwrap();
+        assert_eq!(layout, ChannelLayout::_5POINT1);
+        assert_eq!(layout.describe(), "5.1(side)");
+        assert_eq!(layout.channel_index(Channel::LowFrequency), Some(3));
+        assert_eq!(layout.channel_index(Channel::BackLeft), None);
+        assert_eq!(layout.iter().count(), 6);
+        assert_eq!(ChannelLayout::from_name("ambisonic 1"), None);
+    }
+}
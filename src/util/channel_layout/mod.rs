@@ -1,4 +1,14 @@
+#[cfg(feature = "ffmpeg_5_1")]
+pub mod layout;
+#[cfg(feature = "ffmpeg_5_1")]
+pub use self::layout::{Channel, Layout, Order};
+
+#[cfg(not(feature = "ffmpeg_5_1"))]
+use std::ffi::{CStr, CString};
+
 use ffi::*;
+#[cfg(not(feature = "ffmpeg_5_1"))]
+use libc::c_char;
 use libc::c_ulonglong;
 
 bitflags! {
@@ -62,14 +72,122 @@ bitflags! {
 }
 
 impl ChannelLayout {
+    #[cfg(not(feature = "ffmpeg_7_0"))]
     #[inline]
     pub fn channels(&self) -> i32 {
         unsafe { av_get_channel_layout_nb_channels(self.bits()) }
     }
 
+    #[cfg(feature = "ffmpeg_7_0")]
+    #[inline]
+    pub fn channels(&self) -> i32 {
+        self.bits().count_ones() as i32
+    }
+
+    #[cfg(not(feature = "ffmpeg_7_0"))]
     pub fn default(number: i32) -> ChannelLayout {
         unsafe {
             ChannelLayout::from_bits_truncate(av_get_default_channel_layout(number) as c_ulonglong)
         }
     }
+
+    #[cfg(feature = "ffmpeg_7_0")]
+    pub fn default(number: i32) -> ChannelLayout {
+        Layout::default(number.max(0) as u32)
+            .mask()
+            .unwrap_or_else(ChannelLayout::empty)
+    }
+
+    /// Parse a layout such as "stereo", "5.1(side)" or "FL+FR+LFE", `None`
+    /// if it is invalid or not a native layout.
+    #[cfg(feature = "ffmpeg_5_1")]
+    pub fn from_name(name: &str) -> Option<ChannelLayout> {
+        Layout::parse(name).ok()?.mask()
+    }
+
+    /// Parse a layout such as "stereo", "5.1(side)" or "FL+FR+LFE", `None`
+    /// if it is invalid.
+    #[cfg(not(feature = "ffmpeg_5_1"))]
+    pub fn from_name(name: &str) -> Option<ChannelLayout> {
+        let name = CString::new(name).ok()?;
+
+        match unsafe { av_get_channel_layout(name.as_ptr()) } {
+            0 => None,
+            bits => Some(ChannelLayout::from_bits_truncate(bits as c_ulonglong)),
+        }
+    }
+
+    /// Get the description, e.g. "5.1(side)", as accepted by `from_name`.
+    #[cfg(feature = "ffmpeg_5_1")]
+    pub fn describe(&self) -> String {
+        Layout::native(*self).description()
+    }
+
+    /// Get the description, e.g. "5.1(side)", as accepted by `from_name`.
+    #[cfg(not(feature = "ffmpeg_5_1"))]
+    pub fn describe(&self) -> String {
+        let mut buffer = [0 as c_char; 128];
+
+        unsafe {
+            av_get_channel_layout_string(
+                buffer.as_mut_ptr(),
+                buffer.len() as _,
+                self.channels(),
+                self.bits(),
+            );
+
+            CStr::from_ptr(buffer.as_ptr())
+                .to_string_lossy()
+                .into_owned()
+        }
+    }
+
+    /// Get the index of `channel` in the layout, i.e. of its plane in
+    /// planar frames.
+    #[cfg(feature = "ffmpeg_5_1")]
+    pub fn channel_index(&self, channel: Channel) -> Option<u32> {
+        Layout::native(*self).index(channel)
+    }
+
+    /// Get the channel at `index`.
+    #[cfg(feature = "ffmpeg_5_1")]
+    pub fn channel(&self, index: u32) -> Option<Channel> {
+        Layout::native(*self).channel(index)
+    }
+
+    /// Iterate over the channels, in the order of their planes.
+    #[cfg(feature = "ffmpeg_5_1")]
+    pub fn iter(&self) -> Channels {
+        Channels {
+            layout: Layout::native(*self),
+            cur: 0,
+        }
+    }
+}
+
+#[cfg(feature = "ffmpeg_5_1")]
+pub struct Channels {
+    layout: Layout,
+    cur: u32,
+}
+
+#[cfg(feature = "ffmpeg_5_1")]
+impl Iterator for Channels {
+    type Item = Channel;
+
+    fn next(&mut self) -> Option<Channel> {
+        let channel = self.layout.channel(self.cur)?;
+        self.cur += 1;
+
+        Some(channel)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let length = (self.layout.channels() - self.cur) as usize;
+
+        (length, Some(length))
+    }
 }
+
+#[cfg(feature = "ffmpeg_5_1")]
+impl ExactSizeIterator for Channels {}
@@ -0,0 +1,17 @@
+use ffi::*;
+use libc::c_int;
+
+bitflags! {
+    pub struct Flags: c_int {
+        /// Match keys case-sensitively.
+        const MATCH_CASE     = AV_DICT_MATCH_CASE;
+        /// Match keys by prefix, e.g. to iterate over all the entries.
+        const IGNORE_SUFFIX  = AV_DICT_IGNORE_SUFFIX;
+        /// Keep the current value of existing keys.
+        const DONT_OVERWRITE = AV_DICT_DONT_OVERWRITE;
+        /// Append to the current value of existing keys, without separator.
+        const APPEND         = AV_DICT_APPEND;
+        /// Add entries even if their key already exists.
+        const MULTIKEY       = AV_DICT_MULTIKEY;
+    }
+}
@@ -4,8 +4,10 @@ use std::marker::PhantomData;
 use std::ptr;
 use std::str::from_utf8_unchecked;
 
-use super::{Iter, Owned};
+use super::{Flags, Iter, Owned, Values};
 use ffi::*;
+use libc::{c_char, c_void};
+use Error;
 
 pub struct Ref<'a> {
     ptr: *const AVDictionary,
@@ -42,6 +44,51 @@ impl<'a> Ref<'a> {
         }
     }
 
+    /// Get the value of `key`, matched according to `flags`, e.g. case
+    /// sensitively.
+    pub fn get_with(&'a self, key: &str, flags: Flags) -> Option<&'a str> {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            let entry = av_dict_get(self.as_ptr(), key.as_ptr(), ptr::null_mut(), flags.bits());
+
+            if entry.is_null() {
+                None
+            } else {
+                Some(from_utf8_unchecked(
+                    CStr::from_ptr((*entry).value).to_bytes(),
+                ))
+            }
+        }
+    }
+
+    /// Iterate over all the values of `key`, for keys set multiple times.
+    pub fn get_all(&self, key: &str) -> Values {
+        unsafe { Values::new(self.as_ptr(), key, Flags::empty()) }
+    }
+
+    /// Serialize the entries, e.g. as `k=v:k2=v2` with `'='` and `':'` as
+    /// separators, special characters being escaped.
+    pub fn serialize(&self, key_val_sep: char, pairs_sep: char) -> Result<String, Error> {
+        unsafe {
+            let mut buffer = ptr::null_mut();
+
+            match av_dict_get_string(
+                self.as_ptr(),
+                &mut buffer,
+                key_val_sep as c_char,
+                pairs_sep as c_char,
+            ) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => {
+                    let string = CStr::from_ptr(buffer).to_string_lossy().into_owned();
+                    av_free(buffer as *mut c_void);
+
+                    Ok(string)
+                }
+            }
+        }
+    }
+
     pub fn iter(&self) -> Iter {
         unsafe { Iter::new(self.as_ptr()) }
     }
@@ -3,7 +3,9 @@ use std::marker::PhantomData;
 use std::ptr;
 use std::str::from_utf8_unchecked;
 
+use super::Flags;
 use ffi::*;
+use libc::c_int;
 
 pub struct Iter<'a> {
     ptr: *const AVDictionary,
@@ -44,3 +46,46 @@ impl<'a> Iterator for Iter<'a> {
         }
     }
 }
+
+/// An iterator over the values of a key, added with `Flags::MULTIKEY`.
+pub struct Values<'a> {
+    ptr: *const AVDictionary,
+    key: CString,
+    flags: c_int,
+    cur: *mut AVDictionaryEntry,
+
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> Values<'a> {
+    pub unsafe fn new(dictionary: *const AVDictionary, key: &str, flags: Flags) -> Self {
+        Values {
+            ptr: dictionary,
+            key: CString::new(key).unwrap(),
+            flags: flags.bits(),
+            cur: ptr::null_mut(),
+
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a> Iterator for Values<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        unsafe {
+            let entry = av_dict_get(self.ptr, self.key.as_ptr(), self.cur, self.flags);
+
+            if entry.is_null() {
+                return None;
+            }
+
+            self.cur = entry;
+
+            Some(from_utf8_unchecked(
+                CStr::from_ptr((*entry).value).to_bytes(),
+            ))
+        }
+    }
+}
@@ -8,7 +8,10 @@ mod owned;
 pub use self::owned::Owned;
 
 mod iter;
-pub use self::iter::Iter;
+pub use self::iter::{Iter, Values};
+
+mod flag;
+pub use self::flag::Flags;
 
 #[macro_export]
 macro_rules! dict {
@@ -23,3 +26,52 @@ macro_rules! dict {
 		}
 	);
 }
+
+/// Implement `IntoIterator` and `From<_> for Dictionary` for typed options,
+/// listed by `$values`, or kept in an `options: Vec<(String, String)>` field.
+macro_rules! into_dictionary {
+    ($name:ident) => {
+        into_dictionary!($name, |value: $name| value.options);
+    };
+
+    ($name:ident, $values:expr) => {
+        impl IntoIterator for $name {
+            type Item = (String, String);
+            type IntoIter = ::std::vec::IntoIter<(String, String)>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                let values: fn($name) -> Vec<(String, String)> = $values;
+                values(self).into_iter()
+            }
+        }
+
+        impl<'a> From<$name> for $crate::Dictionary<'a> {
+            fn from(value: $name) -> Self {
+                value.into_iter().collect()
+            }
+        }
+    };
+}
+
+/// Implement `new()` and the private `set()` of typed options kept in an
+/// `options: Vec<(String, String)>` field, along with `into_dictionary!`.
+macro_rules! options {
+    ($name:ident) => {
+        options!($name, |value: $name| value.options);
+    };
+
+    ($name:ident, $values:expr) => {
+        impl $name {
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            fn set<V: Into<String>>(mut self, key: &str, value: V) -> Self {
+                self.options.push((key.into(), value.into()));
+                self
+            }
+        }
+
+        into_dictionary!($name, $values);
+    };
+}
@@ -3,8 +3,9 @@ use std::fmt;
 use std::marker::PhantomData;
 use std::ops::Deref;
 
-use super::immutable;
+use super::{immutable, Flags};
 use ffi::*;
+use Error;
 
 pub struct Ref<'a> {
     ptr: *mut AVDictionary,
@@ -42,6 +43,73 @@ impl<'a> Ref<'a> {
             self.imm = immutable::Ref::wrap(ptr);
         }
     }
+
+    /// Set an entry according to `flags`, e.g. to append to the current
+    /// value or keep it.
+    pub fn set_with(&mut self, key: &str, value: &str, flags: Flags) {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            let value = CString::new(value).unwrap();
+            let mut ptr = self.as_mut_ptr();
+
+            if av_dict_set(&mut ptr, key.as_ptr(), value.as_ptr(), flags.bits()) < 0 {
+                panic!("out of memory");
+            }
+
+            self.ptr = ptr;
+            self.imm = immutable::Ref::wrap(ptr);
+        }
+    }
+
+    /// Parse entries such as `k=v:k2=v2`, the separators being any of the
+    /// characters of `key_val_sep` and `pairs_sep`, and set them according
+    /// to `flags`.
+    pub fn parse(
+        &mut self,
+        string: &str,
+        key_val_sep: &str,
+        pairs_sep: &str,
+        flags: Flags,
+    ) -> Result<(), Error> {
+        unsafe {
+            let string = CString::new(string).map_err(|_| Error::InvalidData)?;
+            let key_val_sep = CString::new(key_val_sep).map_err(|_| Error::InvalidData)?;
+            let pairs_sep = CString::new(pairs_sep).map_err(|_| Error::InvalidData)?;
+            let mut ptr = self.as_mut_ptr();
+
+            let res = av_dict_parse_string(
+                &mut ptr,
+                string.as_ptr(),
+                key_val_sep.as_ptr(),
+                pairs_sep.as_ptr(),
+                flags.bits(),
+            );
+
+            // the entries parsed before a failure are kept
+            self.ptr = ptr;
+            self.imm = immutable::Ref::wrap(ptr);
+
+            match res {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Set the entries of `other` according to `flags`, e.g. to merge
+    /// metadata without overwriting the existing values.
+    pub fn merge(&mut self, other: &immutable::Ref, flags: Flags) {
+        unsafe {
+            let mut ptr = self.as_mut_ptr();
+
+            if av_dict_copy(&mut ptr, other.as_ptr(), flags.bits()) < 0 {
+                panic!("out of memory");
+            }
+
+            self.ptr = ptr;
+            self.imm = immutable::Ref::wrap(ptr);
+        }
+    }
 }
 
 impl<'a> Deref for Ref<'a> {
@@ -57,3 +125,32 @@ impl<'a> fmt::Debug for Ref<'a> {
         self.imm.fmt(fmt)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Flags, Owned};
+
+    #[test]
+    fn test_parse_serialize() {
+        let mut dictionary = Owned::new();
+        dictionary
+            .parse("title=a\\:b:artist=c", "=", ":", Flags::empty())
+            .unwrap();
+
+        assert_eq!(dictionary.get("title"), Some("a:b"));
+        assert_eq!(dictionary.get_with("TITLE", Flags::MATCH_CASE), None);
+
+        dictionary.set_with("artist", "d", Flags::MULTIKEY);
+        assert_eq!(dictionary.get_all("artist").collect::<Vec<_>>(), ["c", "d"]);
+
+        let mut other = Owned::new();
+        other.set("title", "e");
+        other.set("album", "f");
+        dictionary.merge(&other, Flags::DONT_OVERWRITE);
+
+        assert_eq!(
+            dictionary.serialize('=', ':').unwrap(),
+            "title=a\\:b:artist=c:artist=d:album=f"
+        );
+    }
+}
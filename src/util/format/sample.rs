@@ -4,6 +4,8 @@ use std::ptr;
 use std::slice;
 use std::str::from_utf8_unchecked;
 
+#[cfg(feature = "codec")]
+use codec;
 use ffi::AVSampleFormat::*;
 use ffi::*;
 use libc::{c_int, c_void};
@@ -34,6 +36,17 @@ impl Sample {
         }
     }
 
+    /// Look a format up by name, e.g. `fltp`.
+    #[inline]
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = CString::new(name).ok()?;
+
+        match unsafe { Sample::from(av_get_sample_fmt(name.as_ptr())) } {
+            Sample::None => None,
+            format => Some(format),
+        }
+    }
+
     #[inline]
     pub fn packed(&self) -> Self {
         unsafe { Sample::from(av_get_packed_sample_fmt((*self).into())) }
@@ -44,6 +57,18 @@ impl Sample {
         unsafe { Sample::from(av_get_planar_sample_fmt((*self).into())) }
     }
 
+    /// Get the planar variant of a packed format, or the packed variant of
+    /// a planar one.
+    #[inline]
+    pub fn alt(&self) -> Self {
+        unsafe {
+            Sample::from(av_get_alt_sample_fmt(
+                (*self).into(),
+                self.is_packed() as c_int,
+            ))
+        }
+    }
+
     #[inline]
     pub fn is_planar(&self) -> bool {
         unsafe { av_sample_fmt_is_planar((*self).into()) == 1 }
@@ -59,6 +84,28 @@ impl Sample {
         unsafe { av_get_bytes_per_sample((*self).into()) as usize }
     }
 
+    /// Get the size of the samples of each plane of a frame, i.e. of all
+    /// the channels for packed formats.
+    #[inline]
+    pub fn plane_bytes(&self, channels: u16, samples: usize) -> usize {
+        if self.is_planar() {
+            self.bytes() * samples
+        } else {
+            self.bytes() * samples * channels as usize
+        }
+    }
+
+    /// Check whether `codec` accepts the format, codecs not listing their
+    /// formats accepting any.
+    #[cfg(feature = "codec")]
+    #[inline]
+    pub fn is_valid_for(&self, codec: &codec::Audio) -> bool {
+        match codec.formats() {
+            Some(mut formats) => formats.any(|format| format == *self),
+            None => *self != Sample::None,
+        }
+    }
+
     #[inline]
     pub fn buffer(&self, channels: u16, samples: usize, align: bool) -> Buffer {
         Buffer::new(*self, channels, samples, align)
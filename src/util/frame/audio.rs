@@ -1,12 +1,15 @@
 use std::mem;
 use std::ops::{Deref, DerefMut};
+use std::ptr;
 use std::slice;
 
 use super::Frame;
 use ffi::*;
-use libc::{c_int, c_ulonglong};
+use libc::c_int;
+#[cfg(feature = "ffmpeg_5_1")]
+use util::channel_layout::Layout;
 use util::format;
-use ChannelLayout;
+use {ChannelLayout, Error};
 /// The audio frame.
 #[derive(PartialEq, Eq)]
 pub struct Audio(Frame);
@@ -65,49 +68,111 @@ impl Audio {
         }
     }
 
+    #[cfg(not(feature = "ffmpeg_7_0"))]
     #[inline]
     /// Get the channel layout map.
     pub fn channel_layout(&self) -> ChannelLayout {
+        unsafe { ChannelLayout::from_bits_truncate((*self.as_ptr()).channel_layout) }
+    }
+
+    #[cfg(feature = "ffmpeg_7_0")]
+    #[inline]
+    /// Get the channel layout map, empty if the layout is not a native one.
+    pub fn channel_layout(&self) -> ChannelLayout {
+        self.ch_layout().mask().unwrap_or_else(ChannelLayout::empty)
+    }
+
+    #[cfg(not(feature = "ffmpeg_7_0"))]
+    #[inline]
+    /// Set the channel layout map of audio frame.
+    pub fn set_channel_layout(&mut self, value: ChannelLayout) {
         unsafe {
-            ChannelLayout::from_bits_truncate(
-                av_frame_get_channel_layout(self.as_ptr()) as c_ulonglong
-            )
+            (*self.as_mut_ptr()).channel_layout = value.bits();
         }
     }
 
+    #[cfg(feature = "ffmpeg_7_0")]
     #[inline]
-    /// Set the channel layout map of audio frame.
+    /// Set the channel layout map of audio frame, ignoring an empty one,
+    /// panicking if out of memory, see the fallible `set_ch_layout`.
     pub fn set_channel_layout(&mut self, value: ChannelLayout) {
+        if !value.is_empty() {
+            if self.set_ch_layout(&Layout::native(value)).is_err() {
+                panic!("out of memory");
+            }
+        }
+    }
+
+    /// Get the channel layout, of any order.
+    #[cfg(feature = "ffmpeg_5_1")]
+    pub fn ch_layout(&self) -> Layout {
+        unsafe {
+            let mut layout = Layout::unspecified(0);
+            av_channel_layout_copy(layout.as_mut_ptr(), &(*self.as_ptr()).ch_layout);
+
+            layout
+        }
+    }
+
+    /// Set the channel layout, of any order.
+    #[cfg(feature = "ffmpeg_5_1")]
+    pub fn set_ch_layout(&mut self, value: &Layout) -> Result<(), Error> {
         unsafe {
-            av_frame_set_channel_layout(self.as_mut_ptr(), value.bits() as i64);
+            match av_channel_layout_copy(&mut (*self.as_mut_ptr()).ch_layout, value.as_ptr()) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
         }
     }
 
+    #[cfg(not(feature = "ffmpeg_7_0"))]
+    #[inline]
+    /// Get the total amount of channels of audio frame.
+    pub fn channels(&self) -> u16 {
+        unsafe { (*self.as_ptr()).channels as u16 }
+    }
+
+    #[cfg(feature = "ffmpeg_7_0")]
     #[inline]
     /// Get the total amount of channels of audio frame.
     pub fn channels(&self) -> u16 {
-        unsafe { av_frame_get_channels(self.as_ptr()) as u16 }
+        unsafe { (*self.as_ptr()).ch_layout.nb_channels as u16 }
     }
 
+    #[cfg(not(feature = "ffmpeg_7_0"))]
     #[inline]
     /// Set the total amount of channels of audio frame.
     pub fn set_channels(&mut self, value: u16) {
         unsafe {
-            av_frame_set_channels(self.as_mut_ptr(), i32::from(value));
+            (*self.as_mut_ptr()).channels = i32::from(value);
+        }
+    }
+
+    #[cfg(feature = "ffmpeg_7_0")]
+    #[inline]
+    /// Set the total amount of channels of audio frame, unspecified unless
+    /// the current layout already has that many, panicking if out of memory.
+    pub fn set_channels(&mut self, value: u16) {
+        if self.channels() != value {
+            let layout = Layout::unspecified(u32::from(value));
+
+            if self.set_ch_layout(&layout).is_err() {
+                panic!("out of memory");
+            }
         }
     }
 
     #[inline]
     /// Get the sample rate of audio.
     pub fn rate(&self) -> u32 {
-        unsafe { av_frame_get_sample_rate(self.as_ptr()) as u32 }
+        unsafe { (*self.as_ptr()).sample_rate as u32 }
     }
 
     #[inline]
     /// Set the sample rate of audio.
     pub fn set_rate(&mut self, value: u32) {
         unsafe {
-            av_frame_set_sample_rate(self.as_mut_ptr(), value as c_int);
+            (*self.as_mut_ptr()).sample_rate = value as c_int;
         }
     }
 
@@ -164,7 +229,12 @@ impl Audio {
             panic!("unsupported type");
         }
 
-        unsafe { slice::from_raw_parts((*self.as_ptr()).data[index] as *const T, self.samples()) }
+        unsafe {
+            slice::from_raw_parts(
+                (*self.as_ptr()).data[index] as *const T,
+                self.plane_len::<T>(),
+            )
+        }
     }
 
     #[inline]
@@ -181,11 +251,78 @@ impl Audio {
         }
 
         unsafe {
-            slice::from_raw_parts_mut((*self.as_mut_ptr()).data[index] as *mut T, self.samples())
+            slice::from_raw_parts_mut(
+                (*self.as_mut_ptr()).data[index] as *mut T,
+                self.plane_len::<T>(),
+            )
+        }
+    }
+
+    // get the number of `T` in a plane, packed frames holding the samples of
+    // all the channels, as scalars or as tuples
+    #[inline]
+    fn plane_len<T: Sample>(&self) -> usize {
+        if self.is_packed() {
+            self.plane_size() / mem::size_of::<T>()
+        } else {
+            self.samples()
         }
     }
 
+    // get the exact size of a plane, without the padding of its line size
     #[inline]
+    fn plane_size(&self) -> usize {
+        let channels = if self.is_packed() { self.channels() } else { 1 };
+
+        unsafe {
+            match av_samples_get_buffer_size(
+                ptr::null_mut(),
+                c_int::from(channels),
+                self.samples() as c_int,
+                self.format().into(),
+                1,
+            ) {
+                e if e < 0 => 0,
+                size => size as usize,
+            }
+        }
+    }
+
+    /// Get the exact size of the samples of all the planes, in bytes.
+    #[inline]
+    pub fn samples_bytes(&self) -> usize {
+        self.plane_size() * self.planes()
+    }
+
+    /// Get the bytes of the samples of a plane, sized from the number of
+    /// samples.
+    #[inline]
+    pub fn plane_bytes(&self, index: usize) -> &[u8] {
+        if index >= self.planes() {
+            panic!("out of bounds");
+        }
+
+        unsafe { slice::from_raw_parts((*self.as_ptr()).data[index], self.plane_size()) }
+    }
+
+    /// Like [plane_bytes()], but the data is mutable.
+    ///
+    /// [plane_bytes()]: self::plane_bytes()
+    #[inline]
+    pub fn plane_bytes_mut(&mut self, index: usize) -> &mut [u8] {
+        if index >= self.planes() {
+            panic!("out of bounds");
+        }
+
+        unsafe { slice::from_raw_parts_mut((*self.as_mut_ptr()).data[index], self.plane_size()) }
+    }
+
+    #[inline]
+    #[deprecated(
+        since = "4.4.0",
+        note = "sized from the line size, which is larger than the samples when padded; \
+        consider switching to plane_bytes()"
+    )]
     /// Get audio data.
     pub fn data(&self, index: usize) -> &[u8] {
         if index >= self.planes() {
@@ -201,6 +338,11 @@ impl Audio {
     }
 
     #[inline]
+    #[deprecated(
+        since = "4.4.0",
+        note = "sized from the line size, which is larger than the samples when padded; \
+        consider switching to plane_bytes_mut()"
+    )]
     /// Like [data()], but the data is mutable.
     ///
     /// [data()]: self::data()
@@ -243,6 +385,15 @@ impl ::std::fmt::Debug for Audio {
     }
 }
 
+impl Audio {
+    /// Create a new reference to the data of the frame, without copying it,
+    /// unlike `clone`.
+    #[inline]
+    pub fn ref_clone(&self) -> Result<Self, Error> {
+        self.0.ref_clone().map(Audio)
+    }
+}
+
 impl Clone for Audio {
     fn clone(&self) -> Self {
         let mut cloned = Audio::new(self.format(), self.samples(), self.channel_layout());
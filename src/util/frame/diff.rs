@@ -0,0 +1,172 @@
+//! Comparison of video frames, e.g. for regression tests of filters and
+//! encoders, or to detect duplicate frames.
+//!
+//! Components are read through `av_read_image_line`, so any software pixel
+//! format is supported, packed or planar, of any depth.
+
+use std::cmp;
+
+use super::Video;
+use ffi::*;
+use libc::c_int;
+use util::format;
+use Error;
+
+/// The differences of a component, in units of its own depth.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct Component {
+    /// The mean absolute difference.
+    pub mae: f64,
+    /// The maximum absolute difference.
+    pub max: u16,
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct Stats {
+    /// The differences per component, in pixel format order (e.g. Y, U, V or
+    /// R, G, B).
+    pub components: Vec<Component>,
+    /// The number of pixels where any component differs by more than the
+    /// threshold, on an 8-bit scale.
+    pub changed: usize,
+}
+
+impl Stats {
+    pub fn is_identical(&self) -> bool {
+        self.components.iter().all(|c| c.max == 0)
+    }
+}
+
+/// Compare two frames of the same format and dimensions.
+pub fn stats(a: &Video, b: &Video, threshold: u8) -> Result<Stats, Error> {
+    let (components, map) = compare(a, b)?;
+
+    Ok(Stats {
+        components,
+        changed: map.iter().filter(|&&value| value > threshold).count(),
+    })
+}
+
+/// Get a `GRAY8` frame of the largest component difference of each pixel,
+/// on an 8-bit scale.
+pub fn heatmap(a: &Video, b: &Video) -> Result<Video, Error> {
+    let (_, map) = compare(a, b)?;
+    let width = a.width() as usize;
+    let mut frame = Video::new(format::Pixel::GRAY8, a.width(), a.height());
+    let stride = frame.stride(0);
+
+    for (row, line) in map.chunks(width).enumerate() {
+        frame.data_mut(0)[row * stride..row * stride + width].copy_from_slice(line);
+    }
+
+    Ok(frame)
+}
+
+fn compare(a: &Video, b: &Video) -> Result<(Vec<Component>, Vec<u8>), Error> {
+    if a.format() != b.format()
+        || a.width() != b.width()
+        || a.height() != b.height()
+        || a.width() == 0
+        || a.height() == 0
+    {
+        return Err(Error::InvalidData);
+    }
+
+    let descriptor = a.format().descriptor().ok_or(Error::InvalidData)?;
+
+    unsafe {
+        let desc = descriptor.as_ptr();
+
+        if (*desc).flags & AV_PIX_FMT_FLAG_HWACCEL as u64 != 0 {
+            return Err(Error::InvalidData);
+        }
+
+        let palette = ((*desc).flags & AV_PIX_FMT_FLAG_PAL as u64 != 0) as c_int;
+        let rgb = (*desc).flags & AV_PIX_FMT_FLAG_RGB as u64 != 0;
+
+        let width = a.width() as usize;
+        let height = a.height() as usize;
+
+        let mut components = Vec::new();
+        let mut map = vec![0u8; width * height];
+        let mut line_a = vec![0u16; width];
+        let mut line_b = vec![0u16; width];
+
+        for index in 0..(*desc).nb_components as usize {
+            let depth = (*desc).comp[index].depth as u32;
+            let (shift_w, shift_h) = if !rgb && (index == 1 || index == 2) {
+                (
+                    (*desc).log2_chroma_w as usize,
+                    (*desc).log2_chroma_h as usize,
+                )
+            } else {
+                (0, 0)
+            };
+
+            let comp_width = (width + (1 << shift_w) - 1) >> shift_w;
+            let comp_height = (height + (1 << shift_h) - 1) >> shift_h;
+
+            let mut sum = 0u64;
+            let mut max = 0u16;
+
+            for y in 0..comp_height {
+                read_line(a, desc, index, y, palette, &mut line_a[..comp_width]);
+                read_line(b, desc, index, y, palette, &mut line_b[..comp_width]);
+
+                for x in 0..comp_width {
+                    let delta = (i32::from(line_a[x]) - i32::from(line_b[x])).abs() as u16;
+
+                    sum += u64::from(delta);
+                    max = cmp::max(max, delta);
+
+                    if delta == 0 {
+                        continue;
+                    }
+
+                    let scaled = if depth > 8 {
+                        delta >> (depth - 8)
+                    } else {
+                        cmp::min(u32::from(delta) << (8 - depth), 255) as u16
+                    } as u8;
+
+                    for py in (y << shift_h)..cmp::min((y + 1) << shift_h, height) {
+                        for px in (x << shift_w)..cmp::min((x + 1) << shift_w, width) {
+                            let value = &mut map[py * width + px];
+                            *value = cmp::max(*value, scaled);
+                        }
+                    }
+                }
+            }
+
+            let count = (comp_width * comp_height) as f64;
+
+            components.push(Component {
+                mae: if count > 0.0 { sum as f64 / count } else { 0.0 },
+                max,
+            });
+        }
+
+        Ok((components, map))
+    }
+}
+
+unsafe fn read_line(
+    frame: &Video,
+    desc: *const AVPixFmtDescriptor,
+    index: usize,
+    y: usize,
+    palette: c_int,
+    line: &mut [u16],
+) {
+    av_read_image_line(
+        line.as_mut_ptr(),
+        (*frame.as_ptr()).data.as_ptr() as *mut *const u8,
+        (*frame.as_ptr()).linesize.as_ptr(),
+        desc,
+        0,
+        y as c_int,
+        index as c_int,
+        line.len() as c_int,
+        palette,
+    );
+}
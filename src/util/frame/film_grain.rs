@@ -0,0 +1,291 @@
+//! Film grain synthesis parameters, as exported by AV1 decoders.
+//!
+//! Decoders apply the grain by default; with
+//! `decoder::Decoder::apply_film_grain(false)` they output the clean frames
+//! and attach these parameters instead, leaving the synthesis to the player
+//! or to a later stage of the pipeline.
+
+use std::cmp;
+use std::mem;
+
+use self::raw::*;
+use super::{side_data, Frame, SideData};
+use libc::{c_int, ENOMEM};
+use Error;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Type {
+    None,
+    AV1,
+    #[cfg(feature = "ffmpeg_5_0")]
+    H274,
+}
+
+impl From<c_int> for Type {
+    fn from(value: c_int) -> Self {
+        match value {
+            AV_FILM_GRAIN_PARAMS_AV1 => Type::AV1,
+            #[cfg(feature = "ffmpeg_5_0")]
+            AV_FILM_GRAIN_PARAMS_H274 => Type::H274,
+            _ => Type::None,
+        }
+    }
+}
+
+impl Into<c_int> for Type {
+    fn into(self) -> c_int {
+        match self {
+            Type::None => AV_FILM_GRAIN_PARAMS_NONE,
+            Type::AV1 => AV_FILM_GRAIN_PARAMS_AV1,
+            #[cfg(feature = "ffmpeg_5_0")]
+            Type::H274 => AV_FILM_GRAIN_PARAMS_H274,
+        }
+    }
+}
+
+/// The AV1 film grain parameters, as defined in section 6.8.20 of the
+/// specification.
+///
+/// Scaling points are `(value, scaling)` pairs, at most 14 for luma and 10
+/// for each chroma plane.
+#[derive(Eq, PartialEq, Clone, Debug, Default)]
+pub struct Aom {
+    pub y_points: Vec<(u8, u8)>,
+    pub chroma_scaling_from_luma: bool,
+    pub uv_points: [Vec<(u8, u8)>; 2],
+    pub scaling_shift: i32,
+
+    pub ar_coeff_lag: i32,
+    pub ar_coeffs_y: [i8; 24],
+    pub ar_coeffs_uv: [[i8; 25]; 2],
+    pub ar_coeff_shift: i32,
+    pub grain_scale_shift: i32,
+
+    pub uv_mult: [i32; 2],
+    pub uv_mult_luma: [i32; 2],
+    pub uv_offset: [i32; 2],
+
+    pub overlap: bool,
+    pub limit_output_range: bool,
+}
+
+impl Aom {
+    unsafe fn new(params: &AVFilmGrainAOMParams) -> Self {
+        fn points(points: &[[u8; 2]], count: c_int) -> Vec<(u8, u8)> {
+            points[..cmp::min(cmp::max(count, 0) as usize, points.len())]
+                .iter()
+                .map(|p| (p[0], p[1]))
+                .collect()
+        }
+
+        Aom {
+            y_points: points(&params.y_points, params.num_y_points),
+            chroma_scaling_from_luma: params.chroma_scaling_from_luma != 0,
+            uv_points: [
+                points(&params.uv_points[0], params.num_uv_points[0]),
+                points(&params.uv_points[1], params.num_uv_points[1]),
+            ],
+            scaling_shift: params.scaling_shift,
+
+            ar_coeff_lag: params.ar_coeff_lag,
+            ar_coeffs_y: params.ar_coeffs_y,
+            ar_coeffs_uv: params.ar_coeffs_uv,
+            ar_coeff_shift: params.ar_coeff_shift,
+            grain_scale_shift: params.grain_scale_shift,
+
+            uv_mult: params.uv_mult,
+            uv_mult_luma: params.uv_mult_luma,
+            uv_offset: params.uv_offset,
+
+            overlap: params.overlap_flag != 0,
+            limit_output_range: params.limit_output_range != 0,
+        }
+    }
+
+    unsafe fn write(&self, params: &mut AVFilmGrainAOMParams) {
+        fn points(target: &mut [[u8; 2]], source: &[(u8, u8)]) -> c_int {
+            let count = cmp::min(target.len(), source.len());
+
+            for (target, &(value, scaling)) in target.iter_mut().zip(&source[..count]) {
+                *target = [value, scaling];
+            }
+
+            count as c_int
+        }
+
+        params.num_y_points = points(&mut params.y_points, &self.y_points);
+        params.chroma_scaling_from_luma = self.chroma_scaling_from_luma as c_int;
+        params.num_uv_points[0] = points(&mut params.uv_points[0], &self.uv_points[0]);
+        params.num_uv_points[1] = points(&mut params.uv_points[1], &self.uv_points[1]);
+        params.scaling_shift = self.scaling_shift;
+
+        params.ar_coeff_lag = self.ar_coeff_lag;
+        params.ar_coeffs_y = self.ar_coeffs_y;
+        params.ar_coeffs_uv = self.ar_coeffs_uv;
+        params.ar_coeff_shift = self.ar_coeff_shift;
+        params.grain_scale_shift = self.grain_scale_shift;
+
+        params.uv_mult = self.uv_mult;
+        params.uv_mult_luma = self.uv_mult_luma;
+        params.uv_offset = self.uv_offset;
+
+        params.overlap_flag = self.overlap as c_int;
+        params.limit_output_range = self.limit_output_range as c_int;
+    }
+}
+
+/// The film grain parameters of a frame.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Params {
+    pub kind: Type,
+    /// The seed of the pseudo-random generator.
+    pub seed: u64,
+    /// The codec-specific parameters, meaningful when `kind` is `AV1`.
+    pub aom: Aom,
+}
+
+impl Params {
+    pub fn av1(seed: u64, aom: Aom) -> Self {
+        Params {
+            kind: Type::AV1,
+            seed,
+            aom,
+        }
+    }
+
+    /// Parse `FILM_GRAIN_PARAMS` frame side data.
+    pub fn from_side_data(data: &SideData) -> Option<Self> {
+        unsafe {
+            let data = data.data();
+
+            if data.len() < mem::size_of::<AVFilmGrainParams>() {
+                return None;
+            }
+
+            let ptr = data.as_ptr() as *const AVFilmGrainParams;
+
+            Some(Params {
+                kind: Type::from((*ptr).type_),
+                seed: (*ptr).seed,
+                aom: Aom::new(&(*ptr).aom),
+            })
+        }
+    }
+
+    unsafe fn write(&self, ptr: *mut AVFilmGrainParams) {
+        (*ptr).type_ = self.kind.into();
+        (*ptr).seed = self.seed;
+        self.aom.write(&mut (*ptr).aom);
+    }
+}
+
+impl Frame {
+    /// Get the film grain parameters, exported by decoders that were told not
+    /// to apply the grain themselves.
+    pub fn film_grain(&self) -> Option<Params> {
+        self.side_data(side_data::Type::FILM_GRAIN_PARAMS)
+            .and_then(|data| Params::from_side_data(&data))
+    }
+
+    /// Attach film grain parameters, replacing any existing ones, e.g. to
+    /// pass them through filters or on to an encoder.
+    pub fn set_film_grain(&mut self, params: &Params) -> Result<(), Error> {
+        unsafe {
+            self.remove_side_data(side_data::Type::FILM_GRAIN_PARAMS);
+
+            let ptr = av_film_grain_params_create_side_data(self.as_mut_ptr());
+
+            if ptr.is_null() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            params.write(ptr);
+
+            Ok(())
+        }
+    }
+}
+
+// libavutil/film_grain_params.h, which is not part of the generated
+// bindings. Only the AV1 member of the codec union is declared, the
+// structure is never allocated on this side.
+mod raw {
+    use ffi::AVFrame;
+    use libc::{c_int, size_t};
+
+    pub const AV_FILM_GRAIN_PARAMS_AV1: c_int = 1;
+    #[cfg(feature = "ffmpeg_5_0")]
+    pub const AV_FILM_GRAIN_PARAMS_H274: c_int = 2;
+
+    #[repr(C)]
+    pub struct AVFilmGrainAOMParams {
+        pub num_y_points: c_int,
+        pub y_points: [[u8; 2]; 14],
+        pub chroma_scaling_from_luma: c_int,
+        pub num_uv_points: [c_int; 2],
+        pub uv_points: [[[u8; 2]; 10]; 2],
+        pub scaling_shift: c_int,
+        pub ar_coeff_lag: c_int,
+        pub ar_coeffs_y: [i8; 24],
+        pub ar_coeffs_uv: [[i8; 25]; 2],
+        pub ar_coeff_shift: c_int,
+        pub grain_scale_shift: c_int,
+        pub uv_mult: [c_int; 2],
+        pub uv_mult_luma: [c_int; 2],
+        pub uv_offset: [c_int; 2],
+        pub overlap_flag: c_int,
+        pub limit_output_range: c_int,
+    }
+
+    #[repr(C)]
+    pub struct AVFilmGrainParams {
+        pub type_: c_int,
+        pub seed: u64,
+        // width, height, subsampling, color properties and bit depths
+        #[cfg(feature = "ffmpeg_7_0")]
+        pub properties: [c_int; 10],
+        pub aom: AVFilmGrainAOMParams,
+    }
+
+    extern "C" {
+        pub fn av_film_grain_params_alloc(size: *mut size_t) -> *mut AVFilmGrainParams;
+        pub fn av_film_grain_params_create_side_data(frame: *mut AVFrame)
+            -> *mut AVFilmGrainParams;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ffi::av_free;
+    use frame;
+
+    #[test]
+    fn test_round_trip() {
+        unsafe {
+            let mut size = 0;
+            let ptr = av_film_grain_params_alloc(&mut size);
+            assert!(!ptr.is_null());
+            av_free(ptr as *mut _);
+
+            assert!(size >= mem::size_of::<AVFilmGrainParams>());
+        }
+
+        let mut aom = Aom::default();
+        aom.y_points = vec![(0, 20), (128, 40), (255, 20)];
+        aom.uv_points[1] = vec![(64, 10)];
+        aom.scaling_shift = 11;
+        aom.ar_coeff_lag = 3;
+        aom.ar_coeffs_y[5] = -7;
+        aom.ar_coeffs_uv[1][24] = 3;
+        aom.uv_mult = [128, 64];
+        aom.overlap = true;
+
+        let params = Params::av1(42, aom);
+        let mut frame = frame::Video::empty();
+
+        assert_eq!(frame.film_grain(), None);
+        frame.set_film_grain(&params).unwrap();
+        assert_eq!(frame.film_grain(), Some(params));
+    }
+}
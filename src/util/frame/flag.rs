@@ -4,5 +4,23 @@ use libc::c_int;
 bitflags! {
     pub struct Flags: c_int {
         const CORRUPT = AV_FRAME_FLAG_CORRUPT;
+        const DISCARD = AV_FRAME_FLAG_DISCARD;
+        #[cfg(feature = "ffmpeg_6_1")]
+        const KEY = AV_FRAME_FLAG_KEY;
+        #[cfg(feature = "ffmpeg_6_1")]
+        const INTERLACED = AV_FRAME_FLAG_INTERLACED;
+        #[cfg(feature = "ffmpeg_6_1")]
+        const TOP_FIELD_FIRST = AV_FRAME_FLAG_TOP_FIELD_FIRST;
+    }
+}
+
+bitflags! {
+    /// The errors met by the decoder in the frame, set along with
+    /// `Flags::CORRUPT` or on their own for concealed errors.
+    pub struct DecodeErrors: c_int {
+        const INVALID_BITSTREAM  = FF_DECODE_ERROR_INVALID_BITSTREAM;
+        const MISSING_REFERENCE  = FF_DECODE_ERROR_MISSING_REFERENCE;
+        const CONCEALMENT_ACTIVE = FF_DECODE_ERROR_CONCEALMENT_ACTIVE;
+        const DECODE_SLICES      = FF_DECODE_ERROR_DECODE_SLICES;
     }
 }
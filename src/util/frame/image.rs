@@ -0,0 +1,97 @@
+//! Conversions between video frames and the images of the `image` crate.
+
+use image::{DynamicImage, ImageBuffer};
+
+use super::Video;
+use ffi::*;
+#[cfg(not(feature = "software-scaling"))]
+use libc::ENOSYS;
+use util::format::Pixel;
+use Error;
+
+impl Video {
+    /// Convert the frame to an image, grayscale, RGB or RGBA depending on
+    /// its format, converting it first with swscale if needed.
+    ///
+    /// Without the `software-scaling` feature only `GRAY8`, `RGB24` and
+    /// `RGBA` frames can be converted, the others failing with `ENOSYS`.
+    pub fn to_image(&self) -> Result<DynamicImage, Error> {
+        match self.format() {
+            Pixel::GRAY8 => Ok(DynamicImage::ImageLuma8(self.pack()?)),
+            Pixel::RGB24 => Ok(DynamicImage::ImageRgb8(self.pack()?)),
+            Pixel::RGBA => Ok(DynamicImage::ImageRgba8(self.pack()?)),
+            format => {
+                let target = if has_alpha(format) {
+                    Pixel::RGBA
+                } else {
+                    Pixel::RGB24
+                };
+
+                self.convert(target)?.to_image()
+            }
+        }
+    }
+
+    /// Create a frame from an image, as GRAY8, RGB24 or RGBA, the other
+    /// kinds of images being converted to RGBA.
+    pub fn from_image(image: &DynamicImage) -> Result<Self, Error> {
+        match *image {
+            DynamicImage::ImageLuma8(ref buffer) => Video::from_slice(
+                Pixel::GRAY8,
+                buffer.width(),
+                buffer.height(),
+                buffer,
+                buffer.width() as usize,
+            ),
+
+            DynamicImage::ImageRgb8(ref buffer) => Video::from_slice(
+                Pixel::RGB24,
+                buffer.width(),
+                buffer.height(),
+                buffer,
+                buffer.width() as usize * 3,
+            ),
+
+            DynamicImage::ImageRgba8(ref buffer) => Video::from_slice(
+                Pixel::RGBA,
+                buffer.width(),
+                buffer.height(),
+                buffer,
+                buffer.width() as usize * 4,
+            ),
+
+            ref other => Video::from_image(&DynamicImage::ImageRgba8(other.to_rgba8())),
+        }
+    }
+
+    fn pack<P: ::image::Pixel<Subpixel = u8> + 'static>(
+        &self,
+    ) -> Result<ImageBuffer<P, Vec<u8>>, Error> {
+        let mut data = vec![0; self.required_buffer_size(1)?];
+        self.copy_to_buffer(&mut data, 1)?;
+
+        ImageBuffer::from_raw(self.width(), self.height(), data).ok_or(Error::InvalidData)
+    }
+
+    #[cfg(feature = "software-scaling")]
+    fn convert(&self, format: Pixel) -> Result<Video, Error> {
+        let mut converted = Video::empty();
+        self.converter(format)?.run(self, &mut converted)?;
+
+        Ok(converted)
+    }
+
+    #[cfg(not(feature = "software-scaling"))]
+    fn convert(&self, _format: Pixel) -> Result<Video, Error> {
+        Err(Error::Other { errno: ENOSYS })
+    }
+}
+
+fn has_alpha(format: Pixel) -> bool {
+    match format.descriptor() {
+        Some(descriptor) => unsafe {
+            (*descriptor.as_ptr()).flags & AV_PIX_FMT_FLAG_ALPHA as u64 != 0
+        },
+        None => false,
+    }
+}
@@ -9,11 +9,27 @@ pub mod audio;
 pub use self::audio::Audio;
 
 pub mod flag;
-pub use self::flag::Flags;
+pub use self::flag::{DecodeErrors, Flags};
+
+pub mod diff;
+
+#[cfg(feature = "ffmpeg_4_4")]
+pub mod film_grain;
+
+#[cfg(feature = "image")]
+mod image;
+
+#[cfg(feature = "ndarray")]
+mod ndarray;
+
+pub mod samples;
+pub use self::samples::{Channel, Interleaved, SamplesIter};
+
+use std::mem;
 
 use ffi::*;
-use libc::c_int;
-use {Dictionary, DictionaryRef};
+use libc::{c_int, ENOMEM};
+use {Buffer, Dictionary, DictionaryRef, Error};
 
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
 pub struct Packet {
@@ -59,6 +75,27 @@ impl Frame {
         self.ptr
     }
 
+    /// Take the ownership of a frame, e.g. from `into_raw`.
+    #[inline(always)]
+    pub unsafe fn from_raw(ptr: *mut AVFrame) -> Self {
+        Frame { ptr, _own: true }
+    }
+
+    /// Give up the ownership of the frame, to be released with
+    /// `av_frame_free`. The frame is returned back if it is not owned, e.g.
+    /// created with `wrap`.
+    #[inline(always)]
+    pub fn into_raw(self) -> Result<*mut AVFrame, Self> {
+        if !self._own {
+            return Err(self);
+        }
+
+        let ptr = self.ptr;
+        mem::forget(self);
+
+        Ok(ptr)
+    }
+
     #[inline(always)]
     pub unsafe fn is_empty(&self) -> bool {
         (*self.as_ptr()).data[0].is_null()
@@ -66,6 +103,55 @@ impl Frame {
 }
 
 impl Frame {
+    /// Create a new reference to the data of the frame, without copying it,
+    /// see `make_writable` to modify it afterwards.
+    #[inline]
+    pub fn ref_clone(&self) -> Result<Self, Error> {
+        unsafe {
+            let ptr = av_frame_clone(self.as_ptr());
+
+            if ptr.is_null() {
+                Err(Error::Other { errno: ENOMEM })
+            } else {
+                Ok(Frame { ptr, _own: true })
+            }
+        }
+    }
+
+    /// Whether the data of the frame can be modified in place, i.e. it is
+    /// not shared with other references, e.g. kept by the decoder.
+    #[inline]
+    pub fn is_writable(&self) -> bool {
+        unsafe { av_frame_is_writable(self.as_ptr() as *mut _) != 0 }
+    }
+
+    /// Get the number of references to the data of the frame, 0 if it does
+    /// not own it.
+    #[inline]
+    pub fn ref_count(&self) -> usize {
+        unsafe {
+            let buf = (*self.as_ptr()).buf[0];
+
+            if buf.is_null() {
+                0
+            } else {
+                av_buffer_get_ref_count(buf) as usize
+            }
+        }
+    }
+
+    /// Copy the data of the frame if it is shared with other references,
+    /// so it can be modified in place.
+    #[inline]
+    pub fn make_writable(&mut self) -> Result<(), Error> {
+        unsafe {
+            match av_frame_make_writable(self.as_mut_ptr()) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
     #[inline]
     pub fn is_key(&self) -> bool {
         unsafe { (*self.as_ptr()).key_frame == 1 }
@@ -80,11 +166,17 @@ impl Frame {
     pub fn packet(&self) -> Packet {
         unsafe {
             Packet {
-                duration: av_frame_get_pkt_duration(self.as_ptr()) as i64,
-                position: av_frame_get_pkt_pos(self.as_ptr()) as i64,
-                size: av_frame_get_pkt_size(self.as_ptr()) as usize,
+                #[cfg(not(feature = "ffmpeg_6_0"))]
+                duration: (*self.as_ptr()).pkt_duration as i64,
+                #[cfg(feature = "ffmpeg_6_0")]
+                duration: (*self.as_ptr()).duration as i64,
+                position: (*self.as_ptr()).pkt_pos as i64,
+                size: (*self.as_ptr()).pkt_size as usize,
 
+                #[cfg(not(feature = "ffmpeg_5_0"))]
                 pts: (*self.as_ptr()).pkt_pts,
+                #[cfg(feature = "ffmpeg_5_0")]
+                pts: (*self.as_ptr()).pts,
                 dts: (*self.as_ptr()).pkt_dts,
             }
         }
@@ -110,7 +202,7 @@ impl Frame {
     #[inline]
     pub fn timestamp(&self) -> Option<i64> {
         unsafe {
-            match av_frame_get_best_effort_timestamp(self.as_ptr()) {
+            match (*self.as_ptr()).best_effort_timestamp {
                 AV_NOPTS_VALUE => None,
                 t => Some(t as i64),
             }
@@ -127,15 +219,58 @@ impl Frame {
         unsafe { Flags::from_bits_truncate((*self.as_ptr()).flags) }
     }
 
+    #[inline]
+    pub fn set_flags(&mut self, value: Flags) {
+        unsafe {
+            (*self.as_mut_ptr()).flags = value.bits();
+        }
+    }
+
+    /// Get the errors met when decoding the frame, which may be usable
+    /// nonetheless if they were concealed.
+    #[inline]
+    pub fn decode_errors(&self) -> DecodeErrors {
+        unsafe { DecodeErrors::from_bits_truncate((*self.as_ptr()).decode_error_flags) }
+    }
+
+    /// Whether the decoder met any error in the frame, e.g. to skip damaged
+    /// frames for thumbnails.
+    #[inline]
+    pub fn is_damaged(&self) -> bool {
+        self.is_corrupt() || !self.decode_errors().is_empty()
+    }
+
+    /// Get a reference to the buffer holding a plane, `None` if the frame
+    /// does not own its data.
+    #[inline]
+    pub fn buffer(&self, plane: usize) -> Option<Buffer> {
+        unsafe {
+            let ptr = av_frame_get_plane_buffer(self.as_ptr() as *mut _, plane as c_int);
+
+            if ptr.is_null() {
+                return None;
+            }
+
+            let ptr = av_buffer_ref(ptr);
+
+            if ptr.is_null() {
+                None
+            } else {
+                Some(Buffer::wrap(ptr))
+            }
+        }
+    }
+
     #[inline]
     pub fn metadata(&self) -> DictionaryRef {
-        unsafe { DictionaryRef::wrap(av_frame_get_metadata(self.as_ptr())) }
+        unsafe { DictionaryRef::wrap((*self.as_ptr()).metadata) }
     }
 
     #[inline]
     pub fn set_metadata(&mut self, value: Dictionary) {
         unsafe {
-            av_frame_set_metadata(self.as_mut_ptr(), value.disown());
+            av_dict_free(&mut (*self.as_mut_ptr()).metadata);
+            (*self.as_mut_ptr()).metadata = value.disown();
         }
     }
 
@@ -0,0 +1,129 @@
+//! Views of the planes and samples of frames as arrays of the `ndarray`
+//! crate, without copying them.
+
+use std::mem;
+
+use ndarray::{ArrayView2, ArrayView3, ShapeBuilder};
+
+use super::audio::Sample;
+use super::{Audio, Video};
+use ffi::*;
+use Error;
+
+impl Video {
+    /// Get a view of a plane, with the rows, the pixels and the bytes of the
+    /// pixels as axes, e.g. `(height, width, 3)` for RGB24 or
+    /// `(height, width, 1)` for the luma plane of YUV formats.
+    ///
+    /// Formats whose pixels are not made of whole bytes, i.e. bitstream
+    /// formats such as MONOBLACK, and packed formats with subsampled chroma
+    /// such as YUYV422 have no such view and fail with `Error::InvalidData`.
+    pub fn plane_ndarray(&self, index: usize) -> Result<ArrayView3<u8>, Error> {
+        if index >= self.planes() {
+            return Err(Error::InvalidData);
+        }
+
+        let stride = self.stride(index);
+        let width = self.plane_width(index) as usize;
+        let height = self.plane_height(index) as usize;
+        let step = self.step(index)?;
+
+        assert!(width * step <= stride);
+
+        unsafe {
+            Ok(ArrayView3::from_shape_ptr(
+                (height, width, step).strides((stride, step, 1)),
+                (*self.as_ptr()).data[index],
+            ))
+        }
+    }
+
+    // get the size in bytes of a pixel in a plane
+    fn step(&self, index: usize) -> Result<usize, Error> {
+        let descriptor = self.format().descriptor().ok_or(Error::InvalidData)?;
+
+        unsafe {
+            let descriptor = &*descriptor.as_ptr();
+
+            if descriptor.flags & AV_PIX_FMT_FLAG_BITSTREAM as u64 != 0 {
+                return Err(Error::InvalidData);
+            }
+
+            let components = descriptor.comp[..descriptor.nb_components as usize]
+                .iter()
+                .enumerate()
+                .filter(|&(_, component)| component.plane as usize == index)
+                .collect::<Vec<_>>();
+
+            // luma and subsampled chroma in the same plane, e.g. YUYV422
+            let rgb = descriptor.flags & AV_PIX_FMT_FLAG_RGB as u64 != 0;
+            let luma = components.iter().any(|&(i, _)| i == 0);
+            let chroma = components.iter().any(|&(i, _)| i == 1 || i == 2);
+
+            if !rgb && luma && chroma && descriptor.log2_chroma_w > 0 {
+                return Err(Error::InvalidData);
+            }
+
+            // the palette of paletted formats is not a plane of pixels
+            components
+                .iter()
+                .map(|&(_, component)| component.step as usize)
+                .max()
+                .filter(|&step| step > 0)
+                .ok_or(Error::InvalidData)
+        }
+    }
+}
+
+impl Audio {
+    /// Get a view of the samples, with the samples and the channels as axes.
+    ///
+    /// Planar frames are only viewed when their planes are evenly spaced in
+    /// memory, which frames from decoders usually are not, `plane` gives
+    /// the samples of each channel instead.
+    pub fn samples_ndarray<T: Sample>(&self) -> Option<ArrayView2<T>> {
+        if !<T as Sample>::is_valid(self.format(), self.channels()) {
+            panic!("unsupported type");
+        }
+
+        let channels = self.channels() as usize;
+        let samples = self.samples();
+
+        unsafe {
+            let data = (*self.as_ptr()).extended_data;
+
+            if data.is_null() || channels == 0 {
+                return None;
+            }
+
+            if self.is_packed() {
+                return Some(ArrayView2::from_shape_ptr(
+                    (samples, channels).strides((channels, 1)),
+                    *data as *const T,
+                ));
+            }
+
+            let size = mem::size_of::<T>() as isize;
+            let distance = if channels > 1 {
+                (*data.offset(1) as isize) - (*data as isize)
+            } else {
+                samples as isize * size
+            };
+
+            if distance <= 0 || distance % size != 0 {
+                return None;
+            }
+
+            for channel in 1..channels as isize {
+                if *data.offset(channel) as isize - *data as isize != distance * channel {
+                    return None;
+                }
+            }
+
+            Some(ArrayView2::from_shape_ptr(
+                (samples, channels).strides((1, (distance / size) as usize)),
+                *data as *const T,
+            ))
+        }
+    }
+}
@@ -0,0 +1,342 @@
+//! Layout independent access to the samples of audio frames, the same code
+//! handling planar and packed formats.
+
+use std::marker::PhantomData;
+use std::mem;
+
+use super::audio::Sample;
+use super::Audio;
+use ffi::*;
+use util::format;
+use {ChannelLayout, Error};
+
+/// The samples of all the channels at a point in time, a sample for mono
+/// frames or a tuple of samples, e.g. `(f32, f32)` for stereo frames.
+pub unsafe trait Interleaved: Sized {
+    type Sample: Sample + Copy;
+
+    const CHANNELS: usize;
+
+    /// Read the samples at `index` from the samples of each channel, which
+    /// are `step` samples apart.
+    unsafe fn gather(channels: &[*const Self::Sample], index: usize, step: usize) -> Self;
+}
+
+macro_rules! interleaved {
+    ($t:ty) => {
+        unsafe impl Interleaved for $t {
+            type Sample = $t;
+
+            const CHANNELS: usize = 1;
+
+            #[inline(always)]
+            unsafe fn gather(channels: &[*const $t], index: usize, step: usize) -> Self {
+                *channels[0].add(index * step)
+            }
+        }
+
+        interleaved!(@tuple $t; 2; 0, 1);
+        interleaved!(@tuple $t; 3; 0, 1, 2);
+        interleaved!(@tuple $t; 4; 0, 1, 2, 3);
+        interleaved!(@tuple $t; 5; 0, 1, 2, 3, 4);
+        interleaved!(@tuple $t; 6; 0, 1, 2, 3, 4, 5);
+        interleaved!(@tuple $t; 7; 0, 1, 2, 3, 4, 5, 6);
+    };
+
+    (@tuple $t:ty; $n:expr; $($i:tt),+) => {
+        unsafe impl Interleaved for ($(interleaved!(@sample $i $t)),+) {
+            type Sample = $t;
+
+            const CHANNELS: usize = $n;
+
+            #[inline(always)]
+            unsafe fn gather(channels: &[*const $t], index: usize, step: usize) -> Self {
+                ($(*channels[$i].add(index * step)),+)
+            }
+        }
+    };
+
+    (@sample $i:tt $t:ty) => {
+        $t
+    };
+}
+
+interleaved!(u8);
+interleaved!(i16);
+interleaved!(i32);
+interleaved!(f32);
+interleaved!(f64);
+
+/// An iterator over the samples of all the channels of an audio frame.
+pub struct SamplesIter<'a, T: Interleaved> {
+    channels: Vec<*const T::Sample>,
+    step: usize,
+    index: usize,
+    samples: usize,
+
+    _marker: PhantomData<&'a Audio>,
+}
+
+impl<'a, T: Interleaved> Iterator for SamplesIter<'a, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.index >= self.samples {
+            return None;
+        }
+
+        let item = unsafe { T::gather(&self.channels, self.index, self.step) };
+        self.index += 1;
+
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.samples - self.index;
+
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T: Interleaved> ExactSizeIterator for SamplesIter<'a, T> {}
+
+/// The samples of a channel of an audio frame.
+pub struct Channel<'a, T: 'a> {
+    data: &'a [T],
+    step: usize,
+    index: usize,
+    samples: usize,
+}
+
+impl<'a, T: Copy> Channel<'a, T> {
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.samples
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.samples == 0
+    }
+
+    /// Get the sample at `index`, regardless of the progress of iteration.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<T> {
+        if index < self.samples {
+            Some(self.data[index * self.step])
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, T: Copy> Iterator for Channel<'a, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        let item = self.get(self.index)?;
+        self.index += 1;
+
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.samples - self.index;
+
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T: Copy> ExactSizeIterator for Channel<'a, T> {}
+
+impl Audio {
+    /// Iterate over the samples of all the channels, e.g. as `(f32, f32)`
+    /// for stereo frames, whether the format is planar or packed.
+    pub fn samples_iter<T: Interleaved>(&self) -> SamplesIter<T> {
+        if T::CHANNELS != self.channels() as usize || !self.is_scalar::<T::Sample>() {
+            panic!("unsupported type");
+        }
+
+        let (channels, step) = if self.planes() == 0 {
+            (Vec::new(), 1)
+        } else if self.is_planar() {
+            (
+                (0..T::CHANNELS)
+                    .map(|index| self.plane::<T::Sample>(index).as_ptr())
+                    .collect(),
+                1,
+            )
+        } else {
+            let data = self.plane::<T::Sample>(0).as_ptr();
+
+            (
+                (0..T::CHANNELS)
+                    .map(|index| unsafe { data.add(index) })
+                    .collect(),
+                T::CHANNELS,
+            )
+        };
+
+        SamplesIter {
+            step,
+            index: 0,
+            samples: if channels.is_empty() {
+                0
+            } else {
+                self.samples()
+            },
+            channels,
+
+            _marker: PhantomData,
+        }
+    }
+
+    /// Get the samples of a channel, whether the format is planar or packed.
+    pub fn channel<T: Sample + Copy>(&self, index: usize) -> Channel<T> {
+        if index >= self.channels() as usize {
+            panic!("out of bounds");
+        }
+
+        if !self.is_scalar::<T>() {
+            panic!("unsupported type");
+        }
+
+        let samples = self.samples();
+
+        if self.planes() == 0 {
+            Channel {
+                data: &[],
+                step: 1,
+                index: 0,
+                samples: 0,
+            }
+        } else if self.is_planar() {
+            Channel {
+                data: self.plane(index),
+                step: 1,
+                index: 0,
+                samples,
+            }
+        } else {
+            Channel {
+                data: &self.plane(0)[index..],
+                step: self.channels() as usize,
+                index: 0,
+                samples,
+            }
+        }
+    }
+
+    /// Create a frame from the interleaved samples of all the channels, in
+    /// a planar or packed `format` whose samples are `T`, e.g. `f32` for
+    /// `F32(Planar)`.
+    pub fn from_interleaved<T: Sample + Copy>(
+        data: &[T],
+        format: format::Sample,
+        layout: ChannelLayout,
+        rate: u32,
+    ) -> Result<Self, Error> {
+        let channels = layout.channels() as usize;
+
+        if channels == 0 || data.len() % channels != 0 {
+            return Err(Error::InvalidData);
+        }
+
+        let mut frame = Audio::with_samples::<T>(format, data.len() / channels, layout, rate)?;
+
+        if frame.is_packed() {
+            frame.plane_mut::<T>(0).copy_from_slice(data);
+        } else {
+            for channel in 0..channels {
+                let plane = frame.plane_mut::<T>(channel);
+
+                for (sample, value) in plane
+                    .iter_mut()
+                    .zip(data[channel..].iter().step_by(channels))
+                {
+                    *sample = *value;
+                }
+            }
+        }
+
+        Ok(frame)
+    }
+
+    /// Create a frame from the samples of each channel, of the same length,
+    /// in a planar or packed `format` whose samples are `T`.
+    pub fn from_planar<T: Sample + Copy>(
+        planes: &[&[T]],
+        format: format::Sample,
+        layout: ChannelLayout,
+        rate: u32,
+    ) -> Result<Self, Error> {
+        let channels = layout.channels() as usize;
+
+        if channels == 0 || planes.len() != channels {
+            return Err(Error::InvalidData);
+        }
+
+        let samples = planes[0].len();
+
+        if planes.iter().any(|plane| plane.len() != samples) {
+            return Err(Error::InvalidData);
+        }
+
+        let mut frame = Audio::with_samples::<T>(format, samples, layout, rate)?;
+
+        if frame.is_planar() {
+            for (channel, data) in planes.iter().enumerate() {
+                frame.plane_mut::<T>(channel).copy_from_slice(data);
+            }
+        } else {
+            let plane = frame.plane_mut::<T>(0);
+
+            for (channel, data) in planes.iter().enumerate() {
+                for (sample, value) in plane[channel..]
+                    .iter_mut()
+                    .step_by(channels)
+                    .zip(data.iter())
+                {
+                    *sample = *value;
+                }
+            }
+        }
+
+        Ok(frame)
+    }
+
+    // allocate a frame for `samples` samples of `T` per channel
+    fn with_samples<T: Sample>(
+        format: format::Sample,
+        samples: usize,
+        layout: ChannelLayout,
+        rate: u32,
+    ) -> Result<Self, Error> {
+        let mut frame = Audio::empty();
+        frame.set_format(format);
+        frame.set_samples(samples);
+        frame.set_channel_layout(layout);
+        frame.set_rate(rate);
+
+        if !frame.is_scalar::<T>() {
+            return Err(Error::InvalidData);
+        }
+
+        unsafe {
+            match av_frame_get_buffer(frame.as_mut_ptr(), 0) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(frame),
+            }
+        }
+    }
+
+    // whether `T` holds a single sample of the format of the frame
+    fn is_scalar<T: Sample>(&self) -> bool {
+        <T as Sample>::is_valid(self.format(), self.channels())
+            && mem::size_of::<T>() == self.format().bytes()
+    }
+}
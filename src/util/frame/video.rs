@@ -1,15 +1,17 @@
+use std::marker::PhantomData;
 use std::mem;
 use std::ops::{Deref, DerefMut};
+use std::ptr;
 use std::slice;
 
 use super::Frame;
 use color;
 use ffi::*;
-use libc::c_int;
+use libc::{c_int, ENOMEM};
 use picture;
 use util::chroma;
 use util::format;
-use Rational;
+use {Error, Rational};
 
 #[derive(PartialEq, Eq)]
 pub struct Video(Frame);
@@ -117,25 +119,25 @@ impl Video {
 
     #[inline]
     pub fn color_space(&self) -> color::Space {
-        unsafe { color::Space::from(av_frame_get_colorspace(self.as_ptr())) }
+        unsafe { color::Space::from((*self.as_ptr()).colorspace) }
     }
 
     #[inline]
     pub fn set_color_space(&mut self, value: color::Space) {
         unsafe {
-            av_frame_set_colorspace(self.as_mut_ptr(), value.into());
+            (*self.as_mut_ptr()).colorspace = value.into();
         }
     }
 
     #[inline]
     pub fn color_range(&self) -> color::Range {
-        unsafe { color::Range::from(av_frame_get_color_range(self.as_ptr())) }
+        unsafe { color::Range::from((*self.as_ptr()).color_range) }
     }
 
     #[inline]
     pub fn set_color_range(&mut self, value: color::Range) {
         unsafe {
-            av_frame_set_color_range(self.as_mut_ptr(), value.into());
+            (*self.as_mut_ptr()).color_range = value.into();
         }
     }
 
@@ -313,6 +315,168 @@ impl Video {
     }
 }
 
+impl Video {
+    /// Create a frame from a copy of an image, e.g. from another library,
+    /// whose planes are contiguous and whose first plane has rows of
+    /// `stride` bytes, the strides of the other planes being in proportion.
+    pub fn from_slice(
+        format: format::Pixel,
+        width: u32,
+        height: u32,
+        data: &[u8],
+        stride: usize,
+    ) -> Result<Self, Error> {
+        unsafe {
+            let (pointers, linesizes) = fill(format, width, height, data, stride)?;
+            let mut frame = Video::new(format, width, height);
+
+            if frame.is_empty() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            av_image_copy(
+                (*frame.as_mut_ptr()).data.as_mut_ptr(),
+                (*frame.as_mut_ptr()).linesize.as_mut_ptr(),
+                pointers.as_ptr() as *mut *const u8,
+                linesizes.as_ptr(),
+                format.into(),
+                width as c_int,
+                height as c_int,
+            );
+
+            Ok(frame)
+        }
+    }
+
+    /// Get the size of the frame packed in a single buffer, with the rows
+    /// aligned to `align` bytes (1 for no padding).
+    pub fn required_buffer_size(&self, align: usize) -> Result<usize, Error> {
+        unsafe {
+            match av_image_get_buffer_size(
+                self.format().into(),
+                self.width() as c_int,
+                self.height() as c_int,
+                align as c_int,
+            ) {
+                e if e < 0 => Err(Error::from(e)),
+                size => Ok(size as usize),
+            }
+        }
+    }
+
+    /// Copy the frame packed in `buffer`, which must hold at least
+    /// `required_buffer_size(align)` bytes, returning the bytes written.
+    pub fn copy_to_buffer(&self, buffer: &mut [u8], align: usize) -> Result<usize, Error> {
+        if buffer.len() < self.required_buffer_size(align)? {
+            return Err(Error::InvalidData);
+        }
+
+        unsafe {
+            match av_image_copy_to_buffer(
+                buffer.as_mut_ptr(),
+                buffer.len() as c_int,
+                (*self.as_ptr()).data.as_ptr() as *const *const u8,
+                (*self.as_ptr()).linesize.as_ptr(),
+                self.format().into(),
+                self.width() as c_int,
+                self.height() as c_int,
+                align as c_int,
+            ) {
+                e if e < 0 => Err(Error::from(e)),
+                size => Ok(size as usize),
+            }
+        }
+    }
+
+    /// Create a frame referencing an image without copying it, laid out as
+    /// for `from_slice`.
+    ///
+    /// The frame does not own its data, encoders and filters copy it when
+    /// they need to keep it.
+    pub fn borrow(
+        format: format::Pixel,
+        width: u32,
+        height: u32,
+        data: &[u8],
+        stride: usize,
+    ) -> Result<Borrowed, Error> {
+        unsafe {
+            let (pointers, linesizes) = fill(format, width, height, data, stride)?;
+            let mut frame = Video::empty();
+
+            frame.set_format(format);
+            frame.set_width(width);
+            frame.set_height(height);
+
+            for plane in 0..4 {
+                (*frame.as_mut_ptr()).data[plane] = pointers[plane];
+                (*frame.as_mut_ptr()).linesize[plane] = linesizes[plane];
+            }
+
+            Ok(Borrowed {
+                frame,
+                _marker: PhantomData,
+            })
+        }
+    }
+}
+
+// get the planes of an image in `data`
+unsafe fn fill(
+    format: format::Pixel,
+    width: u32,
+    height: u32,
+    data: &[u8],
+    stride: usize,
+) -> Result<([*mut u8; 4], [c_int; 4]), Error> {
+    let mut pointers = [ptr::null_mut(); 4];
+    let mut linesizes = [0; 4];
+
+    match av_image_fill_linesizes(linesizes.as_mut_ptr(), format.into(), width as c_int) {
+        e if e < 0 => return Err(Error::from(e)),
+        _ => (),
+    }
+
+    if linesizes[0] <= 0 || stride < linesizes[0] as usize {
+        return Err(Error::InvalidData);
+    }
+
+    for linesize in &mut linesizes[1..] {
+        *linesize = (*linesize as usize * stride / linesizes[0] as usize) as c_int;
+    }
+
+    linesizes[0] = stride as c_int;
+
+    let size = av_image_fill_pointers(
+        pointers.as_mut_ptr(),
+        format.into(),
+        height as c_int,
+        data.as_ptr() as *mut u8,
+        linesizes.as_ptr(),
+    );
+
+    match size {
+        e if e < 0 => Err(Error::from(e)),
+        size if size as usize > data.len() => Err(Error::InvalidData),
+        _ => Ok((pointers, linesizes)),
+    }
+}
+
+/// A video frame referencing borrowed data.
+pub struct Borrowed<'a> {
+    frame: Video,
+    _marker: PhantomData<&'a [u8]>,
+}
+
+impl<'a> Deref for Borrowed<'a> {
+    type Target = Video;
+
+    #[inline]
+    fn deref(&self) -> &Video {
+        &self.frame
+    }
+}
+
 impl Deref for Video {
     type Target = Frame;
 
@@ -329,6 +493,15 @@ impl DerefMut for Video {
     }
 }
 
+impl Video {
+    /// Create a new reference to the data of the frame, without copying it,
+    /// unlike `clone`.
+    #[inline]
+    pub fn ref_clone(&self) -> Result<Self, Error> {
+        self.0.ref_clone().map(Video)
+    }
+}
+
 impl Clone for Video {
     #[inline]
     fn clone(&self) -> Self {
@@ -0,0 +1,267 @@
+//! Hardware device and frame pool contexts, shared with filters and codecs.
+
+use std::ffi::CString;
+use std::ptr;
+
+use ffi::AVHWDeviceType::*;
+use ffi::*;
+use libc::{c_int, ENOMEM};
+use util::format;
+use {Dictionary, Error};
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum DeviceType {
+    None,
+    VDPAU,
+    CUDA,
+    VAAPI,
+    DXVA2,
+    QSV,
+    VideoToolbox,
+    D3D11VA,
+    #[cfg(feature = "ffmpeg_4_0")]
+    DRM,
+    #[cfg(feature = "ffmpeg_4_0")]
+    OpenCL,
+    #[cfg(feature = "ffmpeg_4_0")]
+    MediaCodec,
+    #[cfg(feature = "ffmpeg_4_3")]
+    Vulkan,
+}
+
+impl From<AVHWDeviceType> for DeviceType {
+    fn from(value: AVHWDeviceType) -> Self {
+        match value {
+            AV_HWDEVICE_TYPE_NONE => DeviceType::None,
+            AV_HWDEVICE_TYPE_VDPAU => DeviceType::VDPAU,
+            AV_HWDEVICE_TYPE_CUDA => DeviceType::CUDA,
+            AV_HWDEVICE_TYPE_VAAPI => DeviceType::VAAPI,
+            AV_HWDEVICE_TYPE_DXVA2 => DeviceType::DXVA2,
+            AV_HWDEVICE_TYPE_QSV => DeviceType::QSV,
+            AV_HWDEVICE_TYPE_VIDEOTOOLBOX => DeviceType::VideoToolbox,
+            AV_HWDEVICE_TYPE_D3D11VA => DeviceType::D3D11VA,
+            #[cfg(feature = "ffmpeg_4_0")]
+            AV_HWDEVICE_TYPE_DRM => DeviceType::DRM,
+            #[cfg(feature = "ffmpeg_4_0")]
+            AV_HWDEVICE_TYPE_OPENCL => DeviceType::OpenCL,
+            #[cfg(feature = "ffmpeg_4_0")]
+            AV_HWDEVICE_TYPE_MEDIACODEC => DeviceType::MediaCodec,
+            #[cfg(feature = "ffmpeg_4_3")]
+            AV_HWDEVICE_TYPE_VULKAN => DeviceType::Vulkan,
+
+            #[allow(unreachable_patterns)]
+            _ => DeviceType::None,
+        }
+    }
+}
+
+impl Into<AVHWDeviceType> for DeviceType {
+    fn into(self) -> AVHWDeviceType {
+        match self {
+            DeviceType::None => AV_HWDEVICE_TYPE_NONE,
+            DeviceType::VDPAU => AV_HWDEVICE_TYPE_VDPAU,
+            DeviceType::CUDA => AV_HWDEVICE_TYPE_CUDA,
+            DeviceType::VAAPI => AV_HWDEVICE_TYPE_VAAPI,
+            DeviceType::DXVA2 => AV_HWDEVICE_TYPE_DXVA2,
+            DeviceType::QSV => AV_HWDEVICE_TYPE_QSV,
+            DeviceType::VideoToolbox => AV_HWDEVICE_TYPE_VIDEOTOOLBOX,
+            DeviceType::D3D11VA => AV_HWDEVICE_TYPE_D3D11VA,
+            #[cfg(feature = "ffmpeg_4_0")]
+            DeviceType::DRM => AV_HWDEVICE_TYPE_DRM,
+            #[cfg(feature = "ffmpeg_4_0")]
+            DeviceType::OpenCL => AV_HWDEVICE_TYPE_OPENCL,
+            #[cfg(feature = "ffmpeg_4_0")]
+            DeviceType::MediaCodec => AV_HWDEVICE_TYPE_MEDIACODEC,
+            #[cfg(feature = "ffmpeg_4_3")]
+            DeviceType::Vulkan => AV_HWDEVICE_TYPE_VULKAN,
+        }
+    }
+}
+
+/// A reference to a hardware device context.
+pub struct Device {
+    ptr: *mut AVBufferRef,
+}
+
+unsafe impl Send for Device {}
+unsafe impl Sync for Device {}
+
+impl Device {
+    /// Wrap a reference to an `AVHWDeviceContext` buffer, taking ownership.
+    pub unsafe fn wrap(ptr: *mut AVBufferRef) -> Self {
+        Device { ptr }
+    }
+
+    pub unsafe fn as_ptr(&self) -> *const AVBufferRef {
+        self.ptr as *const _
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVBufferRef {
+        self.ptr
+    }
+}
+
+impl Device {
+    /// Open a device, `device` selects it in a type-specific way (e.g. a DRM
+    /// render node for VAAPI, a GPU index for CUDA), `None` picks the default.
+    pub fn create(kind: DeviceType, device: Option<&str>) -> Result<Self, Error> {
+        Device::create_with(kind, device, Dictionary::new())
+    }
+
+    pub fn create_with(
+        kind: DeviceType,
+        device: Option<&str>,
+        options: Dictionary,
+    ) -> Result<Self, Error> {
+        unsafe {
+            let device = match device {
+                Some(device) => Some(CString::new(device).map_err(|_| Error::InvalidData)?),
+                None => None,
+            };
+            let mut ptr = ptr::null_mut();
+            let opts = options.disown();
+            let res = av_hwdevice_ctx_create(
+                &mut ptr,
+                kind.into(),
+                device.as_ref().map_or(ptr::null(), |d| d.as_ptr()),
+                opts,
+                0,
+            );
+
+            Dictionary::own(opts);
+
+            match res {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(Device::wrap(ptr)),
+            }
+        }
+    }
+
+    pub fn kind(&self) -> DeviceType {
+        unsafe { DeviceType::from((*((*self.as_ptr()).data as *const AVHWDeviceContext)).type_) }
+    }
+}
+
+impl Clone for Device {
+    fn clone(&self) -> Self {
+        unsafe {
+            let ptr = av_buffer_ref(self.ptr);
+
+            if ptr.is_null() {
+                panic!("out of memory");
+            }
+
+            Device::wrap(ptr)
+        }
+    }
+}
+
+impl Drop for Device {
+    fn drop(&mut self) {
+        unsafe {
+            av_buffer_unref(&mut self.ptr);
+        }
+    }
+}
+
+/// A reference to a pool of hardware frames, as given to buffer sources
+/// receiving hardware frames.
+pub struct Frames {
+    ptr: *mut AVBufferRef,
+}
+
+unsafe impl Send for Frames {}
+unsafe impl Sync for Frames {}
+
+impl Frames {
+    /// Wrap a reference to an `AVHWFramesContext` buffer, taking ownership.
+    pub unsafe fn wrap(ptr: *mut AVBufferRef) -> Self {
+        Frames { ptr }
+    }
+
+    pub unsafe fn as_ptr(&self) -> *const AVBufferRef {
+        self.ptr as *const _
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVBufferRef {
+        self.ptr
+    }
+}
+
+impl Frames {
+    /// Create a pool of `format` (e.g. `Pixel::VAAPI`) frames backed by
+    /// `software` images of the given dimensions, `size` is the number of
+    /// preallocated frames, zero for a dynamic pool.
+    pub fn new(
+        device: &Device,
+        format: format::Pixel,
+        software: format::Pixel,
+        width: u32,
+        height: u32,
+        size: usize,
+    ) -> Result<Self, Error> {
+        unsafe {
+            let ptr = av_hwframe_ctx_alloc(device.ptr);
+
+            if ptr.is_null() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            let mut frames = Frames::wrap(ptr);
+            let context = (*ptr).data as *mut AVHWFramesContext;
+
+            (*context).format = format.into();
+            (*context).sw_format = software.into();
+            (*context).width = width as c_int;
+            (*context).height = height as c_int;
+            (*context).initial_pool_size = size as c_int;
+
+            match av_hwframe_ctx_init(frames.as_mut_ptr()) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(frames),
+            }
+        }
+    }
+
+    pub fn format(&self) -> format::Pixel {
+        unsafe { format::Pixel::from((*self.context()).format) }
+    }
+
+    pub fn software_format(&self) -> format::Pixel {
+        unsafe { format::Pixel::from((*self.context()).sw_format) }
+    }
+
+    pub fn width(&self) -> u32 {
+        unsafe { (*self.context()).width as u32 }
+    }
+
+    pub fn height(&self) -> u32 {
+        unsafe { (*self.context()).height as u32 }
+    }
+
+    unsafe fn context(&self) -> *const AVHWFramesContext {
+        (*self.as_ptr()).data as *const _
+    }
+}
+
+impl Clone for Frames {
+    fn clone(&self) -> Self {
+        unsafe {
+            let ptr = av_buffer_ref(self.ptr);
+
+            if ptr.is_null() {
+                panic!("out of memory");
+            }
+
+            Frames::wrap(ptr)
+        }
+    }
+}
+
+impl Drop for Frames {
+    fn drop(&mut self) {
+        unsafe {
+            av_buffer_unref(&mut self.ptr);
+        }
+    }
+}
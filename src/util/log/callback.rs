@@ -0,0 +1,214 @@
+use std::cell::{Cell, RefCell};
+use std::convert::TryFrom;
+use std::ffi::CStr;
+use std::panic;
+use std::process;
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Once, PoisonError, RwLock};
+
+use super::Level;
+use ffi::*;
+use libc::{c_char, c_int, c_void};
+
+#[cfg(all(target_arch = "x86_64", not(target_os = "windows")))]
+type VaList = *mut __va_list_tag;
+#[cfg(not(all(target_arch = "x86_64", not(target_os = "windows"))))]
+type VaList = va_list;
+
+type Handler = Box<dyn Fn(&Message) + Send + Sync>;
+
+static INIT: Once = Once::new();
+static mut HANDLER: *const RwLock<Option<Handler>> = ptr::null();
+static CAPTURES: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static TAG: RefCell<Option<String>> = RefCell::new(None);
+    static PRINT_PREFIX: Cell<c_int> = Cell::new(1);
+    static CAPTURE: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// A log message emitted by FFmpeg.
+#[derive(Clone, Debug)]
+pub struct Message {
+    /// The level of the message, `None` if it is not one of the standard levels.
+    pub level: Option<Level>,
+    /// The tag of the thread the message was logged from, see `scope`.
+    pub tag: Option<String>,
+    /// The formatted line, including FFmpeg's `[component @ 0x...]` prefix.
+    pub line: String,
+}
+
+fn handler() -> &'static RwLock<Option<Handler>> {
+    unsafe {
+        INIT.call_once(|| {
+            HANDLER = Box::into_raw(Box::new(RwLock::new(None)));
+        });
+
+        &*HANDLER
+    }
+}
+
+// called with the handler locked for writing, so that concurrent calls
+// don't leave the wrong callback installed
+fn install(handler: &Option<Handler>) {
+    let f = if handler.is_some() || CAPTURES.load(Ordering::SeqCst) > 0 {
+        callback
+    } else {
+        av_log_default_callback
+    };
+
+    unsafe {
+        av_log_set_callback(Some(f));
+    }
+}
+
+unsafe extern "C" fn callback(avcl: *mut c_void, level: c_int, fmt: *const c_char, vl: VaList) {
+    let capturing = CAPTURE.try_with(|c| c.borrow().is_some()).unwrap_or(false);
+
+    if !capturing {
+        // only installed to capture the messages of another thread
+        if !handler().read().map(|h| h.is_some()).unwrap_or(false) {
+            av_log_default_callback(avcl, level, fmt, vl);
+            return;
+        }
+
+        if level > av_log_get_level() {
+            return;
+        }
+    }
+
+    let mut buffer = [0 as c_char; 1024];
+    let mut print_prefix = PRINT_PREFIX.try_with(|p| p.get()).unwrap_or(1);
+
+    av_log_format_line2(
+        avcl,
+        level,
+        fmt,
+        vl,
+        buffer.as_mut_ptr(),
+        buffer.len() as c_int,
+        &mut print_prefix,
+    );
+
+    let _ = PRINT_PREFIX.try_with(|p| p.set(print_prefix));
+
+    if capturing {
+        let line = CStr::from_ptr(buffer.as_ptr()).to_string_lossy();
+        let _ = CAPTURE.try_with(|c| {
+            if let Some(ref mut captured) = *c.borrow_mut() {
+                captured.push_str(&line);
+            }
+        });
+
+        return;
+    }
+
+    let message = Message {
+        level: Level::try_from(level).ok(),
+        tag: TAG.try_with(|t| t.borrow().clone()).unwrap_or(None),
+        line: CStr::from_ptr(buffer.as_ptr())
+            .to_string_lossy()
+            .into_owned(),
+    };
+
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        if let Ok(guard) = handler().read() {
+            // the handler may have been reset in the meantime
+            if let Some(ref handler) = *guard {
+                handler(&message);
+            }
+        }
+    }));
+
+    if result.is_err() {
+        process::abort();
+    }
+}
+
+/// Route all FFmpeg log messages to `f` instead of standard error.
+///
+/// Messages above the current log level are dropped before reaching `f`.
+/// The handler must not call back into this module.
+pub fn set_callback<F>(f: F)
+where
+    F: Fn(&Message) + Send + Sync + 'static,
+{
+    if let Ok(mut guard) = handler().write() {
+        *guard = Some(Box::new(f));
+        install(&guard);
+    }
+}
+
+/// Restore FFmpeg's default logging to standard error.
+pub fn reset_callback() {
+    if let Ok(mut guard) = handler().write() {
+        *guard = None;
+        install(&guard);
+    }
+}
+
+/// Run `f`, returning the messages it logged from the current thread instead
+/// of passing them on, whatever the log level, e.g. to get the output of
+/// `av_dump_format`.
+pub fn capture<T, F: FnOnce() -> T>(f: F) -> (T, String) {
+    {
+        let guard = handler().write().unwrap_or_else(PoisonError::into_inner);
+        CAPTURES.fetch_add(1, Ordering::SeqCst);
+        install(&guard);
+    }
+
+    let previous = CAPTURE.with(|c| c.replace(Some(String::new())));
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(f));
+    let captured = CAPTURE.with(|c| c.replace(previous)).unwrap_or_default();
+
+    {
+        let guard = handler().write().unwrap_or_else(PoisonError::into_inner);
+        CAPTURES.fetch_sub(1, Ordering::SeqCst);
+        install(&guard);
+    }
+
+    match result {
+        Ok(value) => (value, captured),
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+
+/// Get the tag attached to messages logged from the current thread.
+pub fn tag() -> Option<String> {
+    TAG.with(|t| t.borrow().clone())
+}
+
+/// Attach `tag` to every message logged from the current thread until the
+/// returned guard is dropped, at which point the previous tag is restored.
+///
+/// Messages logged from threads spawned by FFmpeg itself (e.g. frame
+/// threading in decoders) are not tagged.
+pub fn scope<S: Into<String>>(tag: S) -> Scope {
+    let previous = TAG.with(|t| t.replace(Some(tag.into())));
+
+    Scope {
+        previous,
+        _marker: ptr::null(),
+    }
+}
+
+/// Run `f` with `tag` attached to the messages logged from the current thread.
+pub fn with_tag<S: Into<String>, T, F: FnOnce() -> T>(tag: S, f: F) -> T {
+    let _scope = scope(tag);
+    f()
+}
+
+/// Guard returned by `scope`.
+pub struct Scope {
+    previous: Option<String>,
+    // the tag is thread-local, the guard must be dropped on the same thread
+    _marker: *const (),
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        let _ = TAG.try_with(|t| t.replace(previous));
+    }
+}
@@ -4,6 +4,9 @@ pub use self::level::Level;
 pub mod flag;
 pub use self::flag::Flags;
 
+pub mod callback;
+pub use self::callback::{capture, scope, set_callback, with_tag, Message};
+
 use ffi::*;
 use std::convert::TryInto;
 
@@ -1,12 +1,15 @@
 //! Utility library for mutiple usages.
 #[macro_use]
 pub mod dictionary;
+pub mod audio_fifo;
+pub mod buffer;
 pub mod channel_layout;
 pub mod chroma;
 pub mod color;
 pub mod error;
 pub mod format;
 pub mod frame;
+pub mod hwcontext;
 pub mod interrupt;
 pub mod log;
 pub mod mathematics;
@@ -16,6 +19,10 @@ pub mod picture;
 pub mod range;
 pub mod rational;
 pub mod time;
+pub mod timecode;
+
+#[cfg(feature = "serde")]
+mod serde;
 
 use std::ffi::CStr;
 use std::str::from_utf8_unchecked;
@@ -0,0 +1,247 @@
+use std::ffi::CStr;
+use std::marker::PhantomData;
+use std::ptr;
+use std::str::from_utf8_unchecked;
+
+use super::Type;
+use ffi::AVOptionType::*;
+use ffi::*;
+use libc::{c_char, c_int, c_void};
+use Rational;
+
+bitflags! {
+    pub struct Flags: c_int {
+        const ENCODING_PARAM  = AV_OPT_FLAG_ENCODING_PARAM;
+        const DECODING_PARAM  = AV_OPT_FLAG_DECODING_PARAM;
+        const AUDIO_PARAM     = AV_OPT_FLAG_AUDIO_PARAM;
+        const VIDEO_PARAM     = AV_OPT_FLAG_VIDEO_PARAM;
+        const SUBTITLE_PARAM  = AV_OPT_FLAG_SUBTITLE_PARAM;
+        const EXPORT          = AV_OPT_FLAG_EXPORT;
+        const READONLY        = AV_OPT_FLAG_READONLY;
+        const BSF_PARAM       = AV_OPT_FLAG_BSF_PARAM;
+        const RUNTIME_PARAM   = AV_OPT_FLAG_RUNTIME_PARAM;
+        const FILTERING_PARAM = AV_OPT_FLAG_FILTERING_PARAM;
+        #[cfg(feature = "ffmpeg_4_3")]
+        const DEPRECATED      = AV_OPT_FLAG_DEPRECATED;
+        #[cfg(feature = "ffmpeg_4_3")]
+        const CHILD_CONSTS    = AV_OPT_FLAG_CHILD_CONSTS;
+    }
+}
+
+/// The default value of an option, in the representation of its type.
+#[derive(PartialEq, Clone, Debug)]
+pub enum Value {
+    None,
+    Int(i64),
+    Double(f64),
+    Rational(Rational),
+    String(&'static str),
+}
+
+/// The description of an option of an object, from the static table of its
+/// class.
+#[derive(Copy, Clone)]
+pub struct Descriptor {
+    ptr: *const AVOption,
+    class: *const AVClass,
+}
+
+unsafe impl Send for Descriptor {}
+unsafe impl Sync for Descriptor {}
+
+impl Descriptor {
+    pub unsafe fn wrap(ptr: *const AVOption, class: *const AVClass) -> Self {
+        Descriptor { ptr, class }
+    }
+
+    pub unsafe fn as_ptr(&self) -> *const AVOption {
+        self.ptr
+    }
+}
+
+impl Descriptor {
+    pub fn name(&self) -> &'static str {
+        unsafe { from_utf8_unchecked(CStr::from_ptr((*self.as_ptr()).name).to_bytes()) }
+    }
+
+    pub fn help(&self) -> Option<&'static str> {
+        unsafe { to_str((*self.as_ptr()).help) }
+    }
+
+    pub fn kind(&self) -> Type {
+        unsafe { Type::from((*self.as_ptr()).type_) }
+    }
+
+    pub fn flags(&self) -> Flags {
+        unsafe { Flags::from_bits_truncate((*self.as_ptr()).flags) }
+    }
+
+    pub fn min(&self) -> f64 {
+        unsafe { (*self.as_ptr()).min }
+    }
+
+    pub fn max(&self) -> f64 {
+        unsafe { (*self.as_ptr()).max }
+    }
+
+    /// Get the unit, shared by the option and its named constants.
+    pub fn unit(&self) -> Option<&'static str> {
+        unsafe { to_str((*self.as_ptr()).unit) }
+    }
+
+    pub fn default(&self) -> Value {
+        unsafe {
+            let default = &(*self.as_ptr()).default_val;
+
+            // the default of an array is its string representation, given
+            // with the bounds of the array
+            #[cfg(feature = "ffmpeg_7_0")]
+            {
+                // flagged types are not variants of `AVOptionType`
+                let kind = ptr::read(ptr::addr_of!((*self.as_ptr()).type_) as *const c_int);
+
+                if kind & AV_OPT_TYPE_FLAG_ARRAY as c_int != 0 {
+                    return if default.arr.is_null() {
+                        Value::None
+                    } else {
+                        to_str((*default.arr).def).map_or(Value::None, Value::String)
+                    };
+                }
+            }
+
+            match (*self.as_ptr()).type_ {
+                AV_OPT_TYPE_DOUBLE | AV_OPT_TYPE_FLOAT => Value::Double(default.dbl),
+                AV_OPT_TYPE_RATIONAL => Value::Rational(Rational::from(default.q)),
+
+                AV_OPT_TYPE_STRING
+                | AV_OPT_TYPE_IMAGE_SIZE
+                | AV_OPT_TYPE_VIDEO_RATE
+                | AV_OPT_TYPE_COLOR
+                | AV_OPT_TYPE_DICT => to_str(default.str_).map_or(Value::None, Value::String),
+
+                #[cfg(feature = "ffmpeg_5_1")]
+                AV_OPT_TYPE_CHLAYOUT => to_str(default.str_).map_or(Value::None, Value::String),
+
+                AV_OPT_TYPE_BINARY => Value::None,
+
+                _ => Value::Int(default.i64_),
+            }
+        }
+    }
+
+    /// Iterate over the named constants of the option, e.g. the presets of
+    /// an encoder.
+    pub fn constants(&self) -> Constants {
+        Constants {
+            unit: unsafe { (*self.as_ptr()).unit },
+            class: self.class,
+            cur: ptr::null(),
+        }
+    }
+}
+
+pub struct Constants {
+    unit: *const c_char,
+    class: *const AVClass,
+    cur: *const AVOption,
+}
+
+impl Iterator for Constants {
+    type Item = Descriptor;
+
+    fn next(&mut self) -> Option<Descriptor> {
+        if self.unit.is_null() {
+            return None;
+        }
+
+        unsafe {
+            loop {
+                // av_opt_next() expects an object, whose first field is its
+                // class
+                self.cur = av_opt_next(&self.class as *const _ as *const c_void, self.cur);
+
+                if self.cur.is_null() {
+                    return None;
+                }
+
+                if (*self.cur).type_ == AV_OPT_TYPE_CONST
+                    && !(*self.cur).unit.is_null()
+                    && CStr::from_ptr((*self.cur).unit) == CStr::from_ptr(self.unit)
+                {
+                    return Some(Descriptor::wrap(self.cur, self.class));
+                }
+            }
+        }
+    }
+}
+
+/// An iterator over the options of an object and of its children, e.g. the
+/// private options of a codec, without the named constants.
+pub struct Options<'a> {
+    objects: Vec<*mut c_void>,
+    cur: *const AVOption,
+
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> Options<'a> {
+    pub unsafe fn new(object: *const c_void) -> Self {
+        Options {
+            objects: if object.is_null() {
+                Vec::new()
+            } else {
+                vec![object as *mut _]
+            },
+            cur: ptr::null(),
+
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a> Iterator for Options<'a> {
+    type Item = Descriptor;
+
+    fn next(&mut self) -> Option<Descriptor> {
+        unsafe {
+            while let Some(&object) = self.objects.first() {
+                self.cur = av_opt_next(object, self.cur);
+
+                if self.cur.is_null() {
+                    self.objects.remove(0);
+
+                    let mut child = ptr::null_mut();
+
+                    loop {
+                        child = av_opt_child_next(object, child);
+
+                        if child.is_null() {
+                            break;
+                        }
+
+                        self.objects.push(child);
+                    }
+
+                    continue;
+                }
+
+                if (*self.cur).type_ != AV_OPT_TYPE_CONST {
+                    return Some(Descriptor::wrap(
+                        self.cur,
+                        *(object as *const *const AVClass),
+                    ));
+                }
+            }
+
+            None
+        }
+    }
+}
+
+unsafe fn to_str(ptr: *const c_char) -> Option<&'static str> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(from_utf8_unchecked(CStr::from_ptr(ptr).to_bytes()))
+    }
+}
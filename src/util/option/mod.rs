@@ -1,6 +1,9 @@
 mod traits;
 pub use self::traits::{Gettable, Iterable, Settable, Target};
 
+mod descriptor;
+pub use self::descriptor::{Constants, Descriptor, Flags, Options, Value};
+
 use ffi::AVOptionType::*;
 use ffi::*;
 
@@ -26,6 +29,8 @@ pub enum Type {
     ChannelLayout,
     c_ulong,
     bool,
+    #[cfg(feature = "ffmpeg_7_0")]
+    c_uint,
 }
 
 impl From<AVOptionType> for Type {
@@ -50,7 +55,15 @@ impl From<AVOptionType> for Type {
             AV_OPT_TYPE_VIDEO_RATE => Type::VideoRate,
             AV_OPT_TYPE_DURATION => Type::Duration,
             AV_OPT_TYPE_COLOR => Type::Color,
+            #[cfg(not(feature = "ffmpeg_7_0"))]
             AV_OPT_TYPE_CHANNEL_LAYOUT => Type::ChannelLayout,
+            #[cfg(feature = "ffmpeg_5_1")]
+            AV_OPT_TYPE_CHLAYOUT => Type::ChannelLayout,
+            #[cfg(feature = "ffmpeg_7_0")]
+            AV_OPT_TYPE_UINT => Type::c_uint,
+
+            #[allow(unreachable_patterns)]
+            _ => Type::Binary,
         }
     }
 }
@@ -77,7 +90,12 @@ impl Into<AVOptionType> for Type {
             Type::VideoRate => AV_OPT_TYPE_VIDEO_RATE,
             Type::Duration => AV_OPT_TYPE_DURATION,
             Type::Color => AV_OPT_TYPE_COLOR,
+            #[cfg(not(feature = "ffmpeg_7_0"))]
             Type::ChannelLayout => AV_OPT_TYPE_CHANNEL_LAYOUT,
+            #[cfg(feature = "ffmpeg_7_0")]
+            Type::ChannelLayout => AV_OPT_TYPE_CHLAYOUT,
+            #[cfg(feature = "ffmpeg_7_0")]
+            Type::c_uint => AV_OPT_TYPE_UINT,
         }
     }
 }
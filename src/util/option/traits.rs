@@ -1,13 +1,18 @@
 //! NOTE: this will be much better once specialization comes
 
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::mem;
+use std::ptr;
 
 use ffi::*;
-use libc::{c_int, c_void};
+use libc::{c_char, c_int, c_void};
+#[cfg(feature = "ffmpeg_7_0")]
+use util::channel_layout::Layout;
 use util::format;
 use {ChannelLayout, Error, Rational};
 
+use super::Options;
+
 macro_rules! check {
     ($expr:expr) => {
         match $expr {
@@ -104,6 +109,33 @@ pub trait Settable: Target {
         }
     }
 
+    fn set_video_rate<T: Into<Rational>>(&mut self, name: &str, value: T) -> Result<(), Error> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+
+            check!(av_opt_set_video_rate(
+                self.as_mut_ptr(),
+                name.as_ptr(),
+                value.into().into(),
+                AV_OPT_SEARCH_CHILDREN
+            ))
+        }
+    }
+
+    fn set_binary(&mut self, name: &str, value: &[u8]) -> Result<(), Error> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+
+            check!(av_opt_set_bin(
+                self.as_mut_ptr(),
+                name.as_ptr(),
+                value.as_ptr(),
+                value.len() as c_int,
+                AV_OPT_SEARCH_CHILDREN
+            ))
+        }
+    }
+
     fn set_pixel_format(&mut self, name: &str, format: format::Pixel) -> Result<(), Error> {
         unsafe {
             let name = CString::new(name).unwrap();
@@ -130,6 +162,25 @@ pub trait Settable: Target {
         }
     }
 
+    /// Set options from `key=value` pairs separated by `:`, as returned by
+    /// `serialize`, returning the number of options set.
+    fn set_options(&mut self, options: &str) -> Result<usize, Error> {
+        unsafe {
+            let options = CString::new(options).map_err(|_| Error::InvalidData)?;
+
+            match av_set_options_string(
+                self.as_mut_ptr(),
+                options.as_ptr(),
+                b"=\0".as_ptr() as *const c_char,
+                b":\0".as_ptr() as *const c_char,
+            ) {
+                e if e < 0 => Err(Error::from(e)),
+                count => Ok(count as usize),
+            }
+        }
+    }
+
+    #[cfg(not(feature = "ffmpeg_7_0"))]
     fn set_channel_layout(&mut self, name: &str, layout: ChannelLayout) -> Result<(), Error> {
         unsafe {
             let name = CString::new(name).unwrap();
@@ -142,8 +193,251 @@ pub trait Settable: Target {
             ))
         }
     }
+
+    #[cfg(feature = "ffmpeg_7_0")]
+    fn set_channel_layout(&mut self, name: &str, layout: ChannelLayout) -> Result<(), Error> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let layout = Layout::native(layout);
+
+            check!(av_opt_set_chlayout(
+                self.as_mut_ptr(),
+                name.as_ptr(),
+                layout.as_ptr(),
+                AV_OPT_SEARCH_CHILDREN
+            ))
+        }
+    }
 }
 
-pub trait Gettable: Target {}
+pub trait Gettable: Target {
+    /// Serialize the options of the object and of its children as
+    /// `key=value` pairs separated by `:`, as accepted by `set_options`,
+    /// optionally leaving out the options with their default value.
+    fn serialize(&self, skip_defaults: bool) -> Result<String, Error> {
+        let flags = if skip_defaults {
+            AV_OPT_SERIALIZE_SKIP_DEFAULTS
+        } else {
+            0
+        };
+
+        let mut pairs = Vec::new();
+        let mut objects = vec![self.as_ptr() as *mut c_void];
+
+        unsafe {
+            while let Some(object) = objects.pop() {
+                let mut buffer = ptr::null_mut();
+
+                check!(av_opt_serialize(
+                    object,
+                    0,
+                    flags as c_int,
+                    &mut buffer,
+                    b'=' as c_char,
+                    b':' as c_char
+                ))?;
+
+                if !buffer.is_null() {
+                    let serialized = CStr::from_ptr(buffer).to_string_lossy().into_owned();
+                    av_free(buffer as *mut c_void);
+
+                    if !serialized.is_empty() {
+                        pairs.push(serialized);
+                    }
+                }
+
+                let mut child = ptr::null_mut();
+
+                loop {
+                    child = av_opt_child_next(object, child);
 
-pub trait Iterable: Target {}
+                    if child.is_null() {
+                        break;
+                    }
+
+                    objects.push(child);
+                }
+            }
+        }
+
+        Ok(pairs.join(":"))
+    }
+
+    /// Get the value of any option as a string, as accepted by `set_str`.
+    fn get_str(&self, name: &str) -> Result<String, Error> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let mut value: *mut u8 = ptr::null_mut();
+
+            check!(av_opt_get(
+                self.as_ptr() as *mut _,
+                name.as_ptr(),
+                AV_OPT_SEARCH_CHILDREN,
+                &mut value
+            ))?;
+
+            if value.is_null() {
+                return Ok(String::new());
+            }
+
+            let string = CStr::from_ptr(value as *const c_char)
+                .to_string_lossy()
+                .into_owned();
+            av_free(value as *mut c_void);
+
+            Ok(string)
+        }
+    }
+
+    fn get_int(&self, name: &str) -> Result<i64, Error> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let mut value = 0;
+
+            check!(av_opt_get_int(
+                self.as_ptr() as *mut _,
+                name.as_ptr(),
+                AV_OPT_SEARCH_CHILDREN,
+                &mut value
+            ))
+            .map(|_| value)
+        }
+    }
+
+    fn get_double(&self, name: &str) -> Result<f64, Error> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let mut value = 0.0;
+
+            check!(av_opt_get_double(
+                self.as_ptr() as *mut _,
+                name.as_ptr(),
+                AV_OPT_SEARCH_CHILDREN,
+                &mut value
+            ))
+            .map(|_| value)
+        }
+    }
+
+    fn get_rational(&self, name: &str) -> Result<Rational, Error> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let mut value = AVRational { num: 0, den: 1 };
+
+            check!(av_opt_get_q(
+                self.as_ptr() as *mut _,
+                name.as_ptr(),
+                AV_OPT_SEARCH_CHILDREN,
+                &mut value
+            ))
+            .map(|_| Rational::from(value))
+        }
+    }
+
+    fn get_image_size(&self, name: &str) -> Result<(u32, u32), Error> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let mut w = 0;
+            let mut h = 0;
+
+            check!(av_opt_get_image_size(
+                self.as_ptr() as *mut _,
+                name.as_ptr(),
+                AV_OPT_SEARCH_CHILDREN,
+                &mut w,
+                &mut h
+            ))
+            .map(|_| (w as u32, h as u32))
+        }
+    }
+
+    fn get_video_rate(&self, name: &str) -> Result<Rational, Error> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let mut value = AVRational { num: 0, den: 1 };
+
+            check!(av_opt_get_video_rate(
+                self.as_ptr() as *mut _,
+                name.as_ptr(),
+                AV_OPT_SEARCH_CHILDREN,
+                &mut value
+            ))
+            .map(|_| Rational::from(value))
+        }
+    }
+
+    fn get_pixel_format(&self, name: &str) -> Result<format::Pixel, Error> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let mut value = AVPixelFormat::AV_PIX_FMT_NONE;
+
+            check!(av_opt_get_pixel_fmt(
+                self.as_ptr() as *mut _,
+                name.as_ptr(),
+                AV_OPT_SEARCH_CHILDREN,
+                &mut value
+            ))
+            .map(|_| format::Pixel::from(value))
+        }
+    }
+
+    fn get_sample_format(&self, name: &str) -> Result<format::Sample, Error> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let mut value = AVSampleFormat::AV_SAMPLE_FMT_NONE;
+
+            check!(av_opt_get_sample_fmt(
+                self.as_ptr() as *mut _,
+                name.as_ptr(),
+                AV_OPT_SEARCH_CHILDREN,
+                &mut value
+            ))
+            .map(|_| format::Sample::from(value))
+        }
+    }
+
+    #[cfg(not(feature = "ffmpeg_7_0"))]
+    fn get_channel_layout(&self, name: &str) -> Result<ChannelLayout, Error> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let mut value = 0;
+
+            check!(av_opt_get_channel_layout(
+                self.as_ptr() as *mut _,
+                name.as_ptr(),
+                AV_OPT_SEARCH_CHILDREN,
+                &mut value
+            ))
+            .map(|_| ChannelLayout::from_bits_truncate(value as u64))
+        }
+    }
+
+    /// Get a channel layout option, empty unless the layout is native.
+    #[cfg(feature = "ffmpeg_7_0")]
+    fn get_channel_layout(&self, name: &str) -> Result<ChannelLayout, Error> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let mut value: AVChannelLayout = mem::zeroed();
+
+            check!(av_opt_get_chlayout(
+                self.as_ptr() as *mut _,
+                name.as_ptr(),
+                AV_OPT_SEARCH_CHILDREN,
+                &mut value
+            ))
+            .map(|_| {
+                Layout::own(value)
+                    .mask()
+                    .unwrap_or_else(ChannelLayout::empty)
+            })
+        }
+    }
+}
+
+pub trait Iterable: Target {
+    /// Iterate over the options of the object and of its children, e.g. the
+    /// private options of a codec or muxer.
+    fn options(&self) -> Options {
+        unsafe { Options::new(self.as_ptr()) }
+    }
+}
@@ -0,0 +1,74 @@
+//! Serialization of the values found in media information, as ffprobe
+//! writes them, e.g. rationals as `"1/25"` and formats by name.
+
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use util::format;
+use {media, ChannelLayout, DictionaryRef, Rational};
+
+impl Serialize for Rational {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{}/{}", self.numerator(), self.denominator()))
+    }
+}
+
+impl<'a> Serialize for DictionaryRef<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+
+        for (key, value) in self.iter() {
+            map.serialize_entry(key, value)?;
+        }
+
+        map.end()
+    }
+}
+
+impl<'a> Serialize for ::DictionaryMut<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+impl<'a> Serialize for ::Dictionary<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (***self).serialize(serializer)
+    }
+}
+
+impl Serialize for media::Type {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match *self {
+            media::Type::Unknown => "unknown",
+            media::Type::Video => "video",
+            media::Type::Audio => "audio",
+            media::Type::Data => "data",
+            media::Type::Subtitle => "subtitle",
+            media::Type::Attachment => "attachment",
+        })
+    }
+}
+
+impl Serialize for format::Pixel {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.descriptor() {
+            Some(descriptor) => serializer.serialize_str(descriptor.name()),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+impl Serialize for format::Sample {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            format::Sample::None => serializer.serialize_none(),
+            format => serializer.serialize_str(format.name()),
+        }
+    }
+}
+
+impl Serialize for ChannelLayout {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.describe())
+    }
+}
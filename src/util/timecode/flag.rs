@@ -0,0 +1,10 @@
+use ffi::AVTimecodeFlag::*;
+use libc::c_int;
+
+bitflags! {
+    pub struct Flags: c_int {
+        const DROP_FRAME     = AV_TIMECODE_FLAG_DROPFRAME as c_int;
+        const MAX_24_HOURS   = AV_TIMECODE_FLAG_24HOURSMAX as c_int;
+        const ALLOW_NEGATIVE = AV_TIMECODE_FLAG_ALLOWNEGATIVE as c_int;
+    }
+}
@@ -0,0 +1,144 @@
+//! SMPTE timecodes, including NTSC drop-frame.
+pub mod flag;
+pub use self::flag::Flags;
+
+use std::ffi::{CStr, CString};
+use std::mem;
+use std::ptr;
+use std::str::from_utf8_unchecked;
+
+use ffi::*;
+use libc::{c_char, c_int};
+use {Error, Rational};
+
+#[derive(Copy, Clone)]
+pub struct Timecode(AVTimecode);
+
+impl Timecode {
+    #[inline(always)]
+    pub unsafe fn as_ptr(&self) -> *const AVTimecode {
+        &self.0
+    }
+
+    #[inline(always)]
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVTimecode {
+        &mut self.0
+    }
+}
+
+impl Timecode {
+    /// Create a timecode at `rate` whose first frame is `start`.
+    ///
+    /// Drop-frame is only supported at 30000/1001 and 60000/1001.
+    pub fn new<R: Into<Rational>>(rate: R, flags: Flags, start: i32) -> Result<Self, Error> {
+        unsafe {
+            let mut tc: AVTimecode = mem::zeroed();
+
+            match av_timecode_init(
+                &mut tc,
+                rate.into().into(),
+                flags.bits(),
+                start as c_int,
+                ptr::null_mut(),
+            ) {
+                0 => Ok(Timecode(tc)),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Parse a `HH:MM:SS:FF` timecode at `rate` as the first frame.
+    ///
+    /// A `;` or `.` before the frame field selects drop-frame.
+    pub fn parse<R: Into<Rational>>(rate: R, value: &str) -> Result<Self, Error> {
+        unsafe {
+            let value = CString::new(value).map_err(|_| Error::InvalidData)?;
+            let mut tc: AVTimecode = mem::zeroed();
+
+            match av_timecode_init_from_string(
+                &mut tc,
+                rate.into().into(),
+                value.as_ptr(),
+                ptr::null_mut(),
+            ) {
+                0 => Ok(Timecode(tc)),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Get the frame number of the first frame.
+    #[inline]
+    pub fn start(&self) -> i32 {
+        self.0.start as i32
+    }
+
+    #[inline]
+    pub fn flags(&self) -> Flags {
+        Flags::from_bits_truncate(self.0.flags as c_int)
+    }
+
+    #[inline]
+    pub fn rate(&self) -> Rational {
+        Rational::from(self.0.rate)
+    }
+
+    /// Get the rounded frame rate, e.g. 30 for 29.97.
+    #[inline]
+    pub fn fps(&self) -> u32 {
+        self.0.fps as u32
+    }
+
+    #[inline]
+    pub fn is_drop_frame(&self) -> bool {
+        self.flags().contains(Flags::DROP_FRAME)
+    }
+
+    /// Format the timecode of frame `frame`, counted from `start()`.
+    pub fn format(&self, frame: i32) -> String {
+        unsafe {
+            let mut buf = [0 as c_char; AV_TIMECODE_STR_SIZE as usize];
+
+            av_timecode_make_string(&self.0, buf.as_mut_ptr(), frame as c_int);
+
+            String::from(from_utf8_unchecked(CStr::from_ptr(buf.as_ptr()).to_bytes()))
+        }
+    }
+
+    /// Get the SMPTE 12M binary representation of the timecode of frame
+    /// `frame`, counted from `start()`.
+    pub fn smpte(&self, frame: i32) -> u32 {
+        unsafe { av_timecode_get_smpte_from_framenum(&self.0, frame as c_int) as u32 }
+    }
+}
+
+/// Convert a frame count at the nominal `fps` (30 or 60) into the frame
+/// number it maps to in drop-frame timecode.
+pub fn drop_frame_adjust(frame: i32, fps: u32) -> i32 {
+    unsafe { av_timecode_adjust_ntsc_framenum2(frame as c_int, fps as c_int) as i32 }
+}
+
+/// Check whether a timecode can use the given frame rate.
+pub fn check_rate<R: Into<Rational>>(rate: R) -> bool {
+    unsafe { av_timecode_check_frame_rate(rate.into().into()) == 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drop_frame() {
+        let tc = Timecode::new((30000, 1001), Flags::DROP_FRAME, 0).unwrap();
+
+        assert_eq!(tc.format(0), "00:00:00;00");
+        assert_eq!(tc.format(1799), "00:00:59;29");
+        assert_eq!(tc.format(1800), "00:01:00;02");
+        assert_eq!(tc.format(17982), "00:10:00;00");
+
+        let tc = Timecode::parse((30000, 1001), "00:10:00;00").unwrap();
+
+        assert!(tc.is_drop_frame());
+        assert_eq!(tc.start(), 17982);
+    }
+}